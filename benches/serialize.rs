@@ -0,0 +1,71 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::str::FromStr;
+
+use changelog::{MarkdownToken, Node};
+
+/// Generate a changelog of a few megabytes, with a lot of versions, sections and entries
+fn changelog(versions: usize) -> String {
+    let mut contents = String::from("# Changelog\n\nAll notable changes to this project will be documented in this file.\n\nThe format is based on Keep a Changelog.\n\n## [Unreleased]\n\n- Nothing yet!\n");
+
+    for version in (0..versions).rev() {
+        contents.push_str(&format!("\n## [1.{}.0] - 2022-01-01\n", version));
+
+        for section in ["Added", "Fixed", "Changed"] {
+            contents.push_str(&format!("\n### {}\n\n", section));
+
+            for entry in 0..20 {
+                contents.push_str(&format!(
+                    "- {} something in entry {} ([#{}](https://github.com/acme/widget/pull/{}))\n",
+                    section,
+                    entry,
+                    version * 100 + entry,
+                    version * 100 + entry
+                ));
+            }
+        }
+    }
+
+    contents.push('\n');
+    contents.push_str("[unreleased]: https://github.com/acme/widget/compare/v1.0.0...HEAD\n");
+    for version in (0..versions).rev() {
+        contents.push_str(&format!(
+            "[1.{0}.0]: https://github.com/acme/widget/releases/tag/v1.{0}.0\n",
+            version
+        ));
+    }
+
+    contents
+}
+
+fn serialize(c: &mut Criterion) {
+    let contents = changelog(500);
+    let root = Node::from_str(&contents).unwrap();
+
+    let mut group = c.benchmark_group("serialize");
+    group.sample_size(20);
+
+    group.bench_function("parse", |b| {
+        b.iter(|| Node::from_str(black_box(&contents)).unwrap())
+    });
+    group.bench_function("to_string", |b| b.iter(|| black_box(&root).to_string()));
+    group.bench_function("write", |b| {
+        let mut buffer: Vec<u8> = Vec::with_capacity(contents.len());
+        b.iter(|| {
+            use std::io::Write;
+            buffer.clear();
+            writeln!(buffer, "{}", black_box(&root)).unwrap();
+            black_box(&buffer);
+        })
+    });
+
+    group.finish();
+
+    // Make sure that the benchmark measures a realistic round trip
+    assert!(matches!(
+        root.children.first().and_then(|node| node.data.as_ref()),
+        Some(MarkdownToken::H1(_))
+    ));
+}
+
+criterion_group!(benches, serialize);
+criterion_main!(benches);
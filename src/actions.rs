@@ -0,0 +1,83 @@
+use color_eyre::eyre::{eyre, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Write an output of the current GitHub Actions step, so that later steps can use it as
+/// `${{ steps.<id>.outputs.<key> }}`.
+pub fn set_output(key: &str, value: &str) -> Result<()> {
+    let path = std::env::var_os("GITHUB_OUTPUT").ok_or_else(|| {
+        eyre!("The GITHUB_OUTPUT env var is not set, --github-output only works in GitHub Actions")
+    })?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", output_line(key, value))?;
+
+    Ok(())
+}
+
+/// Multiline values use a heredoc style delimiter that can't occur in the value itself
+fn output_line(key: &str, value: &str) -> String {
+    match value.contains('\n') {
+        true => {
+            let delimiter = format!("CHANGELOG_{}", Uuid::new_v4().to_simple());
+            format!("{}<<{}\n{}\n{}", key, delimiter, value, delimiter)
+        }
+        false => format!("{}={}", key, value),
+    }
+}
+
+/// Emit an annotation that shows up in the summary of the workflow run, e.g.: `::notice::...`
+pub fn annotate(level: &str, message: &str) {
+    println!(
+        "::{}::{}",
+        level,
+        message
+            .replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    );
+}
+
+/// Write the notes to the temp dir of the runner, e.g.: to use them as the body of a GitHub
+/// Release with `body_path`.
+pub fn write_notes(name: &str, notes: &str) -> Result<PathBuf> {
+    let dir = std::env::var_os("RUNNER_TEMP")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let file_name: String = name
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                true => c,
+                false => '-',
+            },
+        )
+        .collect();
+
+    let path = dir.join(format!(
+        "changelog-notes-{}.md",
+        file_name.trim_matches('-')
+    ));
+    fs::write(&path, notes.trim().to_string() + "\n")?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_write_multiline_outputs_with_a_delimiter() {
+        assert_eq!(output_line("version", "1.2.3"), "version=1.2.3");
+
+        let line = output_line("notes", "### Fixed\n\n- A bug");
+        let (key, rest) = line.split_once("<<").unwrap();
+        let (delimiter, rest) = rest.split_once('\n').unwrap();
+
+        assert_eq!(key, "notes");
+        assert_eq!(rest, format!("### Fixed\n\n- A bug\n{}", delimiter));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::markdown::plain;
+use crate::Node;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Discord rejects messages that are longer than this
+const DISCORD_LIMIT: usize = 2000;
+
+/// A webhook that release announcements are posted to, e.g.: a Slack or Discord channel
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// The url of the webhook, or the name of an env var that contains it, e.g.: `$SLACK_WEBHOOK`.
+    /// Webhook urls are secrets, so prefer the env var when the config is committed
+    pub url: String,
+
+    /// The kind of webhook, detected from the url when it is omitted
+    pub kind: Option<WebhookKind>,
+
+    /// The message, `{version}`, `{date}`, `{link}` and `{notes}` are replaced
+    #[serde(default = "default_template")]
+    pub template: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookKind {
+    /// Slack doesn't render markdown, the notes are sent as plain text
+    Slack,
+
+    /// Discord renders markdown, the notes are sent as-is
+    Discord,
+}
+
+fn default_template() -> String {
+    "Released {version} {link}\n\n{notes}".to_string()
+}
+
+/// The version that is announced
+pub struct Announcement<'a> {
+    pub version: &'a str,
+    pub date: Option<&'a str>,
+    pub link: Option<&'a str>,
+
+    /// The notes of the version, as markdown
+    pub notes: &'a str,
+}
+
+impl Webhook {
+    /// The url of the webhook, read from the env var when it starts with a `$`
+    pub fn resolve_url(&self) -> Result<String> {
+        match self.url.strip_prefix('$') {
+            Some(name) => std::env::var(name)
+                .map_err(|_| eyre!("The {} env var of the webhook is not set", name)),
+            None => Ok(self.url.clone()),
+        }
+    }
+
+    /// The kind of the webhook, Discord urls are recognized and everything else is treated like
+    /// Slack, which most chat tools are compatible with
+    pub fn kind(&self, url: &str) -> WebhookKind {
+        match self.kind {
+            Some(kind) => kind,
+            None if url.contains("discord.com/api/webhooks")
+                || url.contains("discordapp.com/api/webhooks") =>
+            {
+                WebhookKind::Discord
+            }
+            None => WebhookKind::Slack,
+        }
+    }
+
+    /// The JSON payload that is posted to the webhook
+    pub fn payload(&self, kind: WebhookKind, announcement: &Announcement) -> serde_json::Value {
+        let notes = match kind {
+            WebhookKind::Slack => announcement
+                .notes
+                .parse::<Node>()
+                .map(|node| plain::render(&node))
+                .unwrap_or_else(|_| announcement.notes.to_string()),
+            WebhookKind::Discord => announcement.notes.trim().to_string(),
+        };
+
+        let message = self
+            .template
+            .replace("{version}", announcement.version)
+            .replace("{date}", announcement.date.unwrap_or_default())
+            .replace("{link}", announcement.link.unwrap_or_default())
+            .replace("{notes}", &notes)
+            .trim()
+            .to_string();
+
+        match kind {
+            WebhookKind::Slack => json!({ "text": message }),
+            WebhookKind::Discord => json!({ "content": truncate(&message, DISCORD_LIMIT) }),
+        }
+    }
+
+    /// Post the announcement to the webhook
+    pub fn post(&self, announcement: &Announcement) -> Result<()> {
+        let url = self.resolve_url()?;
+        let payload = self.payload(self.kind(&url), announcement);
+
+        let response = reqwest::blocking::Client::new()
+            .post(&url)
+            .json(&payload)
+            .send()
+            .map_err(|e| eyre!("Couldn't reach the webhook: {}", e))?;
+
+        match response.status().is_success() {
+            true => Ok(()),
+            false => Err(eyre!(
+                "The webhook responded with {}: {}",
+                response.status(),
+                response.text().unwrap_or_default().trim()
+            )),
+        }
+    }
+}
+
+/// Cut the message at the limit (in chars), ending with an ellipsis
+fn truncate(message: &str, limit: usize) -> String {
+    match message.chars().count() > limit {
+        true => message.chars().take(limit - 1).collect::<String>() + "…",
+        false => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_render_the_payload_per_kind() {
+        let webhook = Webhook {
+            url: "https://discord.com/api/webhooks/1/abc".to_string(),
+            kind: None,
+            template: default_template(),
+        };
+        let announcement = Announcement {
+            version: "1.2.0",
+            date: Some("2022-01-01"),
+            link: Some("https://github.com/acme/widget/releases/tag/v1.2.0"),
+            notes: "### Fixed\n\n- Fix a **bug** ([#1](https://github.com/acme/widget/pull/1))\n",
+        };
+
+        let kind = webhook.kind(&webhook.url);
+        assert_eq!(kind, WebhookKind::Discord);
+        assert_eq!(
+            webhook.payload(kind, &announcement),
+            json!({ "content": "Released 1.2.0 https://github.com/acme/widget/releases/tag/v1.2.0\n\n### Fixed\n\n- Fix a **bug** ([#1](https://github.com/acme/widget/pull/1))" })
+        );
+        assert_eq!(
+            webhook.payload(WebhookKind::Slack, &announcement),
+            json!({ "text": "Released 1.2.0 https://github.com/acme/widget/releases/tag/v1.2.0\n\nFixed\n\n- Fix a bug (#1 (https://github.com/acme/widget/pull/1))" })
+        );
+    }
+}
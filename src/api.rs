@@ -0,0 +1,169 @@
+use changelog::changelog::Changelog;
+use changelog::config::Config;
+use changelog::github::{autolink::autolink, github_info::GitHubInfo, repo::Repo};
+use changelog::package::{PackageJSON, SemVer};
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A single command that can be sent to `changelog api --stdin`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum ApiCommand {
+    /// Add a new entry, either a manual `message` or a GitHub `link`
+    Add {
+        section: String,
+        message: Option<String>,
+        link: Option<String>,
+        scope: Option<String>,
+    },
+
+    /// Release a new version
+    Release {
+        version: String,
+        scope: Option<String>,
+    },
+}
+
+/// The JSON document sent on stdin, which can be a single command or a list of commands
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ApiDocument {
+    Many(Vec<ApiCommand>),
+    One(ApiCommand),
+}
+
+impl ApiDocument {
+    pub fn commands(self) -> Vec<ApiCommand> {
+        match self {
+            ApiDocument::Many(commands) => commands,
+            ApiDocument::One(command) => vec![command],
+        }
+    }
+}
+
+/// The result of a single `ApiCommand`, serialized as JSON on stdout
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum ApiResult {
+    Added { section: String, entry: String },
+    Duplicate { section: String, entry: String },
+    Released { version: String },
+    Error { message: String },
+}
+
+pub struct Api<'a> {
+    pwd: &'a Path,
+    filename: &'a str,
+    config: &'a Config,
+    packages: Vec<PackageJSON>,
+}
+
+impl<'a> Api<'a> {
+    pub fn new(
+        pwd: &'a Path,
+        filename: &'a str,
+        config: &'a Config,
+        packages: Vec<PackageJSON>,
+    ) -> Self {
+        Self {
+            pwd,
+            filename,
+            config,
+            packages,
+        }
+    }
+
+    pub fn execute(&self, command: ApiCommand) -> ApiResult {
+        let result = match command {
+            ApiCommand::Add {
+                section,
+                message,
+                link,
+                scope,
+            } => self.add(section, message, link, scope),
+            ApiCommand::Release { version, scope } => self.release(&version, scope),
+        };
+
+        result.unwrap_or_else(|e| ApiResult::Error {
+            message: e.to_string(),
+        })
+    }
+
+    fn package(&self, scope: Option<String>) -> Result<Option<&PackageJSON>> {
+        match scope {
+            Some(scope) => self
+                .packages
+                .iter()
+                .find(|package| package.name().eq(&scope))
+                .map(Some)
+                .ok_or_else(|| eyre!("Unknown scope: {}", scope)),
+            None => Ok(None),
+        }
+    }
+
+    fn add(
+        &self,
+        section: String,
+        message: Option<String>,
+        link: Option<String>,
+        scope: Option<String>,
+    ) -> Result<ApiResult> {
+        let package = self.package(scope)?;
+        let mut changelog = Changelog::new(
+            package.map(|package| package.pwd()).unwrap_or(self.pwd),
+            self.filename,
+            self.config,
+        )?;
+
+        let mut section = section;
+        let entry = match (message, link) {
+            (Some(message), None) => match (
+                self.config.autolink,
+                Repo::from_git_repo(&self.pwd.to_path_buf()),
+            ) {
+                (true, Ok(repo)) => autolink(&message, &repo),
+                _ => message,
+            },
+            (None, Some(link)) => {
+                let data = link.parse::<GitHubInfo>().map_err(|e| eyre!(e))?;
+                let (resolved, entry) = self.config.render_entry(&data, &link, "api");
+
+                if let Some(resolved) = resolved {
+                    section = resolved;
+                }
+
+                entry
+            }
+            _ => return Err(eyre!("Exactly one of `message` or `link` is required")),
+        };
+
+        if !changelog.add_list_item_to_section(&section, &entry)? {
+            return Ok(ApiResult::Duplicate { section, entry });
+        }
+
+        changelog.persist()?;
+
+        Ok(ApiResult::Added { section, entry })
+    }
+
+    fn release(&self, version: &str, scope: Option<String>) -> Result<ApiResult> {
+        let package = self.package(scope)?;
+        let (mut changelog, version) = match package {
+            Some(package) => (
+                Changelog::new(package.pwd(), self.filename, self.config)?,
+                package.clone().version_mut().change_to(version, None)?,
+            ),
+            None => (
+                Changelog::new(self.pwd, self.filename, self.config)?,
+                version.parse::<SemVer>()?,
+            ),
+        };
+
+        changelog.release(&version, package)?;
+
+        Ok(ApiResult::Released {
+            version: version.to_string(),
+        })
+    }
+}
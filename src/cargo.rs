@@ -0,0 +1,255 @@
+use crate::package::SemVer;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The relevant parts of a `Cargo.toml` file
+#[derive(Debug, Deserialize)]
+pub struct CargoToml {
+    pub package: Option<CargoPackage>,
+    pub workspace: Option<CargoWorkspace>,
+
+    /// Either a version requirement, or a table like `{ path = "../foo", version = "1.0" }`
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CargoPackage {
+    pub name: String,
+
+    /// Either a version, or `{ workspace = true }` to inherit the version of the workspace
+    version: Option<toml::Value>,
+
+    /// Either `false`, or the list of registries the crate may be published to
+    publish: Option<toml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CargoWorkspace {
+    #[serde(default)]
+    pub members: Vec<String>,
+
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    package: Option<CargoWorkspacePackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoWorkspacePackage {
+    version: Option<String>,
+}
+
+impl CargoToml {
+    pub fn from_directory(dir: &Path) -> Result<Self> {
+        let path = dir.join("Cargo.toml");
+        let contents = std::fs::read_to_string(&path)?;
+
+        toml::from_str(&contents).map_err(|e| eyre!("Invalid {}: {}", path.display(), e))
+    }
+
+    /// The version of the crate, inherited versions are resolved via the workspace in one of the
+    /// parent directories.
+    pub fn version(&self, dir: &Path) -> Option<String> {
+        match self
+            .package
+            .as_ref()
+            .and_then(|package| package.version.as_ref())
+        {
+            Some(toml::Value::String(version)) => Some(version.clone()),
+            Some(toml::Value::Table(_)) => dir
+                .ancestors()
+                .skip(1)
+                .filter_map(|dir| CargoToml::from_directory(dir).ok())
+                .find_map(|manifest| manifest.workspace_version()),
+            Some(_) => None,
+            None => self.workspace_version(),
+        }
+    }
+
+    /// The version that is shared by the members of the workspace
+    fn workspace_version(&self) -> Option<String> {
+        self.workspace.as_ref()?.package.as_ref()?.version.clone()
+    }
+
+    /// Whether the crate can't be published, via `publish = false`
+    pub fn is_private(&self) -> bool {
+        matches!(
+            self.package
+                .as_ref()
+                .and_then(|package| package.publish.as_ref()),
+            Some(toml::Value::Boolean(false))
+        )
+    }
+
+    /// The dependencies of the crate and their version requirements, `*` when there is none
+    pub fn dependencies(&self) -> BTreeMap<String, String> {
+        self.dependencies
+            .iter()
+            .map(|(name, dependency)| {
+                let requirement = match dependency {
+                    toml::Value::String(version) => Some(version.as_str()),
+                    toml::Value::Table(table) => table.get("version").and_then(|v| v.as_str()),
+                    _ => None,
+                };
+
+                (name.clone(), requirement.unwrap_or("*").to_string())
+            })
+            .collect()
+    }
+
+    fn inherits_version(&self) -> bool {
+        matches!(
+            self.package
+                .as_ref()
+                .and_then(|package| package.version.as_ref()),
+            Some(toml::Value::Table(_))
+        )
+    }
+}
+
+/// Update the version of the crate in the directory, and return the path of the `Cargo.toml` file
+/// that changed. Inherited versions are updated in the `[workspace.package]` table of the
+/// workspace instead.
+pub fn set_version(dir: &Path, version: &SemVer) -> Result<PathBuf> {
+    let manifest = CargoToml::from_directory(dir)?;
+
+    let (dir, table) = match manifest.inherits_version() {
+        true => (
+            dir.ancestors()
+                .skip(1)
+                .find(|dir| {
+                    CargoToml::from_directory(dir)
+                        .map(|manifest| manifest.workspace_version().is_some())
+                        .unwrap_or(false)
+                })
+                .ok_or_else(|| eyre!("Couldn't find the workspace of {}", dir.display()))?,
+            "workspace.package",
+        ),
+        false => (
+            dir,
+            match manifest.package.is_some() {
+                true => "package",
+                false => "workspace.package",
+            },
+        ),
+    };
+
+    let path = dir.join("Cargo.toml");
+    let contents = std::fs::read_to_string(&path)?;
+    let contents = replace_version(&contents, table, &version.to_string())
+        .ok_or_else(|| eyre!("Couldn't find the version in {}", path.display()))?;
+    std::fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+/// Replace the `version` in the given table, without touching the rest of the formatting
+fn replace_version(contents: &str, table: &str, version: &str) -> Option<String> {
+    let mut in_table = false;
+    let mut replaced = false;
+
+    let lines = contents
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_table = trimmed == format!("[{}]", table);
+                return line.to_string();
+            }
+
+            if !in_table || replaced {
+                return line.to_string();
+            }
+
+            let value = match trimmed
+                .strip_prefix("version")
+                .map(|rest| rest.trim_start())
+                .and_then(|rest| rest.strip_prefix('='))
+            {
+                Some(value) => value.trim_start(),
+                None => return line.to_string(),
+            };
+
+            let quote = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return line.to_string(),
+            };
+
+            let start = line.len() - line.trim_start().len() + trimmed.len() - value.len() + 1;
+            match line[start..].find(quote) {
+                Some(end) => {
+                    replaced = true;
+                    format!("{}{}{}", &line[..start], version, &line[start + end..])
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect::<String>();
+
+    replaced.then_some(lines)
+}
+
+/// The `Cargo.lock` file of the workspace the directory belongs to
+pub fn lockfile(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.exists())
+}
+
+/// Run `cargo check`, which refreshes the `Cargo.lock` file after a version bump
+pub fn check(dir: &Path) -> Result<()> {
+    let output = Command::new("cargo")
+        .args(["check", "--quiet"])
+        .current_dir(dir)
+        .output()?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(eyre!(
+            "cargo check failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_read_cargo_manifests() {
+        let manifest: CargoToml = toml::from_str(
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/internal\"]\n\n[workspace.package]\nversion = \"1.2.3\"\n",
+        )
+        .unwrap();
+
+        assert!(manifest.package.is_none());
+        assert_eq!(
+            manifest.workspace.as_ref().unwrap().members,
+            vec!["crates/*"]
+        );
+        assert_eq!(manifest.version(Path::new("/")), Some("1.2.3".to_string()));
+
+        let manifest: CargoToml =
+            toml::from_str("[package]\nname = \"widget\"\nversion = \"0.4.0\"\n").unwrap();
+        assert_eq!(manifest.version(Path::new("/")), Some("0.4.0".to_string()));
+    }
+
+    #[test]
+    fn it_should_replace_the_version_without_changing_the_formatting() {
+        let contents = "[package]\nname = \"widget\" # the name\nversion   =  \"0.4.0\" # the version\n\n[dependencies]\nserde = { version = \"1.0\" }\nversion = \"2\"\n";
+
+        assert_eq!(
+            replace_version(contents, "package", "0.5.0").unwrap(),
+            "[package]\nname = \"widget\" # the name\nversion   =  \"0.5.0\" # the version\n\n[dependencies]\nserde = { version = \"1.0\" }\nversion = \"2\"\n"
+        );
+        assert_eq!(
+            replace_version(contents, "workspace.package", "0.5.0"),
+            None
+        );
+    }
+}
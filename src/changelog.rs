@@ -1,46 +1,110 @@
-use crate::{git::Git, github::repo::Repo, rich_edit, MarkdownToken, Node, PackageJSON, SemVer};
-use chrono::prelude::*;
+use crate::{
+    config::{
+        Bom, Config, EmojiPlacement, LinkStyle, Position, SortOrder, BREAKING_PREFIX,
+        BREAKING_SECTION,
+    },
+    date::{normalize_heading, parse_date, DateLocale},
+    diff::{diff_entries, diff_lines, Diff, Entry},
+    git::Git,
+    github::{release::Release, repo::Repo},
+    markdown::html::{self, escape},
+    metadata, migrate, MarkdownToken, Node, PackageJSON, SemVer,
+};
+use chrono::NaiveDate;
 use color_eyre::eyre::{eyre, Result};
 use colored::*;
+use serde::{Deserialize, Serialize};
 use std::{
     fs,
+    io::{BufWriter, Write},
     path::{Path, PathBuf},
+    process::{Command, Stdio},
     str::FromStr,
 };
 
 const UNRELEASED_HEADING: &str = "Unreleased";
+const NOTHING_YET: &str = "Nothing yet!";
+const NO_SIGNIFICANT_CHANGES: &str = "No significant changes.";
+const BACKFILL_SECTION: &str = "Changed";
+/// The section of an umbrella release that lists the package versions that were released
+pub const UMBRELLA_SECTION: &str = "Packages";
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
 
 #[derive(Debug, Clone)]
 pub struct Changelog {
     pwd: PathBuf,
     file_path: PathBuf,
     root: Node,
+    config: Config,
+    bom: bool,
 }
 
 impl Changelog {
-    pub fn new(pwd: &Path, filename: &str) -> Result<Self> {
+    /// Read the changelog in the given directory. Packages in a monorepo can override parts of the
+    /// config, see `Config::for_package`.
+    pub fn new(pwd: &Path, filename: &str, config: &Config) -> Result<Self> {
+        let pwd = fs::canonicalize(pwd)?;
+        let config = &config.for_package(&pwd)?;
+        let file_path = pwd.join(config.filename.as_deref().unwrap_or(filename));
+        let (root, bom) = match std::fs::metadata(&file_path).is_ok() {
+            true => {
+                let (contents, bom) = read_contents(&file_path)?;
+                let mut root: Node = contents.parse()?;
+                strip_emoji(&mut root, config);
+                (root, bom)
+            }
+            false => (Node::empty(), false),
+        };
+
+        Ok(Changelog {
+            pwd,
+            file_path,
+            root,
+            config: config.clone(),
+            bom,
+        })
+    }
+
+    /// Same as `new`, but parses the given contents instead of reading the file from disk
+    pub fn from_contents(
+        pwd: &Path,
+        filename: &str,
+        contents: &str,
+        config: &Config,
+    ) -> Result<Self> {
         let pwd = fs::canonicalize(pwd)?;
         let file_path = pwd.join(filename);
-        let root = match std::fs::metadata(&file_path).is_ok() {
-            true => fs::read_to_string(&file_path)?.parse()?,
-            false => Node::empty(),
+        let (contents, bom) = match contents.strip_prefix('\u{feff}') {
+            Some(contents) => (contents, true),
+            None => (contents, false),
         };
+        let mut root: Node = contents.parse()?;
+        strip_emoji(&mut root, config);
 
         Ok(Changelog {
             pwd,
             file_path,
             root,
+            config: config.clone(),
+            bom,
         })
     }
 
-    pub fn file_path_str(&self) -> &str {
-        self.file_path.to_str().unwrap()
+    /// The config of the changelog, including the overrides of its package
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn file_path_str(&self) -> Result<&str> {
+        self.file_path
+            .to_str()
+            .ok_or_else(|| eyre!("Invalid path: {}", self.file_path.display()))
     }
 
     pub fn relative_path(&self) -> Result<String> {
         Ok(self
-            .file_path_str()
-            .replace(std::env::current_dir()?.to_str().unwrap(), "."))
+            .file_path_str()?
+            .replace(&std::env::current_dir()?.display().to_string(), "."))
     }
 
     pub fn unreleased_heading(&self, scope: Option<&PackageJSON>) -> String {
@@ -63,30 +127,166 @@ impl Changelog {
         if !Git::new(Some(&self.pwd))?.is_git_repo() {
             return Ok(format!(
                 "Not a git repository: {}",
-                self.pwd.to_str().unwrap().white().dimmed()
+                self.pwd.display().to_string().white().dimmed()
             ));
         }
 
-        let date = Local::now().format("%Y-%m-%d");
+        let git = Git::new(Some(&self.pwd))?;
         let repo = Repo::from_git_repo(&self.pwd)?;
 
+        // Start from the latest existing tag, so that the compare links are correct from day one
+        let mut tags: Vec<(SemVer, String)> = git
+            .tags()?
+            .into_iter()
+            .filter_map(|tag| {
+                let version = self
+                    .config
+                    .release
+                    .version_from_tag(None, &tag)?
+                    .parse()
+                    .ok()?;
+                Some((version, tag))
+            })
+            .collect();
+        tags.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let links = &self.config.links;
+        let (version, date, notes, tag, link) = match tags.as_slice() {
+            [(version, tag), rest @ ..] => (
+                version.to_string(),
+                git.date(tag)?,
+                "See the git history for the changes up to this version.",
+                tag.to_string(),
+                links.version(
+                    &repo,
+                    rest.first().map(|(_, previous)| previous.as_str()),
+                    tag,
+                ),
+            ),
+            [] => (
+                "0.1.0".to_string(),
+                self.config.timezone.today().format("%Y-%m-%d").to_string(),
+                "### Added\n\n- Everything!",
+                "v0.1.0".to_string(),
+                links.release(&repo, "v0.1.0"),
+            ),
+        };
+
         self.root = include_str!("./fixtures/changelog.md")
             .to_string()
-            .replace("<date>", &date.to_string())
-            .replace("<owner>", &repo.org)
-            .replace("<repo>", &repo.repo)
+            .replace("<version>", &version)
+            .replace("<date>", &date)
+            .replace("<notes>", notes)
+            .replace("<unreleased>", &links.compare(&repo, &tag, "HEAD"))
+            .replace("<link>", &link)
             .parse()?;
 
-        self.persist().map(|_| {
-            format!(
-                "Created new changelog file at: {}",
-                &self.relative_path().unwrap().white().dimmed()
-            )
-        })
+        self.persist()?;
+
+        Ok(format!(
+            "Created new changelog file at: {}",
+            &self.relative_path()?.white().dimmed()
+        ))
+    }
+
+    /// Leniently rewrite an existing (hand written) changelog so that it follows the structure we
+    /// expect: setext headings, version headings and section names are detected heuristically,
+    /// and the dates in the version headings are normalized to ISO dates. The result contains the
+    /// changed lines for review.
+    pub fn migrate(&mut self, locale: DateLocale) -> Result<String> {
+        if fs::metadata(&self.file_path).is_err() {
+            return Ok(format!(
+                "No changelog found at: {}",
+                &self.relative_path()?.white().dimmed()
+            ));
+        }
+
+        let (original, _) = read_contents(&self.file_path)?;
+        self.root = migrate::normalize(&original, &self.config.sections).parse()?;
+        strip_emoji(&mut self.root, &self.config);
+
+        let mut normalized = 0;
+        for node in self
+            .root
+            .filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+        {
+            if let Some(MarkdownToken::H2(heading)) = &node.data {
+                let updated = normalize_heading(heading, locale);
+
+                if !updated.eq(heading) {
+                    normalized += 1;
+                    node.rename_heading(&updated);
+                }
+            }
+        }
+
+        // Generate the missing reference links, when we know the GitHub repository
+        if Repo::from_git_repo(&self.pwd).is_ok() {
+            self.fix_links()?;
+        }
+
+        self.persist()?;
+
+        let (migrated, _) = read_contents(&self.file_path)?;
+        let diffs = diff_lines(&original, &migrated);
+
+        Ok(format!(
+            "Migrated changelog at: {} ({} dates normalized){}",
+            &self.relative_path()?.white().dimmed(),
+            normalized,
+            diffs
+                .iter()
+                .map(|diff| format!("\n{}", diff))
+                .collect::<String>()
+        ))
+    }
+
+    /// Format the changelog according to the config, e.g.: sort the entries within each section.
+    pub fn format(&mut self) -> Result<String> {
+        let config = self.config.clone();
+        for version in self
+            .root
+            .filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+        {
+            sort_sections(version, &config);
+        }
+
+        if let Some(order) = self.config.sort {
+            for section in self
+                .root
+                .filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::H3(_))))
+            {
+                for ul in section.filter_nodes_mut(|node| {
+                    matches!(&node.data, Some(MarkdownToken::UnorderedList))
+                }) {
+                    sort_entries(ul, order);
+                }
+            }
+        }
+
+        self.persist()?;
+
+        Ok(format!(
+            "Formatted changelog at: {}",
+            &self.relative_path()?.white().dimmed()
+        ))
     }
 
     pub fn persist(&self) -> Result<()> {
-        fs::write(&self.file_path, self.root.to_string() + "\n").map_err(|e| eyre!(e))
+        let bom = match self.config.bom {
+            Bom::Preserve => self.bom,
+            Bom::Always => true,
+            Bom::Never => false,
+        };
+
+        // Stream the AST straight into the file, without building the whole contents in memory
+        let mut file = BufWriter::new(fs::File::create(&self.file_path)?);
+        if bom {
+            file.write_all(UTF8_BOM)?;
+        }
+        writeln!(file, "{}", decorate_emoji(&self.root, &self.config))?;
+
+        file.flush().map_err(|e| eyre!(e))
     }
 
     fn find_latest_version(&self) -> Option<&str> {
@@ -105,13 +305,25 @@ impl Changelog {
         None
     }
 
+    /// The bullet marker that the changelog uses for its entries, e.g.: `-`, `*` or `+`
+    pub fn bullet_marker(&self) -> char {
+        self.root
+            .find_node(|node| matches!(&node.data, Some(MarkdownToken::ListItem(_, _, _))))
+            .and_then(|node| match &node.data {
+                Some(MarkdownToken::ListItem(_, _, marker)) => Some(*marker),
+                _ => None,
+            })
+            .unwrap_or('-')
+    }
+
     // TODO: This is horrible... refactor this!
     fn add_list_item_to_section_scope(
         &mut self,
         section_name: &str,
         item: String,
         scope: Option<&PackageJSON>,
-    ) {
+    ) -> Result<bool> {
+        let marker = self.bullet_marker();
         let unreleased_heading = self.unreleased_heading(scope);
         let unreleased = self.root.find_node_mut(|node| match &node.data {
             Some(MarkdownToken::H2(name)) => name.eq_ignore_ascii_case(&unreleased_heading),
@@ -139,12 +351,23 @@ impl Changelog {
                     .find_node_mut(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList)));
 
                 if let Some(ul) = ul {
-                    let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+                    if ul.children.iter().any(|li| is_duplicate_entry(li, &item)) {
+                        return Ok(false);
+                    }
 
-                    ul.add_child(li);
+                    let li = Node::from_token(MarkdownToken::ListItem(item, 0, marker));
+
+                    match self.config.position {
+                        Position::Top => ul.add_child_at(0, li),
+                        Position::Bottom => ul.add_child(li),
+                    }
+
+                    if let Some(order) = self.config.sort {
+                        sort_entries(ul, order);
+                    }
                 } else {
                     let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-                    let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+                    let li = Node::from_token(MarkdownToken::ListItem(item, 0, marker));
 
                     ul.add_child(li);
 
@@ -153,84 +376,103 @@ impl Changelog {
             } else {
                 let mut h3 = Node::from_token(MarkdownToken::H3(section_name.to_string()));
                 let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-                let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+                let li = Node::from_token(MarkdownToken::ListItem(item, 0, marker));
 
                 ul.add_child(li);
                 h3.add_child(ul);
 
-                unreleased.add_child(h3);
+                // Insert the section at the right position according to the canonical order
+                let rank = self.config.section_rank(section_name);
+                match unreleased
+                    .children
+                    .iter()
+                    .position(|node| match &node.data {
+                        Some(MarkdownToken::H3(name)) => self.config.section_rank(name) > rank,
+                        _ => false,
+                    }) {
+                    Some(idx) => unreleased.add_child_at(idx, h3),
+                    None => unreleased.add_child(h3),
+                }
             }
         } else {
             let unreleased_heading = self.unreleased_heading(scope);
             let mut section = Node::from_token(MarkdownToken::H2(unreleased_heading));
             let mut h3 = Node::from_token(MarkdownToken::H3(section_name.to_string()));
             let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-            let li = Node::from_token(MarkdownToken::ListItem(item, 0));
+            let li = Node::from_token(MarkdownToken::ListItem(item, 0, marker));
 
             ul.add_child(li);
             h3.add_child(ul);
             section.add_child(h3);
 
             // Insert "Unreleased" section
-            self.root
-                .children
-                .get_mut(0)
-                .expect("Couldn't find main heading, is your CHANGELOG.md formatted correctly?")
-                .add_child_at(2, section);
+            self.insert_version_section(section)?;
         }
+
+        Ok(true)
     }
 
-    pub fn edit(&self, name: &str, message: &str, scope: Option<&PackageJSON>) -> Option<String> {
-        let contents = &format!(
-            include_str!("./fixtures/edit_entry.txt"),
-            match scope {
-                Some(scope) => format!("# Current scope: '{}'\n\n", scope.name()),
-                None => "".to_string(),
-            },
-            message,
-            name.to_lowercase(),
-        );
-
-        match rich_edit(Some(contents)) {
-            Some(data) => {
-                let data = data.trim();
-                let data = data
-                    .lines()
-                    .map(|line| line.trim())
-                    .filter(|line| !line.is_empty())
-                    .filter(|line| !line.starts_with('#'))
-                    .map(|line| line.to_string())
-                    .collect::<Vec<String>>()
-                    .join("\n");
-
-                if data.is_empty() {
-                    None
-                } else {
-                    Some(data)
+    /// Insert a version section below the main heading and the introduction that follows it
+    fn insert_version_section(&mut self, section: Node) -> Result<()> {
+        let main_heading = self.root.children.get_mut(0).ok_or_else(|| {
+            eyre!("Couldn't find main heading, is your CHANGELOG.md formatted correctly?")
+        })?;
+        main_heading.add_child_at(main_heading.children.len().min(2), section);
+
+        Ok(())
+    }
+
+    /// Add a new entry to the section, returns `false` when an identical entry (or an entry linking
+    /// to the same PR, commit, ...) already exists in that section.
+    pub fn add_list_item_to_section(&mut self, section_name: &str, item: &str) -> Result<bool> {
+        self.add_list_item_to_section_scope(section_name, item.to_string(), None)
+    }
+
+    /// All released versions, newest first. Versions that are not valid semver are kept in file
+    /// order after the valid ones.
+    pub fn versions(&self, scope: Option<&PackageJSON>) -> Vec<String> {
+        let prefix = match scope {
+            Some(scope) if !scope.is_root() => format!("[{}@v", scope.name()),
+            _ => "[".to_string(),
+        };
+        let unreleased_heading = self.unreleased_heading(scope);
+
+        let mut versions: Vec<(Option<SemVer>, String)> = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H2(name))
+                    if !name.eq_ignore_ascii_case(&unreleased_heading) =>
+                {
+                    let version = name.strip_prefix(&prefix)?.split(']').next()?;
+                    Some((version.parse::<SemVer>().ok(), version.to_string()))
                 }
-            }
-            None => None,
-        }
+                _ => None,
+            })
+            .filter(|(_, version)| !version.to_lowercase().starts_with("unreleased"))
+            .collect();
+
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        versions.into_iter().map(|(_, version)| version).collect()
     }
 
-    pub fn add_list_item_to_section(
-        &mut self,
-        section_name: &str,
-        item: &str,
-        edit: bool,
-        scope: Option<&PackageJSON>,
-    ) {
-        self.add_list_item_to_section_scope(
-            section_name,
-            match edit {
-                true => match self.edit(section_name, item, scope) {
-                    Some(data) => data,
-                    None => item.to_string(),
-                },
-                false => item.to_string(),
+    /// Resolve version aliases like `latest`, `previous`, `latest-1` and `first` to an actual
+    /// version. Other names are returned as-is.
+    pub fn resolve_version(&self, name: &str, scope: Option<&PackageJSON>) -> Option<String> {
+        let alias = name.to_lowercase();
+        let offset = match alias.as_str() {
+            "latest" => 0,
+            "previous" => 1,
+            "first" => return self.versions(scope).pop(),
+            _ => match alias.strip_prefix("latest-").map(|n| n.parse::<usize>()) {
+                Some(Ok(offset)) => offset,
+                _ => return Some(name.to_string()),
             },
-            None,
-        );
+        };
+
+        self.versions(scope).into_iter().nth(offset)
     }
 
     pub fn get_contents_of_section_scope(
@@ -238,7 +480,27 @@ impl Changelog {
         name: Option<&String>,
         scope: Option<&PackageJSON>,
     ) -> Option<Node> {
-        let node = self.root.find_node(|node| {
+        let mut copy = self.find_version(name, scope)?.clone();
+        copy.data = None;
+
+        Some(copy)
+    }
+
+    /// The heading of the version that the notes are read from, e.g.: `[1.2.3] - 2024-05-01`
+    pub fn version_heading(&self, name: Option<&String>) -> Option<String> {
+        match &self.find_version(name, None)?.data {
+            Some(MarkdownToken::H2(heading)) => Some(heading.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The node of a version, the unreleased version when it has entries or the latest version
+    /// when there is no name
+    fn find_version(&self, name: Option<&String>, scope: Option<&PackageJSON>) -> Option<&Node> {
+        let resolved = name.and_then(|name| self.resolve_version(name, scope));
+        let name = resolved.as_ref().or(name);
+
+        self.root.find_node(|node| {
             if let Some(MarkdownToken::H2(section_name)) = &node.data {
                 match name {
                     Some(name) => {
@@ -271,16 +533,7 @@ impl Changelog {
             } else {
                 false
             }
-        });
-
-        if let Some(node) = node {
-            let mut copy = node.clone();
-            copy.data = None;
-
-            Some(copy)
-        } else {
-            None
-        }
+        })
     }
 
     pub fn get_contents_of_section(&self, name: &Option<String>) -> Option<Node> {
@@ -316,180 +569,2823 @@ impl Changelog {
         self.notes_scope(version, None)
     }
 
-    pub fn list(&self, amount: Amount) -> Result<String> {
-        let releases = self
+    /// The latest `amount` released versions, with their headings and reference links, e.g.: for
+    /// a "recent changes" page.
+    pub fn last_notes(&self, amount: usize) -> String {
+        let versions: Vec<Node> = self
             .root
-            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+            .filter_nodes(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => !name
+                    .to_lowercase()
+                    .starts_with(&format!("[{}", UNRELEASED_HEADING.to_lowercase())),
+                _ => false,
+            })
+            .into_iter()
+            .take(amount)
+            .cloned()
+            .map(|mut node| {
+                remove_nodes(&mut node, &|node| {
+                    matches!(&node.data, Some(MarkdownToken::Reference(_, _)))
+                });
+                node
+            })
+            .collect();
+
+        let names: Vec<&str> = versions
             .iter()
-            .filter_map(|node| node.data.as_ref())
-            .take(match amount {
-                Amount::All => std::usize::MAX,
-                Amount::Value(x) => x,
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H2(heading)) => heading_reference(heading),
+                _ => None,
             })
-            .map(|token| match token {
-                MarkdownToken::Reference(name, link) => format!("- {:15} {}", name, link),
-                _ => panic!("Expected a reference"),
+            .collect();
+
+        let references: Vec<String> = self
+            .root
+            .filter_nodes(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, _)) => {
+                    names.iter().any(|other| other.eq_ignore_ascii_case(name))
+                }
+                _ => false,
             })
-            .collect::<Vec<_>>()
-            .join("\n");
+            .iter()
+            .map(|node| node.to_string().trim().to_string())
+            .collect();
 
-        if releases.is_empty() {
-            Ok("There are no releases yet.".to_string())
-        } else {
-            Ok(releases)
-        }
+        versions
+            .iter()
+            .map(|node| node.to_string().trim().to_string())
+            .chain((!references.is_empty()).then(|| references.join("\n")))
+            .collect::<Vec<_>>()
+            .join("\n\n")
     }
 
-    pub fn release(&mut self, version: &SemVer, scope: Option<&PackageJSON>) -> Result<()> {
-        let date = Local::now().format("%Y-%m-%d");
-
-        let unreleased_heading = self.unreleased_heading(None);
+    /// Combine the notes of the versions after `from`, up to and including `to` (the latest
+    /// version by default). The entries are grouped per section and annotated with their version,
+    /// e.g.: to show what changed since the version a user is on.
+    pub fn notes_between(&self, from: &str, to: Option<&str>) -> Result<String> {
+        let versions = self.versions(None);
+        let resolve = |name: &str| -> Result<SemVer> {
+            self.resolve_version(name, None)
+                .filter(|version| versions.contains(version))
+                .and_then(|version| version.parse::<SemVer>().ok())
+                .ok_or_else(|| eyre!("Couldn't find version: {}", name.blue().bold()))
+        };
 
-        if let Some(unreleased) = self.root.find_node_mut(|node| {
-            if let Some(MarkdownToken::H2(name)) = &node.data {
-                name.eq_ignore_ascii_case(&unreleased_heading)
-            } else {
-                false
-            }
-        }) {
-            // Convert to the new version
-            unreleased.rename_heading(&format!("[{}] - {}", version, date));
+        let from = resolve(from)?;
+        let to = match to {
+            Some(to) => resolve(to)?,
+            None => resolve("latest")?,
+        };
 
-            // Insert new [Unreleased] section at the top
-            let mut new_unreleased =
-                Node::from_token(MarkdownToken::H2(unreleased_heading.clone()));
-            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
-            let li = Node::from_token(MarkdownToken::ListItem("Nothing yet!".to_string(), 0));
+        let marker = self.bullet_marker();
+        let mut sections: Vec<(Option<String>, Vec<String>)> = vec![];
 
-            ul.add_child(li);
-            new_unreleased.add_child(ul);
+        for version in self.export().versions {
+            match version.version.parse::<SemVer>() {
+                Ok(semver) if semver > from && semver <= to => {}
+                _ => continue,
+            }
 
-            self.root
-                .children
-                .get_mut(0)
-                .expect("Couldn't find main heading, is your CHANGELOG.md formatted correctly?")
-                .add_child_at(2, new_unreleased);
+            for section in version.sections {
+                let entries = section
+                    .entries
+                    .iter()
+                    .map(|entry| range_entry(entry, marker, 0, Some(&version.version)));
+                let key = section.name.as_deref().map(str::to_lowercase);
+
+                match sections
+                    .iter_mut()
+                    .find(|(name, _)| name.as_deref().map(str::to_lowercase) == key)
+                {
+                    Some((_, existing)) => existing.extend(entries),
+                    None => sections.push((section.name, entries.collect())),
+                }
+            }
+        }
 
-            // Update references at the bottom
-            let c = self.clone();
-            match c.find_latest_version() {
-                Some(old_version) => {
-                    if let Some(unreleased_reference) =
-                        self.root.find_node_mut(|node| match &node.data {
-                            Some(MarkdownToken::Reference(name, _)) => name.eq_ignore_ascii_case(
-                                &unreleased_heading[1..unreleased_heading.len() - 1],
-                            ),
-                            _ => false,
-                        })
-                    {
-                        if let Some(MarkdownToken::Reference(name, link)) =
-                            &unreleased_reference.data
-                        {
-                            let (updated_link, new_link) = (
-                                link.clone().replace(old_version, &version.to_string()),
-                                link.clone().replace(
-                                    "HEAD",
-                                    &match scope {
-                                        Some(scope) if !scope.is_root() => {
-                                            format!("{}@v{}", scope.name(), version)
-                                        }
-                                        _ => format!("v{}", version),
-                                    },
-                                ),
-                            );
+        // Entries outside of a section come first, followed by the sections in canonical order
+        sections.sort_by_key(|(name, _)| match name {
+            Some(name) => self.config.section_rank(name) + 1,
+            None => 0,
+        });
 
-                            // Update unreleased_reference
-                            unreleased_reference.data =
-                                Some(MarkdownToken::Reference(name.to_string(), updated_link));
+        Ok(sections
+            .iter()
+            .map(|(name, entries)| match name {
+                Some(name) => format!("### {}\n\n{}", name, entries.join("\n")),
+                None => entries.join("\n"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
 
-                            // Insert new version reference
-                            let new_version_reference = Node::from_token(MarkdownToken::Reference(
-                                version.to_string(),
-                                new_link,
-                            ));
+    /// Write the notes of the given version to the configured notes file, if any. This is useful
+    /// for other tools that want to embed the notes of the latest release.
+    pub fn write_notes_file(&self, version: &SemVer) -> Result<()> {
+        let notes_file = match &self.config.notes_file {
+            Some(notes_file) => self.pwd.join(notes_file),
+            None => return Ok(()),
+        };
 
-                            match self.root.children.iter().position(|node| match &node.data {
-                                Some(MarkdownToken::Reference(name, _)) => {
-                                    !name.to_lowercase().starts_with("unreleased")
-                                }
-                                _ => false,
-                            }) {
-                                Some(idx) => self.root.add_child_at(idx, new_version_reference),
-                                None => self.root.add_child(new_version_reference),
-                            }
-                        }
-                    }
-                }
-                None => {
-                    return Err(eyre!(
-                        "Couldn't find latest version, is your CHANGELOG.md formatted correctly?"
-                    ));
-                }
-            }
+        if let Some(parent) = notes_file.parent() {
+            fs::create_dir_all(parent)?;
         }
 
-        self.persist()
+        let notes = self.notes(Some(&version.to_string()))?;
+        fs::write(&notes_file, notes.trim().to_string() + "\n").map_err(|e| eyre!(e))
     }
-}
 
-#[derive(Debug, Clone, Copy)]
-pub enum Amount {
-    All,
-    Value(usize),
-}
+    /// Pipe the notes of the given version through the translation command of the locale
+    pub fn translate_notes(&self, version: Option<&String>, locale: &str) -> Result<String> {
+        let command = self.config.translations.get(locale).ok_or_else(|| {
+            eyre!(
+                "There is no translation command configured for locale: {}",
+                locale.blue().bold()
+            )
+        })?;
+        let notes = self.notes(version)?;
 
-impl FromStr for Amount {
-    type Err = String;
+        let (shell, flag) = match cfg!(windows) {
+            true => ("cmd", "/C"),
+            false => ("sh", "-c"),
+        };
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "all" => Amount::All,
-            _ => Amount::Value(s.parse::<usize>().map_err(|_| "Invalid amount")?),
-        })
+        let mut child = Command::new(shell)
+            .args([flag, command])
+            .current_dir(&self.pwd)
+            .env("CHANGELOG_LOCALE", locale)
+            .env(
+                "CHANGELOG_VERSION",
+                version.map(|v| v.as_str()).unwrap_or("latest"),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| eyre!("Couldn't open stdin of the translation command"))?
+            .write_all(notes.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "The translation command for {} failed: {}",
+                locale.blue().bold(),
+                command
+            ));
+        }
+
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Translate the notes of the released version for every configured locale and add them to
+    /// the localized changelogs, e.g.: `CHANGELOG.fr.md`. The canonical changelog is untouched.
+    pub fn write_translations(&self, version: &SemVer) -> Result<Vec<PathBuf>> {
+        let version = version.to_string();
+        let heading = self
+            .root
+            .find_node(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => heading_reference(name) == Some(&version),
+                _ => false,
+            })
+            .and_then(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => Some(name.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| eyre!("Couldn't find version: {}", version.blue().bold()))?;
+
+        let mut locales: Vec<&String> = self.config.translations.keys().collect();
+        locales.sort();
+
+        let mut paths = vec![];
+        for locale in locales {
+            let notes = self.translate_notes(Some(&version), locale)?;
+            let path = localized_path(&self.file_path, locale);
+            let section = format!("## {}\n\n{}\n", heading, notes);
+
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => match contents.find("\n## ") {
+                    Some(idx) => format!(
+                        "{}\n{}\n{}",
+                        &contents[..idx],
+                        section,
+                        &contents[idx + 1..]
+                    ),
+                    None => format!("{}\n\n{}", contents.trim_end(), section),
+                },
+                Err(_) => format!("# Changelog\n\n{}", section),
+            };
 
-    #[test]
-    fn it_should_find_the_latest_version() {
-        let c = Changelog {
-            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
-            pwd: PathBuf::default(),
-            file_path: PathBuf::default(),
-        };
+            fs::write(&path, contents)?;
+            paths.push(path);
+        }
 
-        let latest_version = c.find_latest_version();
-        assert_eq!(latest_version, Some("0.1.0"));
+        Ok(paths)
     }
 
-    #[test]
-    fn it_should_get_the_contents_of_a_section() {
-        let c = Changelog {
-            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
-            pwd: PathBuf::default(),
-            file_path: PathBuf::default(),
-        };
+    /// Whether the unreleased section of the scope contains real entries, not just the "Nothing
+    /// yet!" note. Changelogs of packages can use a plain `[Unreleased]` heading as well.
+    pub fn has_changes(&self, scope: Option<&PackageJSON>) -> bool {
+        let headings = [
+            self.unreleased_heading(scope),
+            self.unreleased_heading(None),
+        ];
+
+        self.root
+            .find_node(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => headings
+                    .iter()
+                    .any(|heading| name.eq_ignore_ascii_case(heading)),
+                _ => false,
+            })
+            .map(|unreleased| {
+                !unreleased
+                    .filter_nodes(|node| match &node.data {
+                        Some(
+                            MarkdownToken::ListItem(text, _, _)
+                            | MarkdownToken::OrderedListItem(text, _, _)
+                            | MarkdownToken::Paragraph(text),
+                        ) => text.trim() != NOTHING_YET,
+                        _ => false,
+                    })
+                    .is_empty()
+            })
+            .unwrap_or(false)
+    }
 
-        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
-        assert!(unreleased_section.is_some());
+    /// Whether the version exists and contains real entries, not just the "Nothing yet!" note
+    pub fn has_notes(&self, version: Option<&String>) -> bool {
+        match self.get_contents_of_section_scope(version, None) {
+            Some(node) => !node
+                .filter_nodes(|node| match &node.data {
+                    Some(
+                        MarkdownToken::ListItem(text, _, _)
+                        | MarkdownToken::OrderedListItem(text, _, _)
+                        | MarkdownToken::Paragraph(text),
+                    ) => text.trim() != NOTHING_YET,
+                    _ => false,
+                })
+                .is_empty(),
+            None => false,
+        }
+    }
 
-        let unreleased_section = unreleased_section.unwrap();
-        assert_eq!(
-            unreleased_section,
-            Node::from_str("- Nothing yet!").unwrap()
+    /// Compare the notes of a version with the body of its GitHub Release. Lines that only exist
+    /// in the changelog are reported as `Diff::Added`, lines that only exist in the GitHub Release
+    /// are reported as `Diff::Removed`.
+    pub fn verify_release(&self, version: &str, scope: Option<&PackageJSON>) -> Result<Vec<Diff>> {
+        let version = self
+            .resolve_version(version, scope)
+            .ok_or_else(|| eyre!("Couldn't resolve version: {}", version.blue().bold()))?;
+        let notes = self
+            .get_contents_of_section_scope(Some(&version.to_string()), scope)
+            .ok_or_else(|| eyre!("Couldn't find notes for version: {}", version.blue().bold()))?;
+
+        let tag = self.config.release.tag(
+            scope
+                .filter(|scope| !scope.is_root())
+                .map(|scope| scope.name()),
+            &version.to_string(),
         );
 
-        let first_release = c.get_contents_of_section(&Some("0.1.0".to_string()));
-        assert!(first_release.is_some());
+        let repo = Repo::from_git_repo(&self.pwd)?;
+        let release = Release::from_tag(&repo, &tag)
+            .map_err(|e| eyre!(e))?
+            .ok_or_else(|| {
+                eyre!(
+                    "Couldn't find a GitHub Release for tag: {}",
+                    tag.blue().bold()
+                )
+            })?;
+
+        Ok(diff_lines(&release.body, &notes.to_string()))
+    }
+
+    /// All the entries in the changelog, together with the version and section they belong to.
+    pub fn entries(&self) -> Vec<Entry> {
+        entries(&self.root)
+    }
+
+    /// The unreleased entries that are flagged for review
+    pub fn review_entries(&self) -> Vec<Entry> {
+        self.entries()
+            .into_iter()
+            .filter(|entry| {
+                entry
+                    .version
+                    .to_lowercase()
+                    .starts_with(&format!("[{}", UNRELEASED_HEADING.to_lowercase()))
+                    && metadata::get(&entry.text, "review").is_some()
+            })
+            .collect()
+    }
+
+    /// Approve the pending unreleased entries that contain the query, returns the approved entries
+    pub fn approve(&mut self, query: &str) -> Vec<String> {
+        let query = query.to_lowercase();
+        let mut approved = vec![];
+
+        for unreleased in self.root.filter_nodes_mut(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => name
+                .to_lowercase()
+                .starts_with(&format!("[{}", UNRELEASED_HEADING.to_lowercase())),
+            _ => false,
+        }) {
+            for item in unreleased.filter_nodes_mut(|node| {
+                matches!(
+                    &node.data,
+                    Some(
+                        MarkdownToken::ListItem(_, _, _) | MarkdownToken::OrderedListItem(_, _, _)
+                    )
+                )
+            }) {
+                if let Some(
+                    MarkdownToken::ListItem(text, _, _)
+                    | MarkdownToken::OrderedListItem(text, _, _),
+                ) = &mut item.data
+                {
+                    let (plain, _) = metadata::parse(text);
+                    if metadata::get(text, "review").as_deref() == Some("pending")
+                        && plain.to_lowercase().contains(&query)
+                    {
+                        *text = metadata::set(text, "review", "approved");
+                        approved.push(metadata::parse(text).0.to_string());
+                    }
+                }
+            }
+        }
+
+        approved
+    }
+
+    /// Replace the text of an entry, returns `false` when the entry doesn't exist.
+    pub fn edit_entry(&mut self, entry: &Entry, text: &str) -> bool {
+        let (list, idx) = match self.entry_position_mut(entry) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        match &mut list.children[idx].data {
+            Some(
+                MarkdownToken::ListItem(existing, _, _)
+                | MarkdownToken::OrderedListItem(existing, _, _),
+            ) => *existing = text.to_string(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Remove an entry (and its nested entries), returns `false` when the entry doesn't exist.
+    pub fn remove_entry(&mut self, entry: &Entry) -> bool {
+        if self.take_entry(entry).is_none() {
+            return false;
+        }
+
+        self.remove_empty_sections(&entry.version);
+
+        true
+    }
+
+    /// Move an entry (and its nested entries) to another section of the same version, the section
+    /// is created when it doesn't exist yet. Returns `false` when the entry doesn't exist.
+    pub fn move_entry(&mut self, entry: &Entry, section_name: &str) -> Result<bool> {
+        // Resolve the version of the target list before the entry is taken out of its section, so
+        // that the entry can't get lost
+        let version = self.root.find_node(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => name == &entry.version,
+            _ => false,
+        });
+        if version.is_none() {
+            return Ok(false);
+        }
+
+        let items = match self.take_entry(entry) {
+            Some(items) => items,
+            None => return Ok(false),
+        };
+
+        let (position, sort) = (self.config.position, self.config.sort);
+        let ul = self
+            .section_list_mut(&entry.version, section_name)
+            .ok_or_else(|| eyre!("Couldn't find the {} version of the entry", entry.version))?;
+
+        match position {
+            Position::Top => {
+                ul.children.splice(0..0, items);
+            }
+            Position::Bottom => ul.children.extend(items),
+        }
+
+        if let Some(order) = sort {
+            sort_entries(ul, order);
+        }
+
+        self.remove_empty_sections(&entry.version);
+
+        Ok(true)
+    }
+
+    /// Merge the changes that another branch made to the changelog into this one, e.g.: as a git
+    /// merge driver. `base` is the changelog that both branches started from. The entries that the
+    /// other branch added, moved or removed are added, moved or removed here as well, and new
+    /// entries end up after the same entry as on the other branch.
+    ///
+    /// Returns `false` when the changelogs can't be merged entry by entry, e.g.: when the branches
+    /// released different versions or both changed the text around the entries.
+    pub fn merge(&mut self, base: &Node, theirs: &Node) -> bool {
+        let (base_skeleton, their_skeleton) = (skeleton(base), skeleton(theirs));
+        if their_skeleton != base_skeleton && their_skeleton != skeleton(&self.root) {
+            return false;
+        }
+
+        let (base_entries, their_entries) = (entries(base), entries(theirs));
+
+        // Removed (or edited) on their side
+        for entry in &base_entries {
+            if their_entries.iter().any(|other| other.text == entry.text) {
+                continue;
+            }
+
+            if let Some((list, idx)) = self.entry_position_mut(entry) {
+                list.children.remove(idx);
+                self.remove_empty_sections(&entry.version);
+            }
+        }
+
+        // Moved to another section on their side
+        for entry in &their_entries {
+            if let Some(original) = base_entries.iter().find(|other| other.text == entry.text) {
+                if original != entry
+                    && original.version == entry.version
+                    && self.entries().contains(original)
+                    && self.move_entry(original, &entry.section).is_err()
+                {
+                    return false;
+                }
+            }
+        }
+
+        // Added (or edited) on their side
+        self.insert_entries(
+            &indented(theirs, their_entries.clone()),
+            |changelog, entry| {
+                !base_entries.iter().any(|other| other.text == entry.text)
+                    && !changelog
+                        .entries()
+                        .iter()
+                        .any(|other| other.text == entry.text)
+            },
+        )
+    }
+
+    /// Combine another changelog into this one, e.g.: the changelog of a package that moves into
+    /// this repository. Versions are aligned by their number: the entries and sections of a version
+    /// that exists in both are combined, and missing versions (and their reference links) are
+    /// added in the right order. When both changelogs disagree, e.g.: on the date of a version, this
+    /// changelog wins and the disagreement is reported as a conflict.
+    pub fn combine(&mut self, other: &Node) -> CombineReport {
+        let mut report = CombineReport::default();
+        let before = self.entries().len();
+
+        for version in other.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H2(_)))) {
+            let their_heading = match &version.data {
+                Some(MarkdownToken::H2(heading)) => heading.clone(),
+                _ => unreachable!(),
+            };
+            let name = heading_reference(&their_heading).unwrap_or(&their_heading);
+
+            let our_heading = self
+                .root
+                .find_node(|node| match &node.data {
+                    Some(MarkdownToken::H2(heading)) => heading_reference(heading)
+                        .unwrap_or(heading)
+                        .eq_ignore_ascii_case(name),
+                    _ => false,
+                })
+                .and_then(|node| match &node.data {
+                    Some(MarkdownToken::H2(heading)) => Some(heading.clone()),
+                    _ => None,
+                });
+
+            let our_heading = match our_heading {
+                Some(our_heading) => our_heading,
+                None => {
+                    self.insert_version(version.clone(), name);
+                    report.versions.push(name.to_string());
+                    continue;
+                }
+            };
+
+            let date = |heading: &str| {
+                heading
+                    .split_once(" - ")
+                    .map(|(_, date)| date.trim().to_string())
+            };
+            if let (Some(ours), Some(theirs)) = (date(&our_heading), date(&their_heading)) {
+                if ours != theirs {
+                    report.conflicts.push(format!(
+                        "{} is dated {} here, but {} in the other changelog",
+                        name, ours, theirs
+                    ));
+                }
+            }
+
+            let version_root = Node::new(None, vec![version.clone()]);
+            let their_entries: Vec<(Entry, usize)> =
+                indented(&version_root, entries(&version_root))
+                    .into_iter()
+                    .map(|(entry, indent)| {
+                        let version = our_heading.clone();
+                        (Entry { version, ..entry }, indent)
+                    })
+                    .collect();
+            self.insert_entries(&their_entries, |changelog, entry| {
+                !changelog
+                    .entries()
+                    .iter()
+                    .any(|other| other.version == entry.version && other.text == entry.text)
+            });
+        }
+
+        for reference in other.children.iter() {
+            let (name, link) = match &reference.data {
+                Some(MarkdownToken::Reference(name, link)) => (name, link),
+                _ => continue,
+            };
+
+            match self.root.children.iter().find_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(other, link)) if other.eq_ignore_ascii_case(name) => {
+                    Some(link)
+                }
+                _ => None,
+            }) {
+                // The unreleased link always compares with the latest version of each changelog
+                Some(_) if name.to_lowercase().starts_with("unreleased") => {}
+                Some(ours) if ours != link => report.conflicts.push(format!(
+                    "[{}] links to {} here, but to {} in the other changelog",
+                    name, ours, link
+                )),
+                Some(_) => {}
+                None => {
+                    let version = name.parse::<SemVer>().ok();
+                    let idx =
+                        self.root
+                            .children
+                            .iter()
+                            .position(|node| match (&node.data, &version) {
+                                (Some(MarkdownToken::Reference(other, _)), Some(version)) => other
+                                    .parse::<SemVer>()
+                                    .map(|other| other < *version)
+                                    .unwrap_or(false),
+                                _ => false,
+                            });
+
+                    match idx {
+                        Some(idx) => self.root.add_child_at(idx, reference.clone()),
+                        None => self.root.add_child(reference.clone()),
+                    }
+                }
+            }
+        }
+
+        report.entries = self.entries().len().saturating_sub(before);
+        report
+    }
+
+    /// Insert a version that doesn't exist yet, below the newer versions
+    fn insert_version(&mut self, version: Node, name: &str) {
+        let parent = match self
+            .root
+            .children
+            .iter()
+            .position(|node| matches!(&node.data, Some(MarkdownToken::H1(_))))
+        {
+            Some(idx) => &mut self.root.children[idx],
+            None => &mut self.root,
+        };
+
+        let semver = name.parse::<SemVer>().ok();
+        let unreleased = name.to_lowercase().starts_with("unreleased");
+        let idx = parent
+            .children
+            .iter()
+            .position(|node| match &node.data {
+                Some(MarkdownToken::H2(heading)) => {
+                    let other = heading_reference(heading).unwrap_or(heading);
+                    unreleased
+                        || match (&semver, other.parse::<SemVer>()) {
+                            (Some(semver), Ok(other)) => other < *semver,
+                            _ => false,
+                        }
+                }
+                Some(MarkdownToken::Reference(_, _)) => true,
+                _ => false,
+            })
+            .unwrap_or(parent.children.len());
+
+        parent.add_child_at(idx, version);
+    }
+
+    /// Insert entries (with their indentation) after the entry that precedes them, or at the start
+    /// of their section. Entries for which `is_new` returns `false` are not inserted, but the
+    /// entries that follow them are still inserted after them. Returns `false` when the version of
+    /// an entry doesn't exist.
+    fn insert_entries(
+        &mut self,
+        entries: &[(Entry, usize)],
+        is_new: impl Fn(&Self, &Entry) -> bool,
+    ) -> bool {
+        let marker = self.bullet_marker();
+        let mut previous: Option<&Entry> = None;
+
+        for (entry, indent) in entries {
+            if is_new(self, entry) {
+                let item =
+                    Node::from_token(MarkdownToken::ListItem(entry.text.clone(), *indent, marker));
+
+                let after = previous
+                    .filter(|previous| {
+                        previous.version == entry.version && previous.section == entry.section
+                    })
+                    .and_then(|previous| self.entry_position_mut(previous));
+
+                match after {
+                    Some((list, idx)) => list.add_child_at(idx + 1, item),
+                    None => match self.section_list_mut(&entry.version, &entry.section) {
+                        Some(list) => list.add_child_at(0, item),
+                        None => return false,
+                    },
+                }
+            }
+
+            previous = Some(entry);
+        }
+
+        // Versions that received entries don't need their "Nothing yet!" note anymore
+        for version in self
+            .root
+            .filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+        {
+            let has_entries = !version
+                .filter_nodes(|node| match &node.data {
+                    Some(MarkdownToken::ListItem(text, _, _)) => text.trim() != NOTHING_YET,
+                    _ => false,
+                })
+                .is_empty();
+
+            if has_entries {
+                remove_nodes(version, &|node| match &node.data {
+                    Some(MarkdownToken::ListItem(text, _, _)) => text.trim() == NOTHING_YET,
+                    _ => false,
+                });
+                remove_nodes(version, &|node| {
+                    matches!(&node.data, Some(MarkdownToken::UnorderedList))
+                        && node.children.is_empty()
+                });
+            }
+        }
+
+        true
+    }
+
+    /// The list of a section of a version, the section (and its list) are created when they don't
+    /// exist yet. Entries without a section live in a list directly below the version heading.
+    fn section_list_mut(&mut self, version_name: &str, section_name: &str) -> Option<&mut Node> {
+        let rank = self.config.section_rank(section_name);
+        let version = self.root.find_node_mut(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => name == version_name,
+            _ => false,
+        })?;
+
+        let parent = match section_name.is_empty() {
+            true => version,
+            false => {
+                let idx = match version.children.iter().position(|node| match &node.data {
+                    Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
+                    _ => false,
+                }) {
+                    Some(idx) => idx,
+                    None => {
+                        // Insert the section at the right position according to the canonical
+                        // order
+                        let idx = version
+                            .children
+                            .iter()
+                            .position(|node| match &node.data {
+                                Some(MarkdownToken::H3(name)) => {
+                                    self.config.section_rank(name) > rank
+                                }
+                                Some(MarkdownToken::Reference(_, _)) => true,
+                                _ => false,
+                            })
+                            .unwrap_or(version.children.len());
+                        version.add_child_at(
+                            idx,
+                            Node::from_token(MarkdownToken::H3(section_name.to_string())),
+                        );
+                        idx
+                    }
+                };
+
+                &mut version.children[idx]
+            }
+        };
+
+        let idx = match parent
+            .children
+            .iter()
+            .position(|node| matches!(&node.data, Some(MarkdownToken::UnorderedList)))
+        {
+            Some(idx) => idx,
+            None => {
+                // The list goes before the sections and reference links that follow it
+                let idx = parent
+                    .children
+                    .iter()
+                    .position(|node| {
+                        matches!(
+                            &node.data,
+                            Some(MarkdownToken::H3(_) | MarkdownToken::Reference(_, _))
+                        )
+                    })
+                    .unwrap_or(parent.children.len());
+                parent.add_child_at(idx, Node::from_token(MarkdownToken::UnorderedList));
+                idx
+            }
+        };
+
+        Some(&mut parent.children[idx])
+    }
+
+    /// The list that contains the entry, and the position of the entry in that list
+    fn entry_position_mut(&mut self, entry: &Entry) -> Option<(&mut Node, usize)> {
+        let version = self.root.find_node_mut(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => name == &entry.version,
+            _ => false,
+        })?;
+
+        let lists: Vec<&mut Node> = match entry.section.is_empty() {
+            true => version.children.iter_mut().collect(),
+            false => version
+                .children
+                .iter_mut()
+                .find(|node| match &node.data {
+                    Some(MarkdownToken::H3(name)) => name == &entry.section,
+                    _ => false,
+                })?
+                .children
+                .iter_mut()
+                .collect(),
+        };
+
+        lists.into_iter().find_map(|list| {
+            let idx = list.children.iter().position(|item| match &item.data {
+                Some(
+                    MarkdownToken::ListItem(text, _, _)
+                    | MarkdownToken::OrderedListItem(text, _, _),
+                ) => text == &entry.text,
+                _ => false,
+            })?;
+
+            Some((list, idx))
+        })
+    }
+
+    /// Remove an entry together with its nested entries from the changelog
+    fn take_entry(&mut self, entry: &Entry) -> Option<Vec<Node>> {
+        let marker = self.bullet_marker();
+        let (list, idx) = self.entry_position_mut(entry)?;
+        let indent = match &list.children[idx].data {
+            Some(
+                MarkdownToken::ListItem(_, indent, _)
+                | MarkdownToken::OrderedListItem(_, indent, _),
+            ) => *indent,
+            _ => 0,
+        };
+
+        let nested = list.children[idx + 1..]
+            .iter()
+            .take_while(|item| match &item.data {
+                Some(
+                    MarkdownToken::ListItem(_, other, _)
+                    | MarkdownToken::OrderedListItem(_, other, _),
+                ) => *other > indent,
+                _ => false,
+            })
+            .count();
+
+        // Nested entries become top level entries when the parent moves, and the entries of an
+        // ordered list become bullets, they end up in the unordered list of another section
+        let items = list
+            .children
+            .drain(idx..=idx + nested)
+            .map(|item| match item.data {
+                Some(MarkdownToken::ListItem(text, other, item_marker)) => Node::new(
+                    Some(MarkdownToken::ListItem(text, other - indent, item_marker)),
+                    item.children,
+                ),
+                Some(MarkdownToken::OrderedListItem(text, other, _)) => Node::new(
+                    Some(MarkdownToken::ListItem(text, other - indent, marker)),
+                    item.children,
+                ),
+                _ => item,
+            })
+            .collect();
+
+        Some(items)
+    }
+
+    /// Remove the lists and sections that became empty, an empty unreleased section gets its
+    /// "Nothing yet!" note back.
+    fn remove_empty_sections(&mut self, version_name: &str) {
+        let marker = self.bullet_marker();
+        let unreleased = heading_reference(version_name)
+            .map(|name| name.eq_ignore_ascii_case(UNRELEASED_HEADING))
+            .unwrap_or(false);
+
+        let version = match self.root.find_node_mut(|node| match &node.data {
+            Some(MarkdownToken::H2(name)) => name == version_name,
+            _ => false,
+        }) {
+            Some(version) => version,
+            None => return,
+        };
+
+        let is_empty_list = |node: &Node| {
+            matches!(
+                &node.data,
+                Some(MarkdownToken::UnorderedList | MarkdownToken::OrderedList)
+            ) && node.children.is_empty()
+        };
+
+        version.children.retain(|node| !is_empty_list(node));
+        for section in &mut version.children {
+            section.children.retain(|node| !is_empty_list(node));
+        }
+        version.children.retain(|node| {
+            !matches!(&node.data, Some(MarkdownToken::H3(_)))
+                || node
+                    .children
+                    .iter()
+                    .any(|child| !matches!(&child.data, Some(MarkdownToken::BlankLine)))
+        });
+
+        let has_entries = version.children.iter().any(|node| {
+            matches!(
+                &node.data,
+                Some(
+                    MarkdownToken::H3(_)
+                        | MarkdownToken::UnorderedList
+                        | MarkdownToken::OrderedList
+                        | MarkdownToken::Paragraph(_)
+                )
+            )
+        });
+
+        if unreleased && !has_entries {
+            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+            ul.add_child(Node::from_token(MarkdownToken::ListItem(
+                NOTHING_YET.to_string(),
+                0,
+                marker,
+            )));
+            version.add_child_at(0, ul);
+        }
+    }
+
+    /// The commits that are not referenced by any unreleased entry, either by the number of their
+    /// pull request (e.g.: `Fix a bug (#123)`) or by their hash.
+    pub fn unlogged(&self, commits: &[(String, String)]) -> Vec<(String, String)> {
+        let texts: Vec<String> = self
+            .entries()
+            .into_iter()
+            .filter(|entry| {
+                entry
+                    .version
+                    .to_lowercase()
+                    .starts_with(&format!("[{}", UNRELEASED_HEADING.to_lowercase()))
+            })
+            .map(|entry| entry.text)
+            .collect();
+
+        let numbers: Vec<String> = texts
+            .iter()
+            .flat_map(|text| referenced_numbers(text))
+            .collect();
+
+        commits
+            .iter()
+            .filter(|(hash, subject)| {
+                let by_number = referenced_numbers(subject)
+                    .iter()
+                    .any(|number| numbers.contains(number));
+                let by_hash = texts.iter().any(|text| {
+                    text.split(|c: char| !c.is_ascii_hexdigit())
+                        .any(|part| part.len() >= 7 && hash.starts_with(part))
+                });
+
+                !by_number && !by_hash
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Compare the entries of the changelog with the entries of the changelog at the given git
+    /// ref, e.g.: `HEAD` or `origin/main`.
+    pub fn diff_with_ref(&self, git_ref: &str) -> Result<Vec<Diff>> {
+        Ok(diff_entries(
+            &self.entries_at_ref(git_ref)?,
+            &self.entries(),
+        ))
+    }
+
+    /// The unreleased entries that don't exist in the changelog at the given git ref, e.g.: the
+    /// entries that a pull request added compared to `origin/main`. Entries that only moved to
+    /// another section are not considered new.
+    pub fn new_entries_since(&self, git_ref: &str) -> Result<Vec<Entry>> {
+        Ok(new_unreleased_entries(
+            &self.entries_at_ref(git_ref)?,
+            &self.entries(),
+        ))
+    }
+
+    fn entries_at_ref(&self, git_ref: &str) -> Result<Vec<Entry>> {
+        // The filename can point into a subdirectory, e.g.: `docs/CHANGELOG.md`
+        let path = self
+            .file_path
+            .strip_prefix(&self.pwd)
+            .unwrap_or(&self.file_path);
+        let path = path
+            .to_str()
+            .ok_or_else(|| eyre!("Invalid path: {}", path.display()))?;
+        let base: Node = Git::new(Some(&self.pwd))?.show(git_ref, path)?.parse()?;
+
+        Ok(entries(&base))
+    }
+
+    pub fn list(&self, amount: Amount) -> Result<String> {
+        let mut references: Vec<(&String, &String)> = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, link)) => Some((name, link)),
+                _ => None,
+            })
+            .collect();
+
+        // Unreleased first, followed by the versions from newest to oldest. Names that are not
+        // valid semver versions go last, in file order.
+        references.sort_by_key(|(name, _)| {
+            match (
+                name.to_lowercase().starts_with("unreleased"),
+                name.parse::<SemVer>(),
+            ) {
+                (true, _) => (0, None),
+                (false, Ok(version)) => (1, Some(std::cmp::Reverse(version))),
+                (false, Err(_)) => (2, None),
+            }
+        });
+
+        let releases = references
+            .iter()
+            .take(match amount {
+                Amount::All => usize::MAX,
+                Amount::Value(x) => x,
+            })
+            .map(|(name, link)| format!("- {:15} {}", name, link))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if releases.is_empty() {
+            Ok("There are no releases yet.".to_string())
+        } else {
+            Ok(releases)
+        }
+    }
+
+    /// Render the released versions as an Atom feed, newest first. The `link` is the homepage of
+    /// the project (e.g.: the GitHub repository), it is used as the id of the feed.
+    pub fn atom(&self, link: Option<&str>) -> String {
+        let title = self
+            .root
+            .find_node(|node| matches!(&node.data, Some(MarkdownToken::H1(_))))
+            .and_then(|node| match &node.data {
+                Some(MarkdownToken::H1(title)) => Some(title.as_str()),
+                _ => None,
+            })
+            .unwrap_or("Changelog");
+
+        let mut entries: Vec<(SemVer, NaiveDate, String)> = self
+            .versions(None)
+            .iter()
+            .filter_map(|version| {
+                let node = self.get_contents_of_section(&Some(version.to_string()))?;
+                let heading = self.root.find_node(|node| {
+                    matches!(&node.data, Some(MarkdownToken::H2(heading)) if heading_reference(heading) == Some(version))
+                })?;
+                let date = match &heading.data {
+                    Some(MarkdownToken::H2(heading)) => heading_date(heading),
+                    _ => None,
+                }?;
+
+                Some((version.parse().ok()?, date, html::render(&node)))
+            })
+            .collect();
+        entries.sort_by(|(a, _, _), (b, _, _)| b.cmp(a));
+
+        let id = link
+            .map(|link| link.to_string())
+            .unwrap_or_else(|| format!("urn:changelog:{}", escape(title)));
+        let updated = entries
+            .iter()
+            .map(|(_, date, _)| *date)
+            .max()
+            .unwrap_or_else(|| self.config.timezone.today());
+
+        let mut feed = vec![
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>".to_string(),
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\">".to_string(),
+            format!("  <title>{}</title>", escape(title)),
+            format!("  <id>{}</id>", escape(&id)),
+            format!(
+                "  <updated>{}T00:00:00Z</updated>",
+                updated.format("%Y-%m-%d")
+            ),
+        ];
+        if let Some(link) = link {
+            feed.push(format!("  <link href=\"{}\"/>", escape(link)));
+        }
+
+        for (version, date, content) in entries {
+            let version = version.to_string();
+            let reference = self.root.children.iter().find_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, link)) if *name == version => Some(link),
+                _ => None,
+            });
+
+            feed.push("  <entry>".to_string());
+            feed.push(format!(
+                "    <title>{} - {}</title>",
+                escape(&version),
+                date.format("%Y-%m-%d")
+            ));
+            feed.push(format!(
+                "    <id>{}</id>",
+                escape(&match reference {
+                    Some(reference) => reference.to_string(),
+                    None => format!("{}#{}", id, version),
+                })
+            ));
+            if let Some(reference) = reference {
+                feed.push(format!("    <link href=\"{}\"/>", escape(reference)));
+            }
+            feed.push(format!(
+                "    <updated>{}T00:00:00Z</updated>",
+                date.format("%Y-%m-%d")
+            ));
+            feed.push(format!(
+                "    <content type=\"html\">{}</content>",
+                escape(content.trim())
+            ));
+            feed.push("  </entry>".to_string());
+        }
+
+        feed.push("</feed>".to_string());
+        feed.join("\n") + "\n"
+    }
+
+    /// The whole changelog as plain data, so that other tools don't have to parse the markdown
+    pub fn export(&self) -> Export {
+        let references: Vec<ExportReference> = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, link)) => Some(ExportReference {
+                    name: name.to_string(),
+                    link: link.to_string(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        let versions = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H2(heading)) => Some((heading, node)),
+                _ => None,
+            })
+            .map(|(heading, node)| {
+                let name = heading_reference(heading).unwrap_or(heading);
+                let mut sections: Vec<ExportSection> = vec![];
+                let mut notes = vec![];
+
+                for child in &node.children {
+                    match &child.data {
+                        Some(MarkdownToken::H3(section)) => sections.push(ExportSection {
+                            name: Some(section.to_string()),
+                            entries: child.children.iter().flat_map(export_entries).collect(),
+                        }),
+                        Some(MarkdownToken::Paragraph(paragraph)) => {
+                            notes.push(paragraph.trim().to_string())
+                        }
+                        _ => {
+                            let entries = export_entries(child);
+                            if !entries.is_empty() {
+                                sections.push(ExportSection {
+                                    name: None,
+                                    entries,
+                                });
+                            }
+                        }
+                    }
+                }
+
+                ExportVersion {
+                    version: name.to_string(),
+                    date: heading_date(heading).map(|date| date.format("%Y-%m-%d").to_string()),
+                    link: references
+                        .iter()
+                        .find(|reference| reference.name.eq_ignore_ascii_case(name))
+                        .map(|reference| reference.link.to_string()),
+                    notes,
+                    sections,
+                }
+            })
+            .collect();
+
+        Export {
+            title: self
+                .root
+                .find_node(|node| matches!(&node.data, Some(MarkdownToken::H1(_))))
+                .and_then(|node| match &node.data {
+                    Some(MarkdownToken::H1(title)) => Some(title.to_string()),
+                    _ => None,
+                }),
+            versions,
+            references,
+        }
+    }
+
+    /// Import the data of an export: the entries of the unreleased version are added to the
+    /// unreleased section, and missing versions are added as a whole. Versions that already exist
+    /// are left alone. Returns the amount of entries and versions that were added.
+    pub fn import(&mut self, export: Export) -> Result<(usize, usize)> {
+        let unreleased_heading = self.unreleased_heading(None);
+        let unreleased = heading_reference(&unreleased_heading).unwrap_or(&unreleased_heading);
+        let (mut entries, mut versions) = (0, 0);
+
+        for version in export.versions {
+            let sections: Vec<(String, Vec<String>)> = version
+                .sections
+                .into_iter()
+                .map(|section| {
+                    (
+                        section.name.unwrap_or_else(|| BACKFILL_SECTION.to_string()),
+                        section
+                            .entries
+                            .into_iter()
+                            .map(|entry| entry.text)
+                            .collect(),
+                    )
+                })
+                .collect();
+
+            if version.version.eq_ignore_ascii_case(unreleased) {
+                for (section, messages) in sections {
+                    for message in messages {
+                        if self.add_list_item_to_section_scope(&section, message, None)? {
+                            entries += 1;
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let semver = match version.version.parse::<SemVer>() {
+                Ok(semver) => semver,
+                Err(_) => continue,
+            };
+            let date = version
+                .date
+                .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok())
+                .unwrap_or_else(|| self.config.timezone.today());
+
+            if self.add_version(&semver, &date, sections) {
+                versions += 1;
+            }
+        }
+
+        Ok((entries, versions))
+    }
+
+    /// Add the entries to the unreleased section, duplicates are skipped. Returns the amount of
+    /// entries that were added.
+    pub fn import_entries(&mut self, entries: Vec<ImportEntry>) -> Result<usize> {
+        let mut added = 0;
+        for entry in entries {
+            if self.add_list_item_to_section_scope(&entry.section, entry.message, None)? {
+                added += 1;
+            }
+        }
+
+        Ok(added)
+    }
+
+    /// Find reference definitions that are not used anywhere (orphans), and version headings
+    /// without a reference definition (missing).
+    pub fn link_issues(&self) -> LinkIssues {
+        let headings: Vec<&str> = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H2(_))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H2(heading)) => heading_reference(heading),
+                _ => None,
+            })
+            .collect();
+        let texts: Vec<String> = self
+            .root
+            .filter_nodes(|node| {
+                matches!(
+                    &node.data,
+                    Some(
+                        MarkdownToken::H3(_)
+                            | MarkdownToken::Paragraph(_)
+                            | MarkdownToken::ListItem(_, _, _)
+                            | MarkdownToken::OrderedListItem(_, _, _)
+                    )
+                )
+            })
+            .iter()
+            .filter_map(|node| {
+                node.data
+                    .as_ref()
+                    .map(|token| token.to_string().to_lowercase())
+            })
+            .collect();
+        let references: Vec<(&String, &String)> = self
+            .root
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::Reference(_, _))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::Reference(name, link)) => Some((name, link)),
+                _ => None,
+            })
+            .collect();
+
+        LinkIssues {
+            orphans: references
+                .iter()
+                .filter(|(name, _)| {
+                    !headings
+                        .iter()
+                        .any(|heading| heading.eq_ignore_ascii_case(name))
+                        && !texts
+                            .iter()
+                            .any(|text| text.contains(&format!("[{}]", name.to_lowercase())))
+                })
+                .map(|(name, link)| (name.to_string(), link.to_string()))
+                .collect(),
+            missing: headings
+                .iter()
+                .filter(|heading| {
+                    !references
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case(heading))
+                })
+                .map(|heading| heading.to_string())
+                .collect(),
+        }
+    }
+
+    /// Add a version that was released in the past (e.g.: from a git tag), with the given commit
+    /// subjects as its entries. The version is inserted in order, versions that already exist are
+    /// left alone. Returns whether the version was added.
+    pub fn backfill(&mut self, version: &SemVer, date: &NaiveDate, subjects: &[String]) -> bool {
+        // Group the subjects per section, the conventional commit prefix picks the section
+        let mut sections: Vec<(String, Vec<String>)> = vec![];
+        for subject in subjects {
+            let (section, entry) = self
+                .config
+                .conventional_commits
+                .resolve(subject)
+                .unwrap_or_else(|| (BACKFILL_SECTION.to_string(), subject.to_string()));
+
+            match sections.iter_mut().find(|(name, _)| *name == section) {
+                Some((_, entries)) => entries.push(entry),
+                None => sections.push((section, vec![entry])),
+            }
+        }
+
+        self.add_version(version, date, sections)
+    }
+
+    /// Add a version from the notes of a GitHub Release, versions that already exist are left
+    /// alone. Returns whether the version was added.
+    pub fn import_release(&mut self, version: &SemVer, date: &NaiveDate, body: &str) -> bool {
+        let sections = migrate::release_sections(body, &self.config.sections, BACKFILL_SECTION);
+
+        self.add_version(version, date, sections)
+    }
+
+    /// Insert a version with the given entries per section, in version order
+    fn add_version(
+        &mut self,
+        version: &SemVer,
+        date: &NaiveDate,
+        mut sections: Vec<(String, Vec<String>)>,
+    ) -> bool {
+        if self
+            .versions(None)
+            .iter()
+            .any(|other| other.eq(&version.to_string()))
+        {
+            return false;
+        }
+
+        let marker = self.bullet_marker();
+        let mut node = Node::from_token(MarkdownToken::H2(
+            self.config.release.heading(&version.to_string(), date),
+        ));
+
+        sections.sort_by_key(|(name, _)| self.config.section_rank(name));
+
+        for (section, entries) in sections {
+            let mut h3 = Node::from_token(MarkdownToken::H3(section));
+            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+            for entry in entries {
+                ul.add_child(Node::from_token(MarkdownToken::ListItem(entry, 0, marker)));
+            }
+            h3.add_child(ul);
+            node.add_child(h3);
+        }
+
+        if node.children.is_empty() {
+            node.add_child(Node::from_token(MarkdownToken::Paragraph(
+                NO_SIGNIFICANT_CHANGES.to_string(),
+            )));
+        }
+
+        let h1 = match self.root.children.get_mut(0) {
+            Some(h1) => h1,
+            None => return false,
+        };
+
+        // Versions are ordered from newest to oldest
+        let position = h1.children.iter().position(|child| match &child.data {
+            Some(MarkdownToken::H2(heading)) => heading
+                .strip_prefix('[')
+                .and_then(|heading| heading.split_once(']'))
+                .and_then(|(other, _)| other.parse::<SemVer>().ok())
+                .map(|other| other < *version)
+                .unwrap_or(false),
+            _ => false,
+        });
+
+        match position {
+            Some(idx) => h1.add_child_at(idx, node),
+            None => h1.add_child(node),
+        }
+
+        // The unreleased comparison starts at the latest version, drop it when this version is the
+        // newest one so that it is generated again.
+        let is_latest = self
+            .versions(None)
+            .iter()
+            .filter_map(|other| other.parse::<SemVer>().ok())
+            .all(|other| other <= *version);
+        if is_latest {
+            remove_nodes(
+                &mut self.root,
+                &|node| matches!(&node.data, Some(MarkdownToken::Reference(name, _)) if name.eq_ignore_ascii_case("unreleased")),
+            );
+        }
+
+        true
+    }
+
+    /// Remove the orphaned reference definitions, and generate the missing ones. Returns the
+    /// issues that were fixed.
+    pub fn fix_links(&mut self) -> Result<LinkIssues> {
+        let issues = self.link_issues();
+
+        remove_nodes(&mut self.root, &|node| match &node.data {
+            Some(MarkdownToken::Reference(name, _)) => {
+                issues.orphans.iter().any(|(orphan, _)| orphan == name)
+            }
+            _ => false,
+        });
+
+        if issues.missing.is_empty() {
+            return Ok(issues);
+        }
+
+        let repo = Repo::from_git_repo(&self.pwd)?;
+        let versions: Vec<SemVer> = self
+            .versions(None)
+            .iter()
+            .filter_map(|version| version.parse().ok())
+            .collect();
+
+        for name in &issues.missing {
+            let version = name.parse::<SemVer>().ok();
+            let tag = |version: &SemVer| self.config.release.tag(None, &version.to_string());
+            let links = &self.config.links;
+            let link = match &version {
+                Some(version) => links.version(
+                    &repo,
+                    versions
+                        .iter()
+                        .find(|other| *other < version)
+                        .map(tag)
+                        .as_deref(),
+                    &tag(version),
+                ),
+                None => match versions.first() {
+                    Some(latest) => links.compare(&repo, &tag(latest), "HEAD"),
+                    None => links.commits(&repo),
+                },
+            };
+
+            // Keep the references ordered: unreleased first, followed by the versions from newest
+            // to oldest.
+            let position = self.root.children.iter().position(|node| match &node.data {
+                Some(MarkdownToken::Reference(other, _)) => match &version {
+                    Some(version) => other
+                        .parse::<SemVer>()
+                        .map(|other| other < *version)
+                        .unwrap_or(false),
+                    None => true,
+                },
+                _ => false,
+            });
+
+            let reference = Node::from_token(MarkdownToken::Reference(name.to_lowercase(), link));
+            match position {
+                Some(idx) => self.root.add_child_at(idx, reference),
+                None => self.root.add_child(reference),
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Whether the unreleased section contains the given section
+    fn has_unreleased_section(&self, section_name: &str) -> bool {
+        let unreleased_heading = self.unreleased_heading(None);
+
+        self.root
+            .find_node(|node| match &node.data {
+                Some(MarkdownToken::H2(name)) => name.eq_ignore_ascii_case(&unreleased_heading),
+                _ => false,
+            })
+            .and_then(|unreleased| {
+                unreleased.find_node(|node| match &node.data {
+                    Some(MarkdownToken::H3(name)) => name.eq_ignore_ascii_case(section_name),
+                    _ => false,
+                })
+            })
+            .is_some()
+    }
+
+    /// Whether the unreleased section contains breaking changes
+    pub fn has_breaking_changes(&self) -> bool {
+        let unreleased_heading = self.unreleased_heading(None);
+
+        self.entries().iter().any(|entry| {
+            entry.version.eq_ignore_ascii_case(&unreleased_heading)
+                && (entry.section.eq_ignore_ascii_case(BREAKING_SECTION)
+                    || entry.text.starts_with(BREAKING_PREFIX))
+        })
+    }
+
+    /// Whether releasing the given version would ship breaking changes without a major bump (or
+    /// a minor bump for `0.x` versions).
+    pub fn requires_major_bump(&self, version: &SemVer) -> bool {
+        if !self.has_breaking_changes() {
+            return false;
+        }
+
+        match self.latest_semver() {
+            Some(latest) if latest.major() == 0 => {
+                version.major() == 0 && version.minor() <= latest.minor()
+            }
+            Some(latest) => version.major() <= latest.major(),
+            None => false,
+        }
+    }
+
+    /// The highest released version, according to semver precedence
+    fn latest_semver(&self) -> Option<SemVer> {
+        self.versions(None)
+            .iter()
+            .find_map(|version| version.parse::<SemVer>().ok())
+    }
+
+    pub fn release(&mut self, version: &SemVer, scope: Option<&PackageJSON>) -> Result<()> {
+        self.release_in_memory(version, scope)?;
+        self.persist()
+    }
+
+    /// Render the would-be version heading and its notes, without persisting anything
+    pub fn preview_release(&self, version: &SemVer, scope: Option<&PackageJSON>) -> Result<String> {
+        let mut changelog = self.clone();
+        changelog.release_in_memory(version, scope)?;
+
+        Ok(format!(
+            "## {}\n\n{}",
+            self.config
+                .release
+                .heading(&version.to_string(), &self.config.timezone.today()),
+            changelog
+                .notes_scope(Some(&version.to_string()), None)?
+                .trim()
+        ))
+    }
+
+    fn release_in_memory(&mut self, version: &SemVer, scope: Option<&PackageJSON>) -> Result<()> {
+        if let Some(latest) = self.latest_semver() {
+            if *version <= latest {
+                return Err(eyre!(
+                    "Can't release {}, it should be greater than the latest version {}",
+                    version.to_string().blue().bold(),
+                    latest.to_string().blue().bold()
+                ));
+            }
+        }
+
+        if !self.config.release.allow_empty
+            && !self.has_notes(Some(&UNRELEASED_HEADING.to_string()))
+        {
+            return Err(eyre!(
+                "Can't release {}, the unreleased section is empty. Add some entries first, or use {} to release anyway",
+                version.to_string().blue().bold(),
+                "--allow-empty".blue().bold()
+            ));
+        }
+
+        let heading = self
+            .config
+            .release
+            .heading(&version.to_string(), &self.config.timezone.today());
+
+        // Make sure that the sections every release should contain exist
+        let mut required_sections: Vec<(String, String)> =
+            self.config.release.sections.clone().into_iter().collect();
+        required_sections.sort_by_key(|(section, _)| self.config.section_rank(section));
+
+        for (section, placeholder) in required_sections {
+            if !self.has_unreleased_section(&section) {
+                self.add_list_item_to_section_scope(&section, placeholder, None)?;
+            }
+        }
+
+        let unreleased_heading = self.unreleased_heading(None);
+        let marker = self.bullet_marker();
+
+        if let Some(unreleased) = self.root.find_node_mut(|node| {
+            if let Some(MarkdownToken::H2(name)) = &node.data {
+                name.eq_ignore_ascii_case(&unreleased_heading)
+            } else {
+                false
+            }
+        }) {
+            prune_empty_sections(unreleased);
+
+            if unreleased.children.iter().all(|child| {
+                matches!(
+                    child.data,
+                    Some(MarkdownToken::Reference(_, _) | MarkdownToken::BlankLine)
+                )
+            }) {
+                unreleased.add_child_at(
+                    0,
+                    Node::from_token(MarkdownToken::Paragraph(NO_SIGNIFICANT_CHANGES.to_string())),
+                );
+            }
+
+            // Convert to the new version
+            unreleased.rename_heading(&heading);
+
+            // Insert new [Unreleased] section at the top
+            let mut new_unreleased =
+                Node::from_token(MarkdownToken::H2(unreleased_heading.clone()));
+            let mut ul = Node::from_token(MarkdownToken::UnorderedList);
+            let li = Node::from_token(MarkdownToken::ListItem(NOTHING_YET.to_string(), 0, marker));
+
+            ul.add_child(li);
+            new_unreleased.add_child(ul);
+
+            self.insert_version_section(new_unreleased)?;
+
+            // Update references at the bottom
+            let c = self.clone();
+            match c.find_latest_version() {
+                Some(old_version) => {
+                    if let Some(unreleased_reference) =
+                        self.root.find_node_mut(|node| match &node.data {
+                            Some(MarkdownToken::Reference(name, _)) => name.eq_ignore_ascii_case(
+                                &unreleased_heading[1..unreleased_heading.len() - 1],
+                            ),
+                            _ => false,
+                        })
+                    {
+                        if let Some(MarkdownToken::Reference(name, link)) =
+                            &unreleased_reference.data
+                        {
+                            let tag = self.config.release.tag(
+                                scope
+                                    .filter(|scope| !scope.is_root())
+                                    .map(|scope| scope.name()),
+                                &version.to_string(),
+                            );
+
+                            // The unreleased link compares the new tag with HEAD from now on
+                            let updated_link = match link
+                                .strip_suffix("...HEAD")
+                                .and_then(|link| link.rsplit_once("/compare/"))
+                            {
+                                Some((base, _)) => format!("{}/compare/{}...HEAD", base, tag),
+                                None => link.clone().replace(old_version, &version.to_string()),
+                            };
+                            let new_link = match self.config.links.style {
+                                LinkStyle::Compare => link.clone().replace("HEAD", &tag),
+                                LinkStyle::Tag => self
+                                    .config
+                                    .links
+                                    .release(&Repo::from_git_repo(&self.pwd)?, &tag),
+                            };
+
+                            // Update unreleased_reference
+                            unreleased_reference.data =
+                                Some(MarkdownToken::Reference(name.to_string(), updated_link));
+
+                            // Insert new version reference
+                            let new_version_reference = Node::from_token(MarkdownToken::Reference(
+                                version.to_string(),
+                                new_link,
+                            ));
+
+                            match self.root.children.iter().position(|node| match &node.data {
+                                Some(MarkdownToken::Reference(name, _)) => {
+                                    !name.to_lowercase().starts_with("unreleased")
+                                }
+                                _ => false,
+                            }) {
+                                Some(idx) => self.root.add_child_at(idx, new_version_reference),
+                                None => self.root.add_child(new_version_reference),
+                            }
+                        }
+                    }
+                }
+                None => {
+                    return Err(eyre!(
+                        "Couldn't find latest version, is your CHANGELOG.md formatted correctly?"
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove the configured emoji from the section headings and entries, so that the AST only
+/// contains the actual section names and entries.
+fn strip_emoji(root: &mut Node, config: &Config) {
+    if config.emoji.sections.is_empty() {
+        return;
+    }
+
+    for section in root.filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::H3(_)))) {
+        let name = match &section.data {
+            Some(MarkdownToken::H3(name)) => name.to_string(),
+            _ => continue,
+        };
+
+        let (name, emoji) = match config.emoji.sections.iter().find(|(section, emoji)| {
+            name.eq_ignore_ascii_case(section)
+                || name.eq_ignore_ascii_case(&format!("{} {}", emoji, section))
+        }) {
+            Some((section, emoji)) => (section.to_string(), format!("{} ", emoji)),
+            None => continue,
+        };
+
+        section.rename_heading(&name);
+
+        for item in section
+            .filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::ListItem(_, _, _))))
+        {
+            if let Some(MarkdownToken::ListItem(text, _, _)) = &mut item.data {
+                if let Some(stripped) = text.strip_prefix(&emoji) {
+                    *text = stripped.to_string();
+                }
+            }
+        }
+    }
+}
+
+/// Add the configured emoji to the section headings or entries when writing the changelog.
+fn decorate_emoji(root: &Node, config: &Config) -> Node {
+    let mut root = root.clone();
+
+    if config.emoji.sections.is_empty() {
+        return root;
+    }
+
+    for section in root.filter_nodes_mut(|node| matches!(&node.data, Some(MarkdownToken::H3(_)))) {
+        let (name, emoji) = match &section.data {
+            Some(MarkdownToken::H3(name)) => match config.emoji.for_section(name) {
+                Some(emoji) => (name.to_string(), emoji.to_string()),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        match config.emoji.placement {
+            EmojiPlacement::Heading => section.rename_heading(&format!("{} {}", emoji, name)),
+            EmojiPlacement::Entry => {
+                for item in section.filter_nodes_mut(|node| {
+                    matches!(&node.data, Some(MarkdownToken::ListItem(_, 0, _)))
+                }) {
+                    if let Some(MarkdownToken::ListItem(text, _, _)) = &mut item.data {
+                        *text = format!("{} {}", emoji, text);
+                    }
+                }
+            }
+        }
+    }
+
+    root
+}
+
+/// Read the contents of the changelog, a UTF-8 byte order mark is stripped (the returned boolean
+/// indicates whether it existed). Other encodings result in an error instead of garbled text.
+fn read_contents(file_path: &Path) -> Result<(String, bool)> {
+    let bytes = fs::read(file_path)?;
+
+    let (bytes, bom) = match bytes.strip_prefix(UTF8_BOM) {
+        Some(bytes) => (bytes, true),
+        None => (&bytes[..], false),
+    };
+
+    if bytes.starts_with(&[0xFF, 0xFE]) || bytes.starts_with(&[0xFE, 0xFF]) {
+        return Err(eyre!(
+            "{} is encoded as UTF-16, please convert it to UTF-8",
+            file_path.display()
+        ));
+    }
+
+    match String::from_utf8(bytes.to_vec()) {
+        Ok(contents) => Ok((contents, bom)),
+        Err(e) => Err(eyre!(
+            "{} is not valid UTF-8 (invalid byte at offset {}), please convert it to UTF-8",
+            file_path.display(),
+            e.utf8_error().valid_up_to()
+        )),
+    }
+}
+
+fn entries(root: &Node) -> Vec<Entry> {
+    let mut result = vec![];
+
+    for version in root.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H2(_)))) {
+        let version_name = match &version.data {
+            Some(MarkdownToken::H2(name)) => name.to_string(),
+            _ => unreachable!(),
+        };
+
+        for child in &version.children {
+            let (section, lists) = match &child.data {
+                Some(MarkdownToken::H3(name)) => {
+                    (name.to_string(), child.children.iter().collect())
+                }
+                _ => (String::new(), vec![child]),
+            };
+
+            for list in lists {
+                for item in &list.children {
+                    if let Some(
+                        MarkdownToken::ListItem(text, _, _)
+                        | MarkdownToken::OrderedListItem(text, _, _),
+                    ) = &item.data
+                    {
+                        if text != NOTHING_YET {
+                            result.push(Entry {
+                                version: version_name.clone(),
+                                section: section.clone(),
+                                text: text.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    result
+}
+
+fn new_unreleased_entries(base: &[Entry], current: &[Entry]) -> Vec<Entry> {
+    current
+        .iter()
+        .filter(|entry| {
+            entry
+                .version
+                .to_lowercase()
+                .starts_with(&format!("[{}", UNRELEASED_HEADING.to_lowercase()))
+        })
+        .filter(|entry| !base.iter().any(|other| other.text == entry.text))
+        .cloned()
+        .collect()
+}
+
+/// Extract the urls of all the markdown links in an entry, e.g.: `Title ([#1](url))`
+fn links(text: &str) -> Vec<&str> {
+    text.split("](")
+        .skip(1)
+        .filter_map(|part| part.split_once(')').map(|(url, _)| url))
+        .collect()
+}
+
+/// Extract the PR/issue number of the first link in an entry that has one
+fn link_number(text: &str) -> Option<u64> {
+    links(text)
+        .iter()
+        .find_map(|url| url.trim_end_matches('/').rsplit('/').next()?.parse().ok())
+}
+
+/// Sort the entries of a list, nested entries stay attached to their parent entry.
+fn sort_entries(ul: &mut Node, order: SortOrder) {
+    let mut groups: Vec<Vec<Node>> = vec![];
+
+    for child in ul.children.drain(..) {
+        match (&child.data, groups.last_mut()) {
+            (Some(MarkdownToken::ListItem(_, indent, _)), Some(group)) if *indent > 0 => {
+                group.push(child)
+            }
+            _ => groups.push(vec![child]),
+        }
+    }
+
+    groups.sort_by_cached_key(|group| match &group[0].data {
+        Some(MarkdownToken::ListItem(text, _, _)) => match order {
+            SortOrder::Alphabetical => (false, 0, text.to_lowercase()),
+            SortOrder::LinkNumber => match link_number(text) {
+                Some(number) => (false, number, text.to_lowercase()),
+                None => (true, 0, text.to_lowercase()),
+            },
+        },
+        _ => (true, 0, String::new()),
+    });
+
+    ul.children = groups.into_iter().flatten().collect();
+}
+
+/// Sort the sections of a version according to the canonical order, other nodes (e.g.: the
+/// "Nothing yet!" list) stay where they are.
+fn sort_sections(version: &mut Node, config: &Config) {
+    let positions: Vec<usize> = version
+        .children
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| matches!(&node.data, Some(MarkdownToken::H3(_))))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    let mut sections: Vec<Node> = positions
+        .iter()
+        .map(|idx| version.children[*idx].clone())
+        .collect();
+
+    sections.sort_by_key(|node| match &node.data {
+        Some(MarkdownToken::H3(name)) => config.section_rank(name),
+        _ => usize::MAX,
+    });
+
+    for (idx, section) in positions.into_iter().zip(sections) {
+        version.children[idx] = section;
+    }
+}
+
+fn is_duplicate_entry(node: &Node, item: &str) -> bool {
+    match &node.data {
+        Some(MarkdownToken::ListItem(existing, _, _)) => {
+            existing.trim().eq_ignore_ascii_case(item.trim()) || {
+                let existing_links = links(existing);
+                links(item).iter().any(|link| existing_links.contains(link))
+            }
+        }
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Amount {
+    All,
+    Value(usize),
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "all" => Amount::All,
+            _ => Amount::Value(s.parse::<usize>().map_err(|_| "Invalid amount")?),
+        })
+    }
+}
+
+/// The formats the changelog can be exported to
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Atom,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "atom" => Ok(ExportFormat::Atom),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!(
+                "Invalid export format: {}, expected one of \"atom\" or \"json\"",
+                s
+            )),
+        }
+    }
+}
+
+/// The changelog as plain data, see `changelog export --format json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Export {
+    pub title: Option<String>,
+
+    /// Every version in file order, including the unreleased one
+    #[serde(default)]
+    pub versions: Vec<ExportVersion>,
+
+    /// The reference links at the bottom of the changelog
+    #[serde(default)]
+    pub references: Vec<ExportReference>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportVersion {
+    /// The version, e.g.: `1.2.3` or `Unreleased`
+    pub version: String,
+
+    /// The release date as an ISO date
+    pub date: Option<String>,
+
+    /// The reference link of the version
+    pub link: Option<String>,
+
+    /// Paragraphs that are not part of a section, e.g.: `No significant changes.`
+    #[serde(default)]
+    pub notes: Vec<String>,
+
+    #[serde(default)]
+    pub sections: Vec<ExportSection>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportSection {
+    /// The name of the section, entries outside of a section don't have one
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub entries: Vec<ExportEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportEntry {
+    pub text: String,
+
+    /// The nested list items of the entry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ExportEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportReference {
+    pub name: String,
+    pub link: String,
+}
+
+/// A single entry to add to the unreleased section, see `changelog import`
+#[derive(Debug, Deserialize)]
+pub struct ImportEntry {
+    pub section: String,
+    pub message: String,
+}
+
+/// The formats entries can be imported from
+#[derive(Debug, Clone, Copy)]
+pub enum ImportFormat {
+    Json,
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ImportFormat::Json),
+            _ => Err(format!("Invalid import format: {}, expected \"json\"", s)),
+        }
+    }
+}
+
+/// The entries of a list, nested list items (based on their indentation) become children of the
+/// entry above them.
+fn export_entries(list: &Node) -> Vec<ExportEntry> {
+    let items: Vec<(usize, &str)> = list
+        .children
+        .iter()
+        .filter_map(|item| match &item.data {
+            Some(MarkdownToken::ListItem(text, indent, _))
+            | Some(MarkdownToken::OrderedListItem(text, indent, _)) => {
+                Some((*indent, text.as_str()))
+            }
+            _ => None,
+        })
+        .filter(|(_, text)| *text != NOTHING_YET)
+        .collect();
+
+    nest_entries(&items)
+}
+
+fn nest_entries(items: &[(usize, &str)]) -> Vec<ExportEntry> {
+    let mut entries = vec![];
+
+    let mut idx = 0;
+    while idx < items.len() {
+        let (indent, text) = items[idx];
+        let end = items[idx + 1..]
+            .iter()
+            .position(|(other, _)| *other <= indent)
+            .map(|position| idx + 1 + position)
+            .unwrap_or(items.len());
+
+        entries.push(ExportEntry {
+            text: text.to_string(),
+            children: nest_entries(&items[idx + 1..end]),
+        });
+
+        idx = end;
+    }
+
+    entries
+}
+
+/// What `Changelog::combine` changed, and where both changelogs disagreed
+#[derive(Debug, Default)]
+pub struct CombineReport {
+    /// The versions that only existed in the other changelog
+    pub versions: Vec<String>,
+
+    /// The amount of entries that were added, including the entries of the added versions
+    pub entries: usize,
+
+    /// The disagreements between both changelogs, this changelog won them
+    pub conflicts: Vec<String>,
+}
+
+/// The reference definitions that are not used anywhere, and the headings without a reference
+#[derive(Debug, Default)]
+pub struct LinkIssues {
+    /// The name and link of reference definitions that are not used anywhere
+    pub orphans: Vec<(String, String)>,
+
+    /// The names of headings without a reference definition
+    pub missing: Vec<String>,
+}
+
+impl LinkIssues {
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Remove all nodes (recursively) that match the predicate
+fn remove_nodes(node: &mut Node, predicate: &dyn Fn(&Node) -> bool) {
+    node.children.retain(|child| !predicate(child));
+
+    for child in &mut node.children {
+        remove_nodes(child, predicate);
+    }
+}
+
+/// The entries together with their indentation in the changelog
+fn indented(root: &Node, entries: Vec<Entry>) -> Vec<(Entry, usize)> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let indent = list_item(root, &entry)
+                .and_then(|item| match &item.data {
+                    Some(MarkdownToken::ListItem(_, indent, _)) => Some(*indent),
+                    _ => None,
+                })
+                .unwrap_or(0);
+
+            (entry, indent)
+        })
+        .collect()
+}
+
+/// The list item of an entry
+fn list_item<'a>(root: &'a Node, entry: &Entry) -> Option<&'a Node> {
+    root.find_node(|node| match &node.data {
+        Some(MarkdownToken::H2(name)) => name == &entry.version,
+        _ => false,
+    })?
+    .find_node(|node| match &node.data {
+        Some(MarkdownToken::ListItem(text, _, _)) => text == &entry.text,
+        _ => false,
+    })
+}
+
+/// Everything but the entries of a changelog, e.g.: the intro, the version headings and the
+/// reference links. Blank lines are ignored.
+fn skeleton(root: &Node) -> String {
+    let mut root = root.clone();
+    remove_nodes(&mut root, &|node| {
+        matches!(&node.data, Some(MarkdownToken::ListItem(_, _, _)))
+    });
+    prune_empty_sections(&mut root);
+
+    root.to_string()
+        .lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Drop the "Nothing yet!" placeholder and the sections without entries. An empty section can
+/// contain the sections that follow it (e.g.: `### Added` directly followed by `### Fixed`), those
+/// are kept.
+fn prune_empty_sections(node: &mut Node) {
+    for mut child in std::mem::take(&mut node.children) {
+        prune_empty_sections(&mut child);
+
+        match &child.data {
+            Some(MarkdownToken::ListItem(text, _, _)) if text.trim() == NOTHING_YET => {}
+            Some(MarkdownToken::UnorderedList | MarkdownToken::OrderedList)
+                if child.children.is_empty() => {}
+            Some(MarkdownToken::H3(_))
+                if child.children.iter().all(|child| {
+                    matches!(
+                        child.data,
+                        Some(
+                            MarkdownToken::H1(_)
+                                | MarkdownToken::H2(_)
+                                | MarkdownToken::H3(_)
+                                | MarkdownToken::Reference(_, _)
+                                | MarkdownToken::BlankLine
+                        )
+                    )
+                }) =>
+            {
+                node.children.extend(
+                    child
+                        .children
+                        .into_iter()
+                        .filter(|child| child.data != Some(MarkdownToken::BlankLine)),
+                );
+            }
+            _ => node.children.push(child),
+        }
+    }
+}
+
+/// The issue and pull request numbers referenced in the text, e.g.: `#123` or
+/// `https://github.com/<owner>/<repo>/pull/123`
+fn referenced_numbers(text: &str) -> Vec<String> {
+    ["#", "/pull/", "/issues/"]
+        .iter()
+        .flat_map(|prefix| {
+            text.match_indices(prefix)
+                .map(|(idx, _)| idx + prefix.len())
+        })
+        .map(|idx| {
+            text[idx..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>()
+        })
+        .filter(|number| !number.is_empty())
+        .collect()
+}
+
+/// The path of the localized variant of the changelog, e.g.: `CHANGELOG.fr.md`
+fn localized_path(file_path: &Path, locale: &str) -> PathBuf {
+    let stem = file_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("CHANGELOG");
+
+    match file_path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => file_path.with_file_name(format!("{}.{}.{}", stem, locale, ext)),
+        None => file_path.with_file_name(format!("{}.{}", stem, locale)),
+    }
+}
+
+/// The reference name used by a heading, e.g.: `1.0.0` for `[1.0.0] - 2022-03-05`
+fn heading_reference(heading: &str) -> Option<&str> {
+    heading.strip_prefix('[')?.split(']').next()
+}
+
+/// Render an entry of a version range, only the top level entry is annotated with its version
+fn range_entry(entry: &ExportEntry, marker: char, depth: usize, version: Option<&str>) -> String {
+    let mut text = format!("{}{} {}", "  ".repeat(depth), marker, entry.text);
+    if let Some(version) = version {
+        text.push_str(&format!(" ({})", version));
+    }
+
+    for child in &entry.children {
+        text.push('\n');
+        text.push_str(&range_entry(child, marker, depth + 1, None));
+    }
+
+    text
+}
+
+/// The date of a version heading like `[1.0.0] - 2021-03-05`
+fn heading_date(heading: &str) -> Option<NaiveDate> {
+    let (_, date) = heading.split_once(']')?;
+
+    parse_date(
+        date.trim()
+            .trim_start_matches(['-', '–', '—', '('])
+            .trim_end_matches(')'),
+        DateLocale::DayFirst,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_find_the_latest_version() {
+        let c = Changelog {
+            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let latest_version = c.find_latest_version();
+        assert_eq!(latest_version, Some("0.1.0"));
+    }
+
+    #[test]
+    fn it_should_get_the_contents_of_a_section() {
+        let c = Changelog {
+            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
+        assert!(unreleased_section.is_some());
+
+        let unreleased_section = unreleased_section.unwrap();
+        assert_eq!(
+            unreleased_section,
+            Node::from_str("- Nothing yet!").unwrap()
+        );
+
+        let first_release = c.get_contents_of_section(&Some("0.1.0".to_string()));
+        assert!(first_release.is_some());
+
+        let first_release = first_release.unwrap();
+        assert_eq!(
+            first_release,
+            Node::from_str("### Added\n- Everything!").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_sort_the_list_of_releases_by_version() {
+        let c = Changelog {
+            root: Node::from_str(
+                "## [Unreleased]\n\n[1.2.0]: https://example.com/1.2.0\n[unreleased]: https://example.com/HEAD\n[custom]: https://example.com/custom\n[1.10.0]: https://example.com/1.10.0\n[1.10.0-beta.1]: https://example.com/1.10.0-beta.1",
+            )
+            .unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let names: Vec<String> = c
+            .list(Amount::All)
+            .unwrap()
+            .lines()
+            .map(|line| line[2..].split_whitespace().next().unwrap().to_string())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec!["unreleased", "1.10.0", "1.10.0-beta.1", "1.2.0", "custom"]
+        );
+    }
+
+    #[test]
+    fn it_should_prune_empty_sections_when_releasing() {
+        let mut c = Changelog {
+            root: Node::from_str(
+                "# Changelog\n\nAll notable changes.\n\nThe format is based on Keep a Changelog.\n\n## [Unreleased]\n\n### Added\n\n### Fixed\n\n- A fix\n\n## [0.1.0] - 2022-01-01\n\n- Initial release\n\n[unreleased]: https://github.com/acme/widget/compare/v0.1.0...HEAD\n[0.1.0]: https://github.com/acme/widget/releases/tag/v0.1.0\n",
+            )
+            .unwrap(),
+            pwd: PathBuf::default(),
+            file_path: std::env::temp_dir().join("changelog-prune-empty-sections.md"),
+            config: Config::default(),
+            bom: false,
+        };
+
+        c.release(&"1.0.0".parse().unwrap(), None).unwrap();
+
+        let notes = c
+            .get_contents_of_section(&Some("1.0.0".to_string()))
+            .unwrap();
+        assert!(notes
+            .find_node(|node| node.data == Some(MarkdownToken::H3("Added".to_string())))
+            .is_none());
+        assert!(notes
+            .find_node(|node| node.data == Some(MarkdownToken::H3("Fixed".to_string())))
+            .is_some());
+
+        c.config.release.allow_empty = true;
+        c.release(&"1.0.1".parse().unwrap(), None).unwrap();
+
+        let notes = c
+            .get_contents_of_section(&Some("1.0.1".to_string()))
+            .unwrap();
+        assert_eq!(notes.to_string().trim(), NO_SIGNIFICANT_CHANGES);
+
+        fs::remove_file(&c.file_path).unwrap();
+    }
 
-        let first_release = first_release.unwrap();
+    #[test]
+    fn it_should_know_whether_the_unreleased_section_has_changes() {
+        let mut c = Changelog {
+            root: Node::from_str("## [Unreleased]\n\n- Nothing yet!\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+        assert!(!c.has_changes(None));
+
+        c.root = Node::from_str("## [Unreleased]\n\n### Fixed\n\n- A fix\n").unwrap();
+        assert!(c.has_changes(None));
+    }
+
+    #[test]
+    fn it_should_backfill_versions_in_order() {
+        let mut c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [2.0.0] - 2022-02-01\n\n- Something\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+        let date = NaiveDate::from_ymd(2022, 1, 15);
+
+        assert!(!c.backfill(&"1.0.0".parse().unwrap(), &date, &[]));
+        assert!(c.backfill(
+            &"1.1.0".parse().unwrap(),
+            &date,
+            &["Add a feature".to_string()]
+        ));
+        assert!(c.backfill(&"1.0.1".parse().unwrap(), &date, &[]));
+
+        assert_eq!(c.versions(None), vec!["2.0.0", "1.1.0", "1.0.1", "1.0.0"]);
         assert_eq!(
-            first_release,
-            Node::from_str("### Added\n- Everything!").unwrap()
+            c.notes(Some(&"1.1.0".to_string())).unwrap().trim(),
+            "### Changed\n\n- Add a feature"
+        );
+        assert_eq!(
+            c.notes(Some(&"1.0.1".to_string())).unwrap().trim(),
+            "No significant changes."
+        );
+    }
+
+    #[test]
+    fn it_should_render_the_last_versions() {
+        let c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0] - 2022-02-01\n\n### Fixed\n\n- Fix B\n\n## [1.0.0] - 2022-01-01\n\n### Added\n\n- Add A\n\n[unreleased]: https://github.com/acme/widget/compare/v1.1.0...HEAD\n[1.1.0]: https://github.com/acme/widget/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/acme/widget/releases/tag/v1.0.0\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        assert_eq!(
+            c.last_notes(1),
+            "## [1.1.0] - 2022-02-01\n\n### Fixed\n\n- Fix B\n\n[1.1.0]: https://github.com/acme/widget/compare/v1.0.0...v1.1.0"
+        );
+        assert_eq!(c.last_notes(5).matches("## [").count(), 2);
+        assert!(c.last_notes(0).is_empty());
+        assert_eq!(
+            c.version_heading(Some(&"previous".to_string())).as_deref(),
+            Some("[1.0.0] - 2022-01-01")
+        );
+    }
+
+    #[test]
+    fn it_should_combine_the_notes_of_a_version_range() {
+        let c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.3.0] - 2022-03-01\n\n### Fixed\n\n- Fix C\n\n### Added\n\n- Add C\n  - With a detail\n\n## [1.2.0] - 2022-02-01\n\n### Fixed\n\n- Fix B\n\n## [1.1.0] - 2022-01-01\n\n### Added\n\n- Add A\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        assert_eq!(
+            c.notes_between("1.1.0", None).unwrap(),
+            "### Added\n\n- Add C (1.3.0)\n  - With a detail\n\n### Fixed\n\n- Fix C (1.3.0)\n- Fix B (1.2.0)"
+        );
+        assert_eq!(
+            c.notes_between("first", Some("previous")).unwrap(),
+            "### Fixed\n\n- Fix B (1.2.0)"
+        );
+        assert!(c.notes_between("latest", None).unwrap().is_empty());
+        assert!(c.notes_between("0.9.0", None).is_err());
+    }
+
+    #[test]
+    fn it_should_merge_the_entries_of_two_branches() {
+        let changelog = |contents: &str| Changelog {
+            root: Node::from_str(contents).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+        let base = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- A feature\n- Another feature\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n";
+
+        let mut ours = changelog("# Changelog\n\n## [Unreleased]\n\n### Added\n\n- A feature\n- Another feature\n- Our feature\n\n### Fixed\n\n- Our fix\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n");
+        let theirs = Node::from_str("# Changelog\n\n## [Unreleased]\n\n### Added\n\n- A feature\n- Their feature\n\n### Changed\n\n- Another feature\n\n### Fixed\n\n- Their fix\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n").unwrap();
+
+        assert!(ours.merge(&Node::from_str(base).unwrap(), &theirs));
+        assert_eq!(
+            ours.notes(None).unwrap().trim(),
+            "### Added\n\n- A feature\n- Their feature\n- Our feature\n\n### Changed\n\n- Another feature\n\n### Fixed\n\n- Their fix\n- Our fix"
+        );
+
+        // Both branches released a version
+        let mut ours = changelog("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0] - 2022-02-01\n\n### Added\n\n- A feature\n- Another feature\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n");
+        let theirs = Node::from_str("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [2.0.0] - 2022-02-01\n\n### Added\n\n- A feature\n- Another feature\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n").unwrap();
+
+        assert!(!ours.merge(&Node::from_str(base).unwrap(), &theirs));
+    }
+
+    #[test]
+    fn it_should_combine_two_changelogs() {
+        let mut c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.1.0] - 2022-02-01\n\n### Added\n\n- A feature\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n\n[unreleased]: https://github.com/a/b/compare/v1.1.0...HEAD\n[1.1.0]: https://github.com/a/b/compare/v1.0.0...v1.1.0\n[1.0.0]: https://github.com/a/b/releases/tag/v1.0.0\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+        let other = Node::from_str("# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- A fix\n\n## [1.2.0] - 2022-03-01\n\n- A later feature\n\n## [1.1.0] - 2022-02-02\n\n### Added\n\n- A feature\n- Another feature\n\n[1.2.0]: https://github.com/a/b/compare/v1.1.0...v1.2.0\n[1.1.0]: https://github.com/a/c/compare/v1.0.0...v1.1.0\n").unwrap();
+
+        let report = c.combine(&other);
+        assert_eq!(report.versions, vec!["1.2.0"]);
+        assert_eq!(report.entries, 3);
+        assert_eq!(
+            report.conflicts,
+            vec![
+                "1.1.0 is dated 2022-02-01 here, but 2022-02-02 in the other changelog",
+                "[1.1.0] links to https://github.com/a/b/compare/v1.0.0...v1.1.0 here, but to https://github.com/a/c/compare/v1.0.0...v1.1.0 in the other changelog",
+            ]
+        );
+        assert_eq!(
+            c.versions(None),
+            vec![
+                "1.2.0".to_string(),
+                "1.1.0".to_string(),
+                "1.0.0".to_string()
+            ]
+        );
+        assert_eq!(c.notes(None).unwrap().trim(), "### Fixed\n\n- A fix");
+        assert_eq!(
+            c.notes(Some(&"1.1.0".to_string())).unwrap().trim(),
+            "### Added\n\n- A feature\n- Another feature"
+        );
+        assert!(c.root.to_string().contains(
+            "[unreleased]: https://github.com/a/b/compare/v1.1.0...HEAD\n[1.2.0]: https://github.com/a/b/compare/v1.1.0...v1.2.0\n[1.1.0]"
+        ));
+    }
+
+    #[test]
+    fn it_should_find_the_new_unreleased_entries() {
+        let entry = |version: &str, section: &str, text: &str| Entry {
+            version: version.to_string(),
+            section: section.to_string(),
+            text: text.to_string(),
+        };
+        let base = vec![
+            entry("[Unreleased]", "Added", "A feature"),
+            entry("[1.0.0] - 2022-01-01", "", "Initial release"),
+        ];
+        let current = vec![
+            entry("[Unreleased]", "Changed", "A feature"),
+            entry("[Unreleased]", "Fixed", "A fix"),
+            entry("[1.0.0] - 2022-01-01", "", "Initial release"),
+            entry("[1.0.0] - 2022-01-01", "", "A forgotten entry"),
+        ];
+
+        assert_eq!(
+            new_unreleased_entries(&base, &current),
+            vec![entry("[Unreleased]", "Fixed", "A fix")]
+        );
+    }
+
+    fn ordered_changelog() -> Changelog {
+        Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n### Added\n\n1. A feature\n2. A fix\n\n### Fixed\n\n- Another fix\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        }
+    }
+
+    fn unreleased_entry(section: &str, text: &str) -> Entry {
+        Entry {
+            version: "[Unreleased]".to_string(),
+            section: section.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_list_the_entries_of_ordered_lists() {
+        assert_eq!(
+            ordered_changelog().entries(),
+            vec![
+                unreleased_entry("Added", "A feature"),
+                unreleased_entry("Added", "A fix"),
+                unreleased_entry("Fixed", "Another fix"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_edit_the_entries_of_ordered_lists() {
+        let mut c = ordered_changelog();
+
+        assert!(c.edit_entry(&unreleased_entry("Added", "A feature"), "A new feature"));
+        assert_eq!(
+            c.notes(None).unwrap().trim(),
+            "### Added\n\n1. A new feature\n2. A fix\n\n### Fixed\n\n- Another fix"
+        );
+    }
+
+    #[test]
+    fn it_should_move_the_entries_of_ordered_lists() {
+        let mut c = ordered_changelog();
+
+        assert!(c
+            .move_entry(&unreleased_entry("Added", "A fix"), "Fixed")
+            .unwrap());
+        assert_eq!(
+            c.notes(None).unwrap().trim(),
+            "### Added\n\n1. A feature\n\n### Fixed\n\n- Another fix\n- A fix"
+        );
+    }
+
+    #[test]
+    fn it_should_edit_move_and_remove_entries() {
+        let mut c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n### Added\n\n- A feature\n  - A detail\n- A fix\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+        let entry = |section: &str, text: &str| Entry {
+            version: "[Unreleased]".to_string(),
+            section: section.to_string(),
+            text: text.to_string(),
+        };
+
+        assert!(c.edit_entry(&entry("Added", "A fix"), "Fix a bug"));
+        assert!(c.move_entry(&entry("Added", "Fix a bug"), "Fixed").unwrap());
+        assert!(!c.move_entry(&entry("Added", "Fix a bug"), "Fixed").unwrap());
+        assert_eq!(
+            c.notes(None).unwrap().trim(),
+            "### Added\n\n- A feature\n  - A detail\n\n### Fixed\n\n- Fix a bug"
+        );
+
+        assert!(c
+            .move_entry(&entry("Added", "A feature"), "Changed")
+            .unwrap());
+        assert_eq!(
+            c.notes(None).unwrap().trim(),
+            "### Changed\n\n- A feature\n  - A detail\n\n### Fixed\n\n- Fix a bug"
+        );
+
+        assert!(c.remove_entry(&entry("Changed", "A feature")));
+        assert!(c.remove_entry(&entry("Fixed", "Fix a bug")));
+        assert!(!c.has_changes(None));
+        assert!(c
+            .root
+            .to_string()
+            .contains("## [Unreleased]\n\n- Nothing yet!\n\n## [1.0.0]"));
+    }
+
+    #[test]
+    fn it_should_export_the_changelog_as_plain_data() {
+        let c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.0.1] - 2022-01-02\n\nNo significant changes.\n\n## [1.0.0] - 2022-01-01\n\n### Added\n\n- A feature\n  - A detail\n    - A nested detail\n- Another feature\n\n[unreleased]: https://github.com/acme/widget/compare/v1.0.1...HEAD\n[1.0.1]: https://github.com/acme/widget/compare/v1.0.0...v1.0.1\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let export = serde_json::to_value(c.export()).unwrap();
+
+        assert_eq!(export["title"], "Changelog");
+        assert_eq!(export["versions"][0]["version"], "Unreleased");
+        assert_eq!(export["versions"][0]["sections"], serde_json::json!([]));
+        assert_eq!(
+            export["versions"][1]["link"],
+            "https://github.com/acme/widget/compare/v1.0.0...v1.0.1"
+        );
+        assert_eq!(
+            export["versions"][1]["notes"],
+            serde_json::json!(["No significant changes."])
+        );
+        assert_eq!(export["versions"][2]["date"], "2022-01-01");
+        assert_eq!(export["versions"][2]["link"], serde_json::Value::Null);
+        assert_eq!(
+            export["versions"][2]["sections"],
+            serde_json::json!([{
+                "name": "Added",
+                "entries": [
+                    {
+                        "text": "A feature",
+                        "children": [{ "text": "A detail", "children": [{ "text": "A nested detail" }] }]
+                    },
+                    { "text": "Another feature" }
+                ]
+            }])
+        );
+        assert_eq!(export["references"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn it_should_import_an_export() {
+        let mut c = Changelog {
+            root: Node::from_str("# Changelog\n\n## [Unreleased]\n\n### Fixed\n\n- A fix\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let export: Export = serde_json::from_value(serde_json::json!({
+            "versions": [
+                {
+                    "version": "Unreleased",
+                    "sections": [
+                        { "name": "Fixed", "entries": [{ "text": "A fix" }, { "text": "Another fix" }] }
+                    ]
+                },
+                { "version": "1.1.0", "date": "2022-02-01", "sections": [{ "name": "Added", "entries": [{ "text": "A feature" }] }] },
+                { "version": "1.0.0", "sections": [{ "name": "Added", "entries": [{ "text": "Ignored" }] }] }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(c.import(export).unwrap(), (1, 1));
+        assert_eq!(
+            c.notes(None).unwrap().trim(),
+            "### Fixed\n\n- A fix\n- Another fix"
+        );
+        assert_eq!(c.versions(None), vec!["1.1.0", "1.0.0"]);
+        assert_eq!(
+            c.notes(Some(&"1.1.0".to_string())).unwrap().trim(),
+            "### Added\n\n- A feature"
+        );
+    }
+
+    #[test]
+    fn it_should_find_commits_without_an_entry() {
+        let c = Changelog {
+            root: Node::from_str("## [Unreleased]\n\n### Fixed\n\n- Fix a bug ([#12](https://github.com/acme/widget/pull/12))\n- Fix another bug ([abcdef1](https://github.com/acme/widget/commit/abcdef1))\n\n## [1.0.0] - 2022-01-01\n\n- Add a feature (#13)\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let commits: Vec<(String, String)> = [
+            ("1111111111", "Fix a bug (#12)"),
+            ("abcdef1234", "Fix another bug"),
+            ("2222222222", "Merge pull request #13 from acme/feature"),
+            ("3333333333", "Bump dependencies"),
+        ]
+        .iter()
+        .map(|(hash, subject)| (hash.to_string(), subject.to_string()))
+        .collect();
+
+        assert_eq!(
+            c.unlogged(&commits),
+            vec![
+                (
+                    "2222222222".to_string(),
+                    "Merge pull request #13 from acme/feature".to_string()
+                ),
+                ("3333333333".to_string(), "Bump dependencies".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_the_path_of_localized_changelogs() {
+        assert_eq!(
+            localized_path(Path::new("/repo/CHANGELOG.md"), "fr"),
+            PathBuf::from("/repo/CHANGELOG.fr.md")
+        );
+        assert_eq!(
+            localized_path(Path::new("/repo/CHANGES"), "nl-BE"),
+            PathBuf::from("/repo/CHANGES.nl-BE")
+        );
+    }
+
+    #[test]
+    fn it_should_find_orphaned_and_missing_references() {
+        let c = Changelog {
+            root: Node::from_str(
+                "# Changelog\n\n## [Unreleased]\n\n- See [docs]\n\n## [1.1.0] - 2022-03-01\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n\n[unreleased]: https://example.com/HEAD\n[1.0.0]: https://example.com/1.0.0\n[0.9.0]: https://example.com/0.9.0\n[docs]: https://example.com/docs",
+            )
+            .unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        let issues = c.link_issues();
+        assert_eq!(
+            issues.orphans,
+            vec![("0.9.0".to_string(), "https://example.com/0.9.0".to_string())]
+        );
+        assert_eq!(issues.missing, vec!["1.1.0"]);
+    }
+
+    #[test]
+    fn it_should_resolve_version_aliases() {
+        let c = Changelog {
+            root: Node::from_str(
+                "## [Unreleased]\n\n## [1.10.0] - 2022-03-01\n\n## [1.2.0] - 2022-02-01\n\n## [1.9.0] - 2022-01-15\n\n## [1.0.0] - 2022-01-01",
+            )
+            .unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        assert_eq!(
+            c.resolve_version("latest", None),
+            Some("1.10.0".to_string())
+        );
+        assert_eq!(
+            c.resolve_version("previous", None),
+            Some("1.9.0".to_string())
+        );
+        assert_eq!(
+            c.resolve_version("latest-2", None),
+            Some("1.2.0".to_string())
         );
+        assert_eq!(c.resolve_version("first", None), Some("1.0.0".to_string()));
+        assert_eq!(c.resolve_version("latest-10", None), None);
+        assert_eq!(c.resolve_version("1.2.0", None), Some("1.2.0".to_string()));
     }
 
     #[test]
@@ -498,12 +3394,16 @@ mod tests {
             root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
             pwd: PathBuf::default(),
             file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
         };
 
         assert_eq!(
             c.list(Amount::All).unwrap(),
-            ["- unreleased      https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD",
-                "- 0.1.0           https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"]
+            [
+                "- unreleased      https://github.com/RobinMalfait/changelog/compare/v0.1.0...HEAD",
+                "- 0.1.0           https://github.com/RobinMalfait/changelog/releases/tag/v0.1.0"
+            ]
             .join("\n")
         );
     }
@@ -514,6 +3414,8 @@ mod tests {
             root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
             pwd: PathBuf::default(),
             file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
         };
 
         let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
@@ -527,14 +3429,15 @@ mod tests {
                 children: vec![Node {
                     data: Some(MarkdownToken::UnorderedList,),
                     children: vec![Node {
-                        data: Some(MarkdownToken::ListItem("Nothing yet!".to_string(), 0)),
+                        data: Some(MarkdownToken::ListItem("Nothing yet!".to_string(), 0, '-')),
                         children: vec![],
                     }],
                 }],
             }
         );
 
-        c.add_list_item_to_section("Added", "Something new", false, None);
+        c.add_list_item_to_section("Added", "Something new")
+            .unwrap();
 
         let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
         assert!(unreleased_section.is_some());
@@ -549,7 +3452,11 @@ mod tests {
                     children: vec![Node {
                         data: Some(MarkdownToken::UnorderedList),
                         children: vec![Node {
-                            data: Some(MarkdownToken::ListItem("Something new".to_string(), 0)),
+                            data: Some(MarkdownToken::ListItem(
+                                "Something new".to_string(),
+                                0,
+                                '-'
+                            )),
                             children: vec![],
                         }],
                     }],
@@ -557,7 +3464,8 @@ mod tests {
             }
         );
 
-        c.add_list_item_to_section("Added", "Something newer", false, None);
+        c.add_list_item_to_section("Added", "Something newer")
+            .unwrap();
 
         let unreleased_section = c.get_contents_of_section(&Some("unreleased".to_string()));
         assert!(unreleased_section.is_some());
@@ -573,13 +3481,18 @@ mod tests {
                         data: Some(MarkdownToken::UnorderedList),
                         children: vec![
                             Node {
-                                data: Some(MarkdownToken::ListItem("Something new".to_string(), 0)),
+                                data: Some(MarkdownToken::ListItem(
+                                    "Something new".to_string(),
+                                    0,
+                                    '-'
+                                )),
                                 children: vec![],
                             },
                             Node {
                                 data: Some(MarkdownToken::ListItem(
                                     "Something newer".to_string(),
-                                    0
+                                    0,
+                                    '-'
                                 )),
                                 children: vec![],
                             }
@@ -589,4 +3502,182 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn it_should_not_add_duplicate_entries_to_a_section() {
+        let mut c = Changelog {
+            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        assert!(c
+            .add_list_item_to_section(
+                "Fixed",
+                "Fix a bug ([#1](https://github.com/owner/repo/pull/1))"
+            )
+            .unwrap());
+        assert!(!c
+            .add_list_item_to_section(
+                "Fixed",
+                "Fix a bug ([#1](https://github.com/owner/repo/pull/1))"
+            )
+            .unwrap());
+        assert!(!c
+            .add_list_item_to_section(
+                "Fixed",
+                "Fix that nasty bug ([#1](https://github.com/owner/repo/pull/1))"
+            )
+            .unwrap());
+        assert!(c
+            .add_list_item_to_section(
+                "Fixed",
+                "Fix another bug ([#2](https://github.com/owner/repo/pull/2))"
+            )
+            .unwrap());
+
+        let unreleased_section = c
+            .get_contents_of_section(&Some("unreleased".to_string()))
+            .unwrap();
+        assert_eq!(
+            unreleased_section
+                .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::ListItem(_, _, _))))
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn it_should_keep_entries_sorted_by_link_number() {
+        let mut c = Changelog {
+            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config {
+                sort: Some(SortOrder::LinkNumber),
+                ..Config::default()
+            },
+            bom: false,
+        };
+
+        for item in [
+            "Manual entry",
+            "Fix C ([#30](https://github.com/owner/repo/pull/30))",
+            "Fix A ([#4](https://github.com/owner/repo/pull/4))",
+            "Fix B ([#12](https://github.com/owner/repo/issues/12))",
+        ] {
+            c.add_list_item_to_section("Fixed", item).unwrap();
+        }
+
+        assert_eq!(
+            c.get_contents_of_section(&Some("unreleased".to_string()))
+                .unwrap()
+                .to_string()
+                .trim(),
+            [
+                "### Fixed",
+                "",
+                "- Fix A ([#4](https://github.com/owner/repo/pull/4))",
+                "- Fix B ([#12](https://github.com/owner/repo/issues/12))",
+                "- Fix C ([#30](https://github.com/owner/repo/pull/30))",
+                "- Manual entry",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn it_should_insert_new_sections_in_the_canonical_order() {
+        let mut c = Changelog {
+            root: Node::from_str(include_str!("../CHANGELOG.md")).unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        c.add_list_item_to_section("Fixed", "A fix").unwrap();
+        c.add_list_item_to_section("Custom", "Something custom")
+            .unwrap();
+        c.add_list_item_to_section("Added", "A feature").unwrap();
+        c.add_list_item_to_section("Security", "A security fix")
+            .unwrap();
+
+        let unreleased_section = c
+            .get_contents_of_section(&Some("unreleased".to_string()))
+            .unwrap();
+        let sections: Vec<_> = unreleased_section
+            .filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::H3(_))))
+            .iter()
+            .filter_map(|node| match &node.data {
+                Some(MarkdownToken::H3(name)) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(sections, vec!["Added", "Fixed", "Security", "Custom"]);
+    }
+
+    #[test]
+    fn it_should_add_entries_to_a_changelog_without_sections() {
+        let mut c = Changelog {
+            root: Node::from_str("# Changelog\n").unwrap(),
+            pwd: PathBuf::default(),
+            file_path: PathBuf::default(),
+            config: Config::default(),
+            bom: false,
+        };
+
+        assert!(c
+            .add_list_item_to_section("Added", "Something new")
+            .unwrap());
+        assert_eq!(
+            c.notes(None).unwrap().trim(),
+            "### Added\n\n- Something new"
+        );
+
+        c.root = Node::from_str("").unwrap();
+        assert!(c
+            .add_list_item_to_section("Added", "Something new")
+            .is_err());
+    }
+
+    #[test]
+    fn it_should_compare_with_a_changelog_in_a_subdirectory() {
+        let dir = std::env::temp_dir().join(format!("changelog-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("docs")).unwrap();
+
+        let git = Git::new(Some(&dir)).unwrap();
+        git.exec(vec!["init", "--quiet"]).unwrap();
+        git.set_config("user.name", "Changelog").unwrap();
+        git.set_config("user.email", "changelog@example.com")
+            .unwrap();
+        git.set_config("commit.gpgsign", "false").unwrap();
+
+        let contents =
+            "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n### Added\n\n- A feature\n";
+        fs::write(dir.join("docs/CHANGELOG.md"), contents).unwrap();
+        git.add("docs/CHANGELOG.md").unwrap();
+        git.commit("init", &crate::git::CommitOptions::default())
+            .unwrap();
+
+        let mut c =
+            Changelog::from_contents(&dir, "docs/CHANGELOG.md", contents, &Config::default())
+                .unwrap();
+        c.add_list_item_to_section("Added", "Another feature")
+            .unwrap();
+
+        assert_eq!(
+            c.new_entries_since("HEAD")
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.text)
+                .collect::<Vec<_>>(),
+            vec!["Another feature"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
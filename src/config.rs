@@ -0,0 +1,927 @@
+use crate::announce::Webhook;
+use crate::date::Timezone;
+use crate::github::{github_info::GitHubInfo, repo::Repo};
+use crate::list_format::conjunction;
+use crate::metadata;
+use crate::sanitize::{
+    collapse_whitespace, escape_markdown, split_conventional_prefix, strip_conventional_prefix,
+    truncate, uppercase_first,
+};
+use crate::version_file::VersionFile;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{NaiveDate, SecondsFormat, Utc};
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const CONFIG_PATH: &str = ".changelog/config.json";
+
+/// Project specific configuration, read from `.changelog/config.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Config {
+    /// Keep the entries within each section sorted
+    pub sort: Option<SortOrder>,
+
+    /// The canonical order of the sections within a version, unknown sections go last
+    pub sections: Vec<String>,
+
+    /// Where new entries are inserted within a section
+    pub position: Position,
+
+    /// Whether the changelog file should start with a UTF-8 byte order mark
+    pub bom: Bom,
+
+    /// How breaking changes are marked
+    pub breaking: Breaking,
+
+    /// How titles fetched from GitHub are cleaned up
+    pub title: TitleOptions,
+
+    /// Credit the authors of PRs fetched from GitHub
+    pub attribution: Attribution,
+
+    /// Use the conventional commit prefix of titles fetched from GitHub to pick the section
+    pub conventional_commits: ConventionalCommits,
+
+    /// Prefix entries or section headings with an emoji per section (gitmoji style)
+    pub emoji: Emoji,
+
+    /// Rewrite bare issue references like `#123` in manual messages to links
+    pub autolink: bool,
+
+    /// How entries for resources fetched from GitHub are formatted
+    pub entry_template: String,
+
+    /// How the heading of a released version looks like
+    pub release: ReleaseOptions,
+
+    /// The timezone used for the dates of new versions
+    pub timezone: Timezone,
+
+    /// Named groups of packages in a monorepo, usable as `--scope <group>`
+    pub scope_groups: HashMap<String, Vec<String>>,
+
+    /// Annotate generated entries with a hidden comment that records where they came from
+    pub provenance: bool,
+
+    /// Also write the notes of a released version to this file, relative to the changelog
+    pub notes_file: Option<String>,
+
+    /// Commands per locale that translate the notes of a released version, the notes are piped
+    /// through the command and the output is written to a localized changelog, e.g.:
+    /// `CHANGELOG.fr.md`
+    pub translations: HashMap<String, String>,
+
+    /// Record who added each entry and mark new entries as pending review
+    pub review: bool,
+
+    /// Files that contain the version of the project, which are rewritten when releasing
+    pub version_files: Vec<VersionFile>,
+
+    /// The messages of the commits that are created, `{version}`, `{package}` and `{date}` are
+    /// replaced
+    pub commit_messages: CommitMessages,
+
+    /// The URLs of the version reference links, for repositories that are not hosted on GitHub
+    pub links: Links,
+
+    /// Webhooks that `changelog announce` posts the notes of a released version to
+    pub announce: Vec<Webhook>,
+
+    /// The changelog filename, this takes precedence over `--filename`. This is mostly useful for
+    /// packages in a monorepo, see `PackageConfig`
+    pub filename: Option<String>,
+
+    /// The directory the config was read from, packages in other directories can override it
+    #[serde(skip)]
+    pub dir: Option<PathBuf>,
+}
+
+/// The options that a package in a monorepo can override, read from its own
+/// `.changelog/config.json` file or from the `changelog` field of its `package.json` file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PackageConfig {
+    pub filename: Option<String>,
+    pub sections: Option<Vec<String>>,
+    pub sort: Option<SortOrder>,
+    pub position: Option<Position>,
+    pub release: PackageReleaseOptions,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PackageReleaseOptions {
+    pub tag_format: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            sort: None,
+            sections: [
+                "Added",
+                "Changed",
+                "Deprecated",
+                "Removed",
+                "Fixed",
+                "Security",
+            ]
+            .iter()
+            .map(|section| section.to_string())
+            .collect(),
+            position: Position::Bottom,
+            bom: Bom::Preserve,
+            breaking: Breaking::Prefix,
+            title: TitleOptions::default(),
+            attribution: Attribution::default(),
+            conventional_commits: ConventionalCommits::default(),
+            emoji: Emoji::default(),
+            autolink: true,
+            entry_template: "{title} ({link})".to_string(),
+            release: ReleaseOptions::default(),
+            scope_groups: HashMap::new(),
+            provenance: false,
+            timezone: Timezone::default(),
+            notes_file: None,
+            translations: HashMap::new(),
+            review: false,
+            version_files: vec![],
+            commit_messages: CommitMessages::default(),
+            links: Links::default(),
+            announce: vec![],
+            filename: None,
+            dir: None,
+        }
+    }
+}
+
+impl Config {
+    /// Read the config from the given directory, falls back to the default config if the
+    /// directory doesn't contain a config file.
+    pub fn from_directory(dir: &Path) -> Result<Self> {
+        let config_path = dir.join(CONFIG_PATH);
+
+        if !config_path.exists() {
+            return Ok(Self {
+                dir: Some(dir.to_path_buf()),
+                ..Self::default()
+            });
+        }
+
+        let contents = std::fs::read_to_string(&config_path)?;
+        let mut config = serde_json::from_str::<Self>(&contents)
+            .map_err(|e| eyre!("Invalid config in {}: {}", config_path.display(), e))?;
+        config.dir = Some(dir.to_path_buf());
+
+        if !config.release.heading.starts_with("[{version}]") {
+            return Err(eyre!(
+                "Invalid config in {}: release.heading should start with \"[{{version}}]\"",
+                config_path.display()
+            ));
+        }
+
+        if StrftimeItems::new(&config.release.date_format).any(|item| item == Item::Error) {
+            return Err(eyre!(
+                "Invalid config in {}: release.dateFormat \"{}\" is not a valid date format",
+                config_path.display(),
+                config.release.date_format
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// The config of the package in the given directory, its `PackageConfig` is merged over this
+    /// config. The directory that this config was read from keeps this config as-is.
+    pub fn for_package(&self, dir: &Path) -> Result<Self> {
+        let canonical = |dir: &Path| std::fs::canonicalize(dir).unwrap_or(dir.to_path_buf());
+        if self.dir.as_deref().map(canonical) == Some(canonical(dir)) {
+            return Ok(self.clone());
+        }
+
+        let config_path = dir.join(CONFIG_PATH);
+        let manifest_path = dir.join("package.json");
+        let overrides = match (config_path.exists(), manifest_path.exists()) {
+            (true, _) => {
+                serde_json::from_str::<PackageConfig>(&std::fs::read_to_string(&config_path)?)
+                    .map_err(|e| eyre!("Invalid config in {}: {}", config_path.display(), e))?
+            }
+            (false, true) => {
+                match serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(
+                    &manifest_path,
+                )?)?
+                .get("changelog")
+                {
+                    Some(field) => serde_json::from_value(field.clone()).map_err(|e| {
+                        eyre!(
+                            "Invalid changelog config in {}: {}",
+                            manifest_path.display(),
+                            e
+                        )
+                    })?,
+                    None => return Ok(self.clone()),
+                }
+            }
+            (false, false) => return Ok(self.clone()),
+        };
+
+        Ok(self.with_overrides(overrides))
+    }
+
+    fn with_overrides(&self, overrides: PackageConfig) -> Self {
+        let mut config = self.clone();
+        config.filename = overrides.filename.or(config.filename);
+        config.sections = overrides.sections.unwrap_or(config.sections);
+        config.sort = overrides.sort.or(config.sort);
+        config.position = overrides.position.unwrap_or(config.position);
+        config.release.tag_format = overrides.release.tag_format.or(config.release.tag_format);
+        config
+    }
+
+    /// Render the message of a commit, the changelog message is used when there is no version and
+    /// the package message is used for packages in a monorepo
+    pub fn commit_message(&self, version: Option<&str>, package: Option<&str>) -> String {
+        let template = match (version, package) {
+            (None, _) => &self.commit_messages.changelog,
+            (Some(_), None) => &self.commit_messages.release,
+            (Some(_), Some(_)) => &self.commit_messages.package_release,
+        };
+
+        template
+            .replace("{version}", version.unwrap_or_default())
+            .replace("{package}", package.unwrap_or_default())
+            .replace(
+                "{date}",
+                &self.timezone.today().format("%Y-%m-%d").to_string(),
+            )
+    }
+
+    /// Render the entry for a resource fetched from GitHub. The title is cleaned up, formatted
+    /// according to the entry template and the authors are credited. Returns the section that was
+    /// resolved from the conventional commit prefix, if any, and the entry itself.
+    ///
+    /// The `source` is the link the entry was generated from, and the `command` is the command
+    /// that generated it, which are recorded when `provenance` is enabled.
+    pub fn render_entry(
+        &self,
+        data: &GitHubInfo,
+        source: &str,
+        command: &str,
+    ) -> (Option<String>, String) {
+        let (section, title) = match self.conventional_commits.resolve(data.title()) {
+            Some((section, title)) => (Some(section), title),
+            None => (None, data.title().to_string()),
+        };
+
+        let authors: Vec<String> = data
+            .authors()
+            .iter()
+            .map(|author| format!("@{}", author))
+            .collect();
+
+        let entry = self
+            .entry_template
+            .replace("{title}", &self.title.apply(&title))
+            .replace("{link}", &data.link())
+            .replace("{author}", authors.first().map_or("", |author| author))
+            .replace("{authors}", &conjunction(&authors));
+
+        let entry = self.attribution.apply(entry.trim(), data.authors());
+
+        match self.provenance {
+            true => (section, provenance(&entry, source, command)),
+            false => (section, entry),
+        }
+    }
+
+    /// Expand the scope groups in the given scopes to the packages they contain, other scopes are
+    /// kept as-is.
+    pub fn expand_scopes(&self, scopes: &[String]) -> Vec<String> {
+        let mut expanded: Vec<String> = vec![];
+
+        for scope in scopes {
+            let packages = match self.scope_groups.get(scope) {
+                Some(packages) => packages.clone(),
+                None => vec![scope.clone()],
+            };
+
+            for package in packages {
+                if !expanded.contains(&package) {
+                    expanded.push(package);
+                }
+            }
+        }
+
+        expanded
+    }
+
+    /// The position of a section in the canonical order
+    pub fn section_rank(&self, name: &str) -> usize {
+        self.sections
+            .iter()
+            .position(|section| section.eq_ignore_ascii_case(name))
+            .unwrap_or(self.sections.len())
+    }
+}
+
+/// Append a hidden comment to a generated entry, recording its source, the command that generated
+/// it and when, e.g.: `<!-- changelog: source=https://... command=add generated=2022-03-05T... -->`
+fn provenance(entry: &str, source: &str, command: &str) -> String {
+    let entry = metadata::set(entry, "source", source);
+    let entry = metadata::set(&entry, "command", command);
+
+    metadata::set(
+        &entry,
+        "generated",
+        &Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// Sort entries alphabetically (case insensitive)
+    Alphabetical,
+
+    /// Sort entries by the PR/issue number in their link, entries without a number go last
+    LinkNumber,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alphabetical" => Ok(SortOrder::Alphabetical),
+            "link-number" => Ok(SortOrder::LinkNumber),
+            _ => Err(format!(
+                "Invalid sort order: {}, expected one of \"alphabetical\" or \"link-number\"",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Position {
+    /// Prepend new entries, so that the newest entries come first
+    Top,
+
+    /// Append new entries, so that the newest entries come last
+    Bottom,
+}
+
+impl FromStr for Position {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(Position::Top),
+            "bottom" => Ok(Position::Bottom),
+            _ => Err(format!(
+                "Invalid position: {}, expected one of \"top\" or \"bottom\"",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bom {
+    /// Write a byte order mark only if the original file had one
+    Preserve,
+
+    /// Always write a byte order mark
+    Always,
+
+    /// Never write a byte order mark
+    Never,
+}
+
+pub const BREAKING_PREFIX: &str = "**BREAKING:**";
+pub const BREAKING_SECTION: &str = "Breaking Changes";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Breaking {
+    /// Prefix the entry with `**BREAKING:**`, but keep it in its own section
+    Prefix,
+
+    /// Move the entry to the `Breaking Changes` section
+    Section,
+}
+
+impl Breaking {
+    /// Mark the entries as breaking changes, returns the (possibly updated) section name and the
+    /// updated entries.
+    pub fn apply(&self, section: &str, entries: Vec<String>) -> (String, Vec<String>) {
+        match self {
+            Breaking::Prefix => (
+                section.to_string(),
+                entries
+                    .into_iter()
+                    .map(|entry| format!("{} {}", BREAKING_PREFIX, entry))
+                    .collect(),
+            ),
+            Breaking::Section => (BREAKING_SECTION.to_string(), entries),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TitleOptions {
+    /// Truncate titles that are longer than this amount of characters
+    pub max_length: Option<usize>,
+
+    /// Strip conventional commit prefixes like `fix:` or `feat(scope):`
+    pub strip_conventional_prefix: bool,
+
+    /// Escape characters that have a special meaning in markdown
+    pub escape_markdown: bool,
+
+    /// Collapse consecutive whitespace into a single space
+    pub collapse_whitespace: bool,
+}
+
+impl Default for TitleOptions {
+    fn default() -> Self {
+        Self {
+            max_length: None,
+            strip_conventional_prefix: false,
+            escape_markdown: false,
+            collapse_whitespace: true,
+        }
+    }
+}
+
+impl TitleOptions {
+    pub fn apply(&self, title: &str) -> String {
+        let mut title = title.to_string();
+
+        if self.collapse_whitespace {
+            title = collapse_whitespace(&title);
+        }
+
+        if self.strip_conventional_prefix {
+            title = strip_conventional_prefix(&title);
+        }
+
+        if let Some(max_length) = self.max_length {
+            title = truncate(&title, max_length);
+        }
+
+        if self.escape_markdown {
+            title = escape_markdown(&title);
+        }
+
+        title
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReleaseOptions {
+    /// The heading of a released version, `{version}`, `{date}` and `{name}` are replaced
+    pub heading: String,
+
+    /// The format of the `{date}`, using `strftime` specifiers
+    pub date_format: String,
+
+    /// Sections every release should contain, mapped to the entry that is added when the section
+    /// is empty, e.g.: `{ "Security": "None reported" }`
+    pub sections: HashMap<String, String>,
+
+    /// The name of the release, passed with `--name` instead of the config file
+    #[serde(skip)]
+    pub name: Option<String>,
+
+    /// Allow releasing a version without any entries, passed with `--allow-empty`
+    #[serde(skip)]
+    pub allow_empty: bool,
+
+    /// Sign the release commits and tags with your GPG or SSH key
+    pub sign: bool,
+
+    /// Create annotated tags with the notes of the released version as the message
+    pub annotate: bool,
+
+    /// The name of the git tag of a released version, `{name}` and `{version}` are replaced.
+    /// Defaults to `v{version}`, or `{name}@v{version}` for packages in a monorepo
+    pub tag_format: Option<String>,
+}
+
+impl Default for ReleaseOptions {
+    fn default() -> Self {
+        Self {
+            heading: "[{version}] - {date}".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            sections: HashMap::new(),
+            name: None,
+            allow_empty: false,
+            sign: false,
+            annotate: false,
+            tag_format: None,
+        }
+    }
+}
+
+impl ReleaseOptions {
+    /// Render the heading of a released version
+    pub fn heading(&self, version: &str, date: &NaiveDate) -> String {
+        self.heading
+            .replace("{version}", version)
+            .replace("{date}", &date.format(&self.date_format).to_string())
+            .replace("{name}", self.name.as_deref().unwrap_or_default())
+            .trim()
+            .to_string()
+    }
+
+    /// The version of a git tag that follows the tag format, e.g.: `1.2.3` for `v1.2.3`
+    pub fn version_from_tag<'a>(&self, package: Option<&str>, tag: &'a str) -> Option<&'a str> {
+        let template = self.tag(package, "\0");
+        let (prefix, suffix) = template.split_once('\0')?;
+
+        tag.strip_prefix(prefix)?.strip_suffix(suffix)
+    }
+
+    /// Render the name of the git tag of a released version, the package is only passed for
+    /// packages in a monorepo
+    pub fn tag(&self, package: Option<&str>, version: &str) -> String {
+        match (&self.tag_format, package) {
+            (Some(tag_format), _) => tag_format
+                .replace("{name}", package.unwrap_or_default())
+                .replace("{version}", version),
+            (None, Some(package)) => format!("{}@v{}", package, version),
+            (None, None) => format!("v{}", version),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommitMessages {
+    /// Commits that only update the changelog
+    pub changelog: String,
+
+    /// Commits that release a version
+    pub release: String,
+
+    /// Commits that release a version of a package in a monorepo
+    pub package_release: String,
+}
+
+impl Default for CommitMessages {
+    fn default() -> Self {
+        Self {
+            changelog: "update changelog".to_string(),
+            release: "{version}".to_string(),
+            package_release: "{version} - {package}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Attribution {
+    /// Whether to credit the authors at all
+    pub enabled: bool,
+
+    /// The suffix that is appended to the entry, `{authors}` is replaced with the authors
+    pub template: String,
+
+    /// Authors that should never be credited, e.g.: the maintainers themselves
+    pub exclude: Vec<String>,
+}
+
+impl Default for Attribution {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            template: "Thanks {authors}!".to_string(),
+            exclude: vec![],
+        }
+    }
+}
+
+impl Attribution {
+    /// Append the attribution to the entry, if enabled and there is anyone to credit
+    pub fn apply(&self, entry: &str, authors: &[String]) -> String {
+        let authors: Vec<String> = authors
+            .iter()
+            .filter(|author| !self.exclude.contains(author))
+            .map(|author| format!("@{}", author))
+            .collect();
+
+        if !self.enabled || authors.is_empty() {
+            return entry.to_string();
+        }
+
+        format!(
+            "{} {}",
+            entry,
+            self.template.replace("{authors}", &conjunction(&authors))
+        )
+    }
+}
+
+/// URL templates for the reference links of the versions, `{owner}` and `{repo}` are replaced by
+/// the `origin` remote of the git repository
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Links {
+    /// Compares two refs, `{from}` and `{to}` are replaced by tags (or `HEAD`)
+    pub compare: String,
+
+    /// Shows a single tag, used for the first version. `{tag}` is replaced
+    pub release: String,
+
+    /// Shows the commits, used for the unreleased changes when there is no version yet
+    pub commits: String,
+
+    /// Whether the link of a version compares it with the previous version, or shows its tag
+    pub style: LinkStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkStyle {
+    /// Link to the changes since the previous version, e.g.: `.../compare/v1.0.0...v1.1.0`
+    Compare,
+
+    /// Link to the tag of the version, e.g.: `.../releases/tag/v1.1.0`
+    Tag,
+}
+
+impl Default for Links {
+    fn default() -> Self {
+        Self {
+            compare: "https://github.com/{owner}/{repo}/compare/{from}...{to}".to_string(),
+            release: "https://github.com/{owner}/{repo}/releases/tag/{tag}".to_string(),
+            commits: "https://github.com/{owner}/{repo}/commits/HEAD".to_string(),
+            style: LinkStyle::Compare,
+        }
+    }
+}
+
+impl Links {
+    pub fn compare(&self, repo: &Repo, from: &str, to: &str) -> String {
+        render_link(&self.compare, repo)
+            .replace("{from}", from)
+            .replace("{to}", to)
+    }
+
+    pub fn release(&self, repo: &Repo, tag: &str) -> String {
+        render_link(&self.release, repo).replace("{tag}", tag)
+    }
+
+    /// The link of a released version, according to the link style. The first version always
+    /// links to its tag, there is nothing to compare it with.
+    pub fn version(&self, repo: &Repo, previous: Option<&str>, tag: &str) -> String {
+        match (self.style, previous) {
+            (LinkStyle::Compare, Some(previous)) => self.compare(repo, previous, tag),
+            _ => self.release(repo, tag),
+        }
+    }
+
+    pub fn commits(&self, repo: &Repo) -> String {
+        render_link(&self.commits, repo)
+    }
+}
+
+fn render_link(template: &str, repo: &Repo) -> String {
+    template
+        .replace("{owner}", &repo.org)
+        .replace("{repo}", &repo.repo)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConventionalCommits {
+    /// Whether to use the conventional commit prefix to pick the section
+    pub enabled: bool,
+
+    /// Map of conventional commit types to section names
+    pub sections: HashMap<String, String>,
+}
+
+impl Default for ConventionalCommits {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sections: [
+                ("feat", "Added"),
+                ("fix", "Fixed"),
+                ("perf", "Performance"),
+                ("refactor", "Changed"),
+                ("revert", "Changed"),
+                ("deprecate", "Deprecated"),
+                ("remove", "Removed"),
+                ("security", "Security"),
+            ]
+            .iter()
+            .map(|(kind, section)| (kind.to_string(), section.to_string()))
+            .collect(),
+        }
+    }
+}
+
+impl ConventionalCommits {
+    /// Resolve the section for a title with a known conventional commit prefix, returns the
+    /// section and the title without its prefix.
+    pub fn resolve(&self, title: &str) -> Option<(String, String)> {
+        if !self.enabled {
+            return None;
+        }
+
+        let (kind, rest) = split_conventional_prefix(title)?;
+        let section = self.sections.get(kind)?;
+
+        Some((section.to_string(), uppercase_first(rest)))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Emoji {
+    /// Map of section names to emoji, e.g.: `{ "Added": "✨", "Fixed": "🐛" }`
+    pub sections: HashMap<String, String>,
+
+    /// Where the emoji is placed
+    pub placement: EmojiPlacement,
+}
+
+impl Emoji {
+    /// The emoji for the given section, if any
+    pub fn for_section(&self, section: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(section))
+            .map(|(_, emoji)| emoji.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmojiPlacement {
+    /// Prefix every entry, e.g.: `- ✨ New feature`
+    #[default]
+    Entry,
+
+    /// Prefix the section heading, e.g.: `### ✨ Added`
+    Heading,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_annotate_entries_with_their_provenance() {
+        let entry = provenance(
+            "Fix a bug ([#1](https://github.com/a/b/pull/1))",
+            "https://github.com/a/b/pull/1",
+            "fix",
+        );
+
+        assert!(entry.starts_with(
+            "Fix a bug ([#1](https://github.com/a/b/pull/1)) <!-- changelog: source=https://github.com/a/b/pull/1 command=fix generated="
+        ));
+        assert!(entry.ends_with("Z -->"));
+    }
+
+    #[test]
+    fn it_should_expand_scope_groups() {
+        let config = Config {
+            scope_groups: [(
+                "frontend".to_string(),
+                vec!["@acme/ui".to_string(), "@acme/icons".to_string()],
+            )]
+            .into_iter()
+            .collect(),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.expand_scopes(&[
+                "frontend".to_string(),
+                "@acme/ui".to_string(),
+                "@acme/api".to_string()
+            ]),
+            vec!["@acme/ui", "@acme/icons", "@acme/api"]
+        );
+    }
+
+    #[test]
+    fn it_should_render_reference_links() {
+        let repo = Repo::new("group/subgroup".to_string(), "widget".to_string());
+        let links = Links {
+            compare: "https://gitlab.acme.com/{owner}/{repo}/-/compare/{from}...{to}".to_string(),
+            release: "https://gitlab.acme.com/{owner}/{repo}/-/tags/{tag}".to_string(),
+            ..Links::default()
+        };
+
+        assert_eq!(
+            links.compare(&repo, "v1.0.0", "HEAD"),
+            "https://gitlab.acme.com/group/subgroup/widget/-/compare/v1.0.0...HEAD"
+        );
+        assert_eq!(
+            links.release(&repo, "v1.0.0"),
+            "https://gitlab.acme.com/group/subgroup/widget/-/tags/v1.0.0"
+        );
+        assert_eq!(
+            links.version(&repo, Some("v1.0.0"), "v1.1.0"),
+            "https://gitlab.acme.com/group/subgroup/widget/-/compare/v1.0.0...v1.1.0"
+        );
+        assert_eq!(
+            Links {
+                style: LinkStyle::Tag,
+                ..links
+            }
+            .version(&repo, Some("v1.0.0"), "v1.1.0"),
+            "https://gitlab.acme.com/group/subgroup/widget/-/tags/v1.1.0"
+        );
+        assert_eq!(
+            Links::default().commits(&repo),
+            "https://github.com/group/subgroup/widget/commits/HEAD"
+        );
+    }
+
+    #[test]
+    fn it_should_render_the_release_heading() {
+        let date = NaiveDate::from_ymd(2022, 3, 5);
+        let mut release = ReleaseOptions::default();
+        assert_eq!(release.heading("1.2.3", &date), "[1.2.3] - 2022-03-05");
+
+        release.heading = "[{version}] - {date} {name}".to_string();
+        release.date_format = "%d.%m.%Y".to_string();
+        assert_eq!(release.heading("1.2.3", &date), "[1.2.3] - 05.03.2022");
+
+        release.name = Some("\"Codename\"".to_string());
+        assert_eq!(
+            release.heading("1.2.3", &date),
+            "[1.2.3] - 05.03.2022 \"Codename\""
+        );
+
+        release.heading = "[{version}]".to_string();
+        assert_eq!(release.heading("1.2.3", &date), "[1.2.3]");
+    }
+
+    #[test]
+    fn it_should_render_commit_messages() {
+        let mut config = Config::default();
+        assert_eq!(config.commit_message(None, None), "update changelog");
+        assert_eq!(config.commit_message(Some("1.2.3"), None), "1.2.3");
+        assert_eq!(
+            config.commit_message(Some("1.2.3"), Some("@acme/ui")),
+            "1.2.3 - @acme/ui"
+        );
+
+        config.commit_messages.release = "chore(release): v{version}".to_string();
+        assert_eq!(
+            config.commit_message(Some("1.2.3"), None),
+            "chore(release): v1.2.3"
+        );
+    }
+
+    #[test]
+    fn it_should_merge_package_overrides_over_the_root_config() {
+        let overrides: PackageConfig = serde_json::from_str(
+            r#"{ "filename": "HISTORY.md", "sections": ["Features", "Fixes"], "release": { "tagFormat": "{name}-{version}" } }"#,
+        )
+        .unwrap();
+        let root = Config {
+            sort: Some(SortOrder::Alphabetical),
+            ..Config::default()
+        };
+        let config = root.with_overrides(overrides);
+
+        assert_eq!(config.filename.as_deref(), Some("HISTORY.md"));
+        assert_eq!(config.sections, vec!["Features", "Fixes"]);
+        assert_eq!(config.sort, Some(SortOrder::Alphabetical));
+        assert_eq!(config.release.tag(Some("widget"), "1.0.0"), "widget-1.0.0");
+    }
+
+    #[test]
+    fn it_should_render_the_tag_name() {
+        let mut release = ReleaseOptions::default();
+        assert_eq!(release.tag(None, "1.2.3"), "v1.2.3");
+        assert_eq!(release.tag(Some("@acme/ui"), "1.2.3"), "@acme/ui@v1.2.3");
+
+        release.tag_format = Some("release-{version}".to_string());
+        assert_eq!(release.tag(None, "1.2.3"), "release-1.2.3");
+
+        release.tag_format = Some("{name}-{version}".to_string());
+        assert_eq!(release.tag(Some("widget"), "1.2.3"), "widget-1.2.3");
+
+        release.tag_format = None;
+        assert_eq!(release.version_from_tag(None, "v1.2.3"), Some("1.2.3"));
+        assert_eq!(release.version_from_tag(None, "nightly"), None);
+    }
+}
@@ -0,0 +1,229 @@
+use chrono::{Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// The timezone used to determine the date of a release, so that the date doesn't depend on who
+/// cuts the release.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Timezone {
+    /// The timezone of the machine that runs the command
+    #[default]
+    Local,
+
+    /// Coordinated Universal Time
+    Utc,
+
+    /// A named timezone from the IANA database, e.g.: `Europe/Brussels`
+    Named(Tz),
+}
+
+impl Timezone {
+    /// The current date in this timezone
+    pub fn today(&self) -> NaiveDate {
+        match self {
+            Timezone::Local => Local::now().date_naive(),
+            Timezone::Utc => Utc::now().date_naive(),
+            Timezone::Named(tz) => Utc::now().with_timezone(tz).date_naive(),
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Timezone::Local),
+            "utc" => Ok(Timezone::Utc),
+            _ => s.parse::<Tz>().map(Timezone::Named).map_err(|_| {
+                format!(
+                    "Invalid timezone: {}, expected one of \"local\", \"utc\" or a named timezone like \"Europe/Brussels\"",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Timezone {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Hint used to disambiguate numeric dates like `05/03/2021`, which is the 5th of March in most
+/// of the world, but the 3rd of May in the US.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateLocale {
+    /// Day first, e.g.: `05/03/2021` is the 5th of March
+    DayFirst,
+
+    /// Month first, e.g.: `05/03/2021` is the 3rd of May
+    MonthFirst,
+}
+
+impl FromStr for DateLocale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dmy" | "day-first" | "eu" => Ok(DateLocale::DayFirst),
+            "mdy" | "month-first" | "us" => Ok(DateLocale::MonthFirst),
+            _ => Err(format!(
+                "Invalid date locale: {}, expected one of \"dmy\" or \"mdy\"",
+                s
+            )),
+        }
+    }
+}
+
+/// Leniently parse a date in one of the commonly used (non-ISO) formats.
+pub fn parse_date(input: &str, locale: DateLocale) -> Option<NaiveDate> {
+    let input = strip_ordinals(input.trim().trim_end_matches('.'));
+
+    let numeric = match locale {
+        DateLocale::DayFirst => ["%d/%m/%Y", "%d.%m.%Y", "%d-%m-%Y"],
+        DateLocale::MonthFirst => ["%m/%d/%Y", "%m.%d.%Y", "%m-%d-%Y"],
+    };
+
+    ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"]
+        .iter()
+        .chain(numeric.iter())
+        .chain(
+            [
+                "%B %d, %Y",
+                "%B %d %Y",
+                "%d %B %Y",
+                "%d %B, %Y",
+                "%b %d, %Y",
+                "%b %d %Y",
+                "%d %b %Y",
+            ]
+            .iter(),
+        )
+        .find_map(|format| NaiveDate::parse_from_str(&input, format).ok())
+}
+
+/// Rewrite a version heading like `[1.0.0] - March 5, 2021` or `1.0.0 (05/03/2021)` into
+/// `[1.0.0] - 2021-03-05`. Headings without a recognizable date are returned as-is.
+pub fn normalize_heading(heading: &str, locale: DateLocale) -> String {
+    let heading = heading.trim();
+
+    let (version, rest) = match heading.starts_with('[') {
+        true => match heading.find(']') {
+            Some(idx) => heading.split_at(idx + 1),
+            None => return heading.to_string(),
+        },
+        false => match heading.split_once(char::is_whitespace) {
+            Some((version, rest)) => (version, rest),
+            None => return heading.to_string(),
+        },
+    };
+
+    let date = rest
+        .trim()
+        .trim_start_matches(['-', '–', '—', '('])
+        .trim_end_matches(')')
+        .trim();
+
+    match parse_date(date, locale) {
+        Some(date) => format!("{} - {}", version, date.format("%Y-%m-%d")),
+        None => heading.to_string(),
+    }
+}
+
+/// Remove ordinal suffixes (`1st`, `2nd`, `3rd`, `4th`) so that chrono can parse the day.
+fn strip_ordinals(input: &str) -> String {
+    input
+        .split(' ')
+        .map(|word| {
+            let (digits, suffix) = word.split_at(
+                word.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(word.len()),
+            );
+            let suffix_lower = suffix.trim_end_matches(',').to_lowercase();
+
+            if !digits.is_empty() && ["st", "nd", "rd", "th"].contains(&suffix_lower.as_str()) {
+                format!("{}{}", digits, &suffix[2..])
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_written_dates() {
+        let expected = NaiveDate::from_ymd(2021, 3, 5);
+
+        for input in [
+            "March 5, 2021",
+            "March 5th, 2021",
+            "Mar 5 2021",
+            "5 March 2021",
+            "5th March 2021",
+            "2021-03-05",
+            "2021/03/05",
+        ] {
+            assert_eq!(
+                parse_date(input, DateLocale::DayFirst),
+                Some(expected),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_use_the_locale_hint_for_numeric_dates() {
+        assert_eq!(
+            parse_date("05/03/2021", DateLocale::DayFirst),
+            Some(NaiveDate::from_ymd(2021, 3, 5))
+        );
+        assert_eq!(
+            parse_date("05/03/2021", DateLocale::MonthFirst),
+            Some(NaiveDate::from_ymd(2021, 5, 3))
+        );
+    }
+
+    #[test]
+    fn it_should_parse_timezones() {
+        assert_eq!("utc".parse::<Timezone>(), Ok(Timezone::Utc));
+        assert_eq!("Local".parse::<Timezone>(), Ok(Timezone::Local));
+        assert_eq!(
+            "Europe/Brussels".parse::<Timezone>(),
+            Ok(Timezone::Named(chrono_tz::Europe::Brussels))
+        );
+        assert!("Mars/Olympus_Mons".parse::<Timezone>().is_err());
+    }
+
+    #[test]
+    fn it_should_normalize_version_headings() {
+        assert_eq!(
+            normalize_heading("[1.0.0] - March 5, 2021", DateLocale::DayFirst),
+            "[1.0.0] - 2021-03-05"
+        );
+        assert_eq!(
+            normalize_heading("1.0.0 (05.03.2021)", DateLocale::DayFirst),
+            "1.0.0 - 2021-03-05"
+        );
+        assert_eq!(
+            normalize_heading("[Unreleased]", DateLocale::DayFirst),
+            "[Unreleased]"
+        );
+        assert_eq!(
+            normalize_heading("[1.0.0] - 2021-03-05", DateLocale::DayFirst),
+            "[1.0.0] - 2021-03-05"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use colored::*;
+use std::fmt::Display;
+
+/// A single line level difference between two (rendered) changelog sections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diff {
+    /// Only exists in the new version
+    Added(String),
+
+    /// Only exists in the old version
+    Removed(String),
+
+    /// Exists in both versions, but in a different place
+    Moved(String),
+}
+
+/// Compare two rendered sections line by line. Blank lines and surrounding whitespace are ignored
+/// so that only meaningful drift is reported.
+pub fn diff_lines(old: &str, new: &str) -> Vec<Diff> {
+    let normalize = |contents: &str| -> Vec<String> {
+        contents
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect()
+    };
+
+    let (old, new) = (normalize(old), normalize(new));
+
+    old.iter()
+        .filter(|line| !new.contains(line))
+        .map(|line| Diff::Removed(line.to_string()))
+        .chain(
+            new.iter()
+                .filter(|line| !old.contains(line))
+                .map(|line| Diff::Added(line.to_string())),
+        )
+        .collect()
+}
+
+/// A single entry of a changelog, together with the version and section it belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub version: String,
+    pub section: String,
+    pub text: String,
+}
+
+impl Entry {
+    fn location(&self) -> String {
+        match self.section.is_empty() {
+            true => self.version.clone(),
+            false => format!("{} › {}", self.version, self.section),
+        }
+    }
+}
+
+/// Compare two sets of changelog entries. Entries that exist in both sets but in a different
+/// version or section are reported as moved.
+pub fn diff_entries(old: &[Entry], new: &[Entry]) -> Vec<Diff> {
+    let mut diffs = vec![];
+
+    for entry in old {
+        if new.contains(entry) {
+            continue;
+        }
+
+        match new.iter().find(|other| other.text == entry.text) {
+            Some(other) => diffs.push(Diff::Moved(format!(
+                "{} ({} → {})",
+                entry.text,
+                entry.location(),
+                other.location()
+            ))),
+            None => diffs.push(Diff::Removed(format!(
+                "{} ({})",
+                entry.text,
+                entry.location()
+            ))),
+        }
+    }
+
+    for entry in new {
+        if !old.iter().any(|other| other.text == entry.text) {
+            diffs.push(Diff::Added(format!(
+                "{} ({})",
+                entry.text,
+                entry.location()
+            )));
+        }
+    }
+
+    diffs
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diff::Added(line) => write!(f, "{}", format!("+ {}", line).green()),
+            Diff::Removed(line) => write!(f, "{}", format!("- {}", line).red()),
+            Diff::Moved(line) => write!(f, "{}", format!("~ {}", line).yellow()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: &str, section: &str, text: &str) -> Entry {
+        Entry {
+            version: version.to_string(),
+            section: section.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_should_diff_entries_semantically() {
+        let old = vec![
+            entry("Unreleased", "Added", "Feature A"),
+            entry("Unreleased", "Added", "Feature B"),
+            entry("Unreleased", "Fixed", "Bug A"),
+        ];
+        let new = vec![
+            entry("Unreleased", "Added", "Feature A"),
+            entry("Unreleased", "Fixed", "Feature B"),
+            entry("Unreleased", "Fixed", "Bug B"),
+        ];
+
+        assert_eq!(
+            diff_entries(&old, &new),
+            vec![
+                Diff::Moved("Feature B (Unreleased › Added → Unreleased › Fixed)".to_string()),
+                Diff::Removed("Bug A (Unreleased › Fixed)".to_string()),
+                Diff::Added("Bug B (Unreleased › Fixed)".to_string()),
+            ]
+        );
+    }
+}
@@ -2,9 +2,23 @@ use color_eyre::eyre::{eyre, Result};
 use std::path::PathBuf;
 use std::process::Command;
 
+/// Extra options for the commits that are created
+#[derive(Debug, Clone, Default)]
+pub struct CommitOptions {
+    /// Skip the pre-commit and commit-msg hooks
+    pub no_verify: bool,
+
+    /// Add a `Signed-off-by` trailer
+    pub signoff: bool,
+
+    /// Extra arguments that are passed to `git commit` as-is
+    pub args: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct Git {
     pwd: PathBuf,
+    sign: bool,
 }
 
 impl Git {
@@ -14,9 +28,16 @@ impl Git {
                 Some(pwd) => pwd.to_path_buf(),
                 None => std::env::current_dir()?,
             },
+            sign: false,
         })
     }
 
+    /// Sign commits and tags with the configured GPG or SSH key (`user.signingkey`)
+    pub fn with_signing(mut self, sign: bool) -> Self {
+        self.sign = sign;
+        self
+    }
+
     pub fn long_hash(&self, hash: &str) -> Result<String> {
         self.exec(vec!["log", "-1", "--format=%H", hash])
     }
@@ -33,6 +54,128 @@ impl Git {
             })
     }
 
+    /// Get the contents of a file at a specific ref, the path is relative to the current working
+    /// directory.
+    pub fn show(&self, git_ref: &str, path: &str) -> Result<String> {
+        let object = format!("{}:./{}", git_ref, path);
+        if self
+            .exec(vec!["rev-parse", "--verify", "--quiet", &object])
+            .map_or(true, |hash| hash.is_empty())
+        {
+            return Err(eyre!("Couldn't find {} at {}", path, git_ref));
+        }
+
+        self.exec(vec!["show", &object])
+    }
+
+    /// The most recent tag reachable from HEAD, optionally only the tags matching the pattern
+    pub fn latest_tag(&self, pattern: Option<&str>) -> Option<String> {
+        let mut args = vec!["describe", "--tags", "--abbrev=0"];
+        if let Some(pattern) = pattern {
+            args.extend(["--match", pattern]);
+        }
+
+        self.exec(args).ok().filter(|tag| !tag.is_empty())
+    }
+
+    /// The hashes and subjects of the commits since the given ref that touch the current working
+    /// directory, newest first. All commits are used when there is no ref.
+    pub fn commits_since(&self, since: Option<&str>) -> Result<Vec<(String, String)>> {
+        self.commits_between(since, "HEAD")
+    }
+
+    /// Same as `commits_since`, but up to (and including) the given ref instead of HEAD
+    pub fn commits_between(
+        &self,
+        since: Option<&str>,
+        until: &str,
+    ) -> Result<Vec<(String, String)>> {
+        let range = match since {
+            Some(since) => format!("{}..{}", since, until),
+            None => until.to_string(),
+        };
+
+        Ok(self
+            .exec(vec!["log", "--format=%H%x09%s", &range, "--", "."])?
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(hash, subject)| (hash.to_string(), subject.to_string()))
+            .collect())
+    }
+
+    /// The full messages of the commits that are reachable from `until` but not from `since`
+    pub fn messages_between(&self, since: &str, until: &str) -> Result<Vec<String>> {
+        Ok(self
+            .exec(vec![
+                "log",
+                "--format=%B%x00",
+                &format!("{}..{}", since, until),
+            ])?
+            .split('\0')
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty())
+            .collect())
+    }
+
+    /// All tags in the repository
+    pub fn tags(&self) -> Result<Vec<String>> {
+        Ok(self
+            .exec(vec!["tag", "--list"])?
+            .lines()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect())
+    }
+
+    /// The date (`YYYY-MM-DD`) of the commit a ref points to
+    pub fn date(&self, git_ref: &str) -> Result<String> {
+        self.exec(vec!["log", "-1", "--format=%cs", git_ref])
+    }
+
+    /// Read a value from the git config, e.g.: `user.name`
+    pub fn config(&self, key: &str) -> Option<String> {
+        self.exec(vec!["config", key])
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Write a value to the git config of the repository, it is never shared with others
+    pub fn set_config(&self, key: &str, value: &str) -> Result<&Self> {
+        self.exec(vec!["config", "--local", key, value])?;
+        Ok(self)
+    }
+
+    /// The paths (relative to the root of the repository) of the tracked files with uncommitted
+    /// changes, staged or not
+    pub fn changed_files(&self) -> Result<Vec<String>> {
+        Ok(self
+            .exec(vec!["status", "--porcelain", "--untracked-files=no"])?
+            .lines()
+            .filter_map(|line| line.trim_start().split_once(' '))
+            .map(|(_, path)| {
+                // Renames are formatted as `R  old -> new`
+                let path = path.trim_start();
+                path.rsplit_once(" -> ")
+                    .map(|(_, path)| path)
+                    .unwrap_or(path)
+                    .to_string()
+            })
+            .collect())
+    }
+
+    /// Whether the working tree is clean, ignoring the changed files that match the predicate
+    pub fn is_clean(&self, ignore: impl Fn(&str) -> bool) -> Result<bool> {
+        Ok(self.changed_files()?.iter().all(|path| ignore(path)))
+    }
+
+    /// The directory that git runs the hooks from, this respects the `core.hooksPath` config
+    pub fn hooks_dir(&self) -> Result<PathBuf> {
+        match self.exec(vec!["rev-parse", "--git-path", "hooks"])? {
+            dir if dir.is_empty() => Err(eyre!("Not a git repository")),
+            dir => Ok(self.pwd.join(dir)),
+        }
+    }
+
     pub fn is_git_repo(&self) -> bool {
         self.exec(vec!["rev-parse", "--is-inside-work-tree"])
             .map(|output| output.trim() == "true")
@@ -44,34 +187,118 @@ impl Git {
         Ok(self)
     }
 
-    pub fn tag(&self, path: &str) -> Result<&Self> {
-        self.exec(vec!["tag", path])?;
+    /// Create a tag, the notes are used as the body of an annotated tag. Signed tags are always
+    /// annotated, with the name of the tag as the message when there are no notes.
+    pub fn tag(&self, path: &str, notes: Option<&str>) -> Result<&Self> {
+        if notes.is_none() && !self.sign {
+            self.exec(vec!["tag", path])?;
+            return Ok(self);
+        }
+
+        let message = match notes {
+            Some(notes) => format!("{}\n\n{}", path, notes.trim()),
+            None => path.to_string(),
+        };
+
+        // The notes contain markdown headings, which git would strip as comments otherwise
+        self.exec(vec![
+            "tag",
+            if self.sign { "-s" } else { "-a" },
+            "--cleanup=whitespace",
+            path,
+            "-m",
+            &message,
+        ])?;
         Ok(self)
     }
 
-    pub fn commit(&self, msg: &str) -> Result<&Self> {
-        self.exec(vec!["commit", "-m", msg])?;
+    pub fn commit(&self, msg: &str, options: &CommitOptions) -> Result<&Self> {
+        let mut args = vec!["commit", "-m", msg];
+
+        if self.sign {
+            args.push("-S");
+        }
+
+        if options.no_verify {
+            args.push("--no-verify");
+        }
+
+        if options.signoff {
+            args.push("--signoff");
+        }
+
+        args.extend(options.args.iter().map(|arg| arg.as_str()));
+
+        self.exec(args)?;
         Ok(self)
     }
 
+    /// Run git, a failing command (e.g.: a rejecting hook or a missing signing key) is an error
+    /// that includes its stderr
     pub fn exec(&self, args: Vec<&str>) -> Result<String> {
         let mut cmd = Command::new("git");
 
         cmd.current_dir(&self.pwd);
 
-        for arg in args {
+        for arg in &args {
             cmd.arg(arg);
         }
 
         match cmd.output() {
-            Ok(output) => {
+            Ok(output) if output.status.success() => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 let stdout = stdout.trim();
                 let stdout = stdout.to_string();
 
                 Ok(stdout)
             }
+            Ok(output) => Err(eyre!(
+                "`git {}` failed:\n{}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
             Err(e) => Err(eyre!(e)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn it_should_fail_when_a_hook_rejects_the_commit() {
+        let dir = std::env::temp_dir().join(format!("changelog-git-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let git = Git::new(Some(&dir)).unwrap();
+        git.exec(vec!["init", "--quiet"]).unwrap();
+        git.set_config("user.name", "Changelog").unwrap();
+        git.set_config("user.email", "changelog@example.com")
+            .unwrap();
+        git.set_config("commit.gpgsign", "false").unwrap();
+
+        let hook = git.hooks_dir().unwrap().join("pre-commit");
+        fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        fs::write(&hook, "#!/bin/sh\necho 'Changelog rejected' >&2\nexit 1\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+        fs::write(dir.join("CHANGELOG.md"), "# Changelog\n").unwrap();
+        git.add("CHANGELOG.md").unwrap();
+
+        let error = git
+            .commit("update changelog", &CommitOptions::default())
+            .unwrap_err();
+        assert!(error.to_string().contains("Changelog rejected"));
+
+        let options = CommitOptions {
+            no_verify: true,
+            ..CommitOptions::default()
+        };
+        assert!(git.commit("update changelog", &options).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
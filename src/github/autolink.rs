@@ -0,0 +1,105 @@
+use crate::github::repo::Repo;
+
+/// Rewrite bare issue references like `#123` or `owner/repo#456` to markdown links. References
+/// that are already part of a link or a code span are kept as-is.
+pub fn autolink(text: &str, repo: &Repo) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+
+    let mut in_code = false;
+    let mut in_label = 0;
+    let mut in_url = false;
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+
+        match c {
+            '`' if !in_url => in_code = !in_code,
+            '[' if !in_code && !in_url => in_label += 1,
+            ']' if !in_code && !in_url && in_label > 0 => {
+                in_label -= 1;
+                if chars.get(idx + 1) == Some(&'(') {
+                    in_url = true;
+                }
+            }
+            ')' if in_url => in_url = false,
+            '#' if !in_code && !in_url && in_label == 0 => {
+                let number: String = chars[idx + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                let next = chars.get(idx + 1 + number.len());
+
+                if !number.is_empty() && next.is_none_or(|c| !c.is_alphanumeric()) {
+                    // Look back for an `owner/repo` prefix
+                    let word_start = result
+                        .rfind(|c: char| c.is_whitespace() || c == '(')
+                        .map(|idx| idx + 1)
+                        .unwrap_or(0);
+                    let word = result[word_start..].to_string();
+
+                    let (owner, name) = match word.split_once('/') {
+                        Some((owner, name)) if is_name(owner) && is_name(name) => {
+                            (owner.to_string(), name.to_string())
+                        }
+                        _ if word.is_empty() => (repo.org.clone(), repo.repo.clone()),
+                        _ => {
+                            result.push(c);
+                            idx += 1;
+                            continue;
+                        }
+                    };
+
+                    result.truncate(word_start);
+                    result.push_str(&format!(
+                        "[{}#{}](https://github.com/{}/{}/issues/{})",
+                        word, number, owner, name, number
+                    ));
+
+                    idx += 1 + number.len();
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        result.push(c);
+        idx += 1;
+    }
+
+    result
+}
+
+fn is_name(part: &str) -> bool {
+    !part.is_empty()
+        && part
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_link_bare_issue_references() {
+        let repo = Repo::new("acme".to_string(), "widget".to_string());
+
+        assert_eq!(
+            autolink("Fix crash (#12) and tailwindlabs/tailwindcss#34", &repo),
+            "Fix crash ([#12](https://github.com/acme/widget/issues/12)) and [tailwindlabs/tailwindcss#34](https://github.com/tailwindlabs/tailwindcss/issues/34)"
+        );
+        assert_eq!(
+            autolink(
+                "Already linked ([#12](https://github.com/acme/widget/pull/12)) and `#34`",
+                &repo
+            ),
+            "Already linked ([#12](https://github.com/acme/widget/pull/12)) and `#34`"
+        );
+        assert_eq!(
+            autolink("Not an issue: C#8 or #abc", &repo),
+            "Not an issue: C#8 or #abc"
+        );
+    }
+}
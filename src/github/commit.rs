@@ -11,7 +11,7 @@ use std::str::FromStr;
 pub struct Commit {
     hash: String,
     short_hash: String,
-    title: String,
+    pub title: String,
     repo: Repo,
 }
 
@@ -34,15 +34,19 @@ impl Commit {
             repo,
         })
     }
+
+    /// The markdown link to the commit, e.g.: `[abc1234](https://github.com/...)`
+    pub fn link(&self) -> String {
+        format!(
+            "[{}](https://github.com/{}/{}/commit/{})",
+            self.short_hash, self.repo.org, self.repo.repo, self.hash
+        )
+    }
 }
 
 impl Display for Commit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([{}](https://github.com/{}/{}/commit/{}))",
-            self.title, self.short_hash, self.repo.org, self.repo.repo, self.hash
-        )
+        write!(f, "{} ({})", self.title, self.link())
     }
 }
 
@@ -65,12 +69,13 @@ impl FromStr for Commit {
 
                 let json = graphql(data)?;
 
+                let not_found = || format!("Couldn't find commit {} in {}", commit, url.repo);
                 let title = json["data"]["repository"]["object"]["title"]
                     .as_str()
-                    .unwrap();
+                    .ok_or_else(not_found)?;
                 let short_hash = json["data"]["repository"]["object"]["short_hash"]
                     .as_str()
-                    .unwrap();
+                    .ok_or_else(not_found)?;
 
                 Ok(Self {
                     hash: commit.to_string(),
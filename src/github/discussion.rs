@@ -7,17 +7,23 @@ use std::str::FromStr;
 #[derive(Debug)]
 pub struct Discussion {
     number: usize,
-    title: String,
+    pub title: String,
     repo: Repo,
 }
 
+impl Discussion {
+    /// The markdown link to the discussion, e.g.: `[#123](https://github.com/...)`
+    pub fn link(&self) -> String {
+        format!(
+            "[#{}](https://github.com/{}/{}/discussions/{})",
+            self.number, self.repo.org, self.repo.repo, self.number
+        )
+    }
+}
+
 impl Display for Discussion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([#{}](https://github.com/{}/{}/discussions/{}))",
-            self.title, self.number, self.repo.org, self.repo.repo, self.number
-        )
+        write!(f, "{} ({})", self.title, self.link())
     }
 }
 
@@ -47,7 +53,7 @@ impl FromStr for Discussion {
 
         let title = json["data"]["repository"]["discussion"]["title"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| format!("Couldn't find discussion #{} in {}", discussion, url.repo))?;
 
         Ok(Self {
             number: discussion,
@@ -1,6 +1,7 @@
 use crate::github::{
     commit::Commit, discussion::Discussion, issue::Issue, pull_request::PullRequest,
 };
+use crate::graphql::validate_token;
 use std::fmt::Display;
 use std::str::FromStr;
 
@@ -12,6 +13,35 @@ pub enum GitHubInfo {
     Discussion(Discussion),
 }
 
+impl GitHubInfo {
+    /// The GitHub usernames of the people who authored the resource, if known
+    pub fn authors(&self) -> &[String] {
+        match self {
+            GitHubInfo::PullRequest(pr) => &pr.authors,
+            _ => &[],
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        match self {
+            GitHubInfo::PullRequest(pr) => &pr.title,
+            GitHubInfo::Commit(commit) => &commit.title,
+            GitHubInfo::Issue(issue) => &issue.title,
+            GitHubInfo::Discussion(discussion) => &discussion.title,
+        }
+    }
+
+    /// The markdown link to the resource, e.g.: `[#123](https://github.com/...)`
+    pub fn link(&self) -> String {
+        match self {
+            GitHubInfo::PullRequest(pr) => pr.link(),
+            GitHubInfo::Commit(commit) => commit.link(),
+            GitHubInfo::Issue(issue) => issue.link(),
+            GitHubInfo::Discussion(discussion) => discussion.link(),
+        }
+    }
+}
+
 impl Display for GitHubInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -31,6 +61,12 @@ impl FromStr for GitHubInfo {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains("/discussion/") || s.contains("/discussions/") {
+            validate_token(&["read:discussion"], "discussion links")?;
+        } else if s.contains("github.com") {
+            validate_token(&[], "GitHub links")?;
+        }
+
         if s.contains("/commit/") || s.contains("/commits/") {
             return Ok(GitHubInfo::Commit(s.parse()?));
         }
@@ -8,17 +8,23 @@ use std::str::FromStr;
 #[derive(Debug)]
 pub struct Issue {
     number: usize,
-    title: String,
+    pub title: String,
     repo: Repo,
 }
 
+impl Issue {
+    /// The markdown link to the issue, e.g.: `[#123](https://github.com/...)`
+    pub fn link(&self) -> String {
+        format!(
+            "[#{}](https://github.com/{}/{}/issues/{})",
+            self.number, self.repo.org, self.repo.repo, self.number
+        )
+    }
+}
+
 impl Display for Issue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([#{}](https://github.com/{}/{}/issues/{}))",
-            self.title, self.number, self.repo.org, self.repo.repo, self.number
-        )
+        write!(f, "{} ({})", self.title, self.link())
     }
 }
 
@@ -48,7 +54,7 @@ impl FromStr for Issue {
 
         let title = json["data"]["repository"]["issue"]["title"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| format!("Couldn't find issue #{} in {}", issue, url.repo))?;
 
         Ok(Self {
             number: issue,
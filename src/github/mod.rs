@@ -1,7 +1,9 @@
+pub mod autolink;
 pub mod commit;
 pub mod discussion;
 pub mod github_info;
 pub mod github_url;
 pub mod issue;
 pub mod pull_request;
+pub mod release;
 pub mod repo;
@@ -8,17 +8,24 @@ use std::str::FromStr;
 #[derive(Debug)]
 pub struct PullRequest {
     number: usize,
-    title: String,
+    pub title: String,
+    pub authors: Vec<String>,
     repo: Repo,
 }
 
+impl PullRequest {
+    /// The markdown link to the pull request, e.g.: `[#123](https://github.com/...)`
+    pub fn link(&self) -> String {
+        format!(
+            "[#{}](https://github.com/{}/{}/pull/{})",
+            self.number, self.repo.org, self.repo.repo, self.number
+        )
+    }
+}
+
 impl Display for PullRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ([#{}](https://github.com/{}/{}/pull/{}))",
-            self.title, self.number, self.repo.org, self.repo.repo, self.number
-        )
+        write!(f, "{} ({})", self.title, self.link())
     }
 }
 
@@ -46,13 +53,38 @@ impl FromStr for PullRequest {
 
         let json = graphql(data)?;
 
-        let title = json["data"]["repository"]["pullRequest"]["title"]
+        let pr = &json["data"]["repository"]["pullRequest"];
+        let title = pr["title"]
             .as_str()
-            .unwrap();
+            .ok_or_else(|| format!("Couldn't find pull request #{} in {}", pull, url.repo))?;
+
+        // The author of the PR first, followed by the co-authors of its commits
+        let mut authors: Vec<String> = vec![];
+        let co_authors = pr["commits"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|node| {
+                node["commit"]["authors"]["nodes"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+            })
+            .map(|author| &author["user"]["login"]);
+
+        for login in std::iter::once(&pr["author"]["login"])
+            .chain(co_authors)
+            .filter_map(|login| login.as_str())
+        {
+            if !login.ends_with("[bot]") && !authors.iter().any(|author| author == login) {
+                authors.push(login.to_string());
+            }
+        }
 
         Ok(Self {
             number: pull,
             title: title.to_string(),
+            authors,
             repo: url.repo,
         })
     }
@@ -0,0 +1,152 @@
+use crate::github::repo::Repo;
+use crate::graphql::{graphql, rest, validate_token};
+use reqwest::Method;
+use serde_json::json;
+use std::fmt::Debug;
+
+#[derive(Debug)]
+pub struct Release {
+    pub body: String,
+}
+
+/// What happened to a GitHub Release when syncing it
+#[derive(Debug, PartialEq, Eq)]
+pub enum Sync {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// A published GitHub Release
+#[derive(Debug)]
+pub struct PublishedRelease {
+    pub tag: String,
+
+    /// The date it was published on, e.g.: `2021-03-05`
+    pub date: String,
+
+    pub body: String,
+}
+
+impl Release {
+    /// Fetch the GitHub Release for the given tag, if it exists.
+    pub fn from_tag(repo: &Repo, tag: &str) -> Result<Option<Self>, String> {
+        let data = json!({
+            "query": include_str!("./graphql/release-info/query.graphql"),
+            "variables": {
+                "org": repo.org,
+                "repo": repo.repo,
+                "tag": tag
+            }
+        });
+
+        let json = graphql(data)?;
+        let release = &json["data"]["repository"]["release"];
+
+        if release.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            body: release["description"]
+                .as_str()
+                .unwrap_or_default()
+                .replace("\r\n", "\n"),
+        }))
+    }
+
+    /// Fetch every published GitHub Release of the repository, newest first. Drafts are skipped.
+    pub fn all(repo: &Repo) -> Result<Vec<PublishedRelease>, String> {
+        validate_token(&[], "GitHub Releases")?;
+
+        let mut releases = vec![];
+        let mut after: Option<String> = None;
+
+        loop {
+            let data = json!({
+                "query": include_str!("./graphql/releases-info/query.graphql"),
+                "variables": {
+                    "org": repo.org,
+                    "repo": repo.repo,
+                    "after": after
+                }
+            });
+
+            let json = graphql(data)?;
+            let page = &json["data"]["repository"]["releases"];
+
+            if page.is_null() {
+                return Err(format!("Couldn't find the releases of {}", repo));
+            }
+
+            for release in page["nodes"].as_array().into_iter().flatten() {
+                if release["isDraft"].as_bool().unwrap_or(false) {
+                    continue;
+                }
+
+                releases.push(PublishedRelease {
+                    tag: release["tagName"].as_str().unwrap_or_default().to_string(),
+                    date: release["publishedAt"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .chars()
+                        .take(10)
+                        .collect(),
+                    body: release["description"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .replace("\r\n", "\n"),
+                });
+            }
+
+            match page["pageInfo"]["hasNextPage"].as_bool() {
+                Some(true) => after = page["pageInfo"]["endCursor"].as_str().map(String::from),
+                _ => return Ok(releases),
+            }
+        }
+    }
+
+    /// Create the GitHub Release for the tag, or update its body when it already exists
+    pub fn sync(repo: &Repo, tag: &str, body: &str, prerelease: bool) -> Result<Sync, String> {
+        validate_token(&["public_repo"], "syncing GitHub Releases")?;
+
+        let releases = format!("/repos/{}/{}/releases", repo.org, repo.repo);
+        let existing = rest(Method::GET, &format!("{}/tags/{}", releases, tag), None)?;
+
+        match existing {
+            Some(release)
+                if release["body"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .replace("\r\n", "\n")
+                    .trim()
+                    == body.trim() =>
+            {
+                Ok(Sync::Unchanged)
+            }
+            Some(release) => {
+                rest(
+                    Method::PATCH,
+                    &format!("{}/{}", releases, release["id"]),
+                    Some(json!({ "body": body })),
+                )?;
+
+                Ok(Sync::Updated)
+            }
+            None => {
+                rest(
+                    Method::POST,
+                    &releases,
+                    Some(json!({
+                        "tag_name": tag,
+                        "name": tag,
+                        "body": body,
+                        "prerelease": prerelease
+                    })),
+                )?;
+
+                Ok(Sync::Created)
+            }
+        }
+    }
+}
@@ -15,23 +15,61 @@ impl Repo {
 
     pub fn from_git_repo(pwd: &PathBuf) -> Result<Self> {
         match Git::new(Some(pwd))?.exec(vec!["config", "--get", "remote.origin.url"]) {
-            Ok(output) => {
-                let output = output.replace(".git", "");
-
-                let parts = output
-                    .split(':')
-                    .collect::<Vec<&str>>()
-                    .pop()
-                    .unwrap()
-                    .split('/')
-                    .collect::<Vec<&str>>();
-
-                match (parts.first(), parts.get(1)) {
-                    (Some(owner), Some(repo)) => Ok(Self::new(owner.to_string(), repo.to_string())),
-                    _ => Err(eyre!("Could not parse git remote url")),
-                }
-            }
+            Ok(output) => Self::from_remote_url(&output),
             Err(e) => Err(eyre!("Failed running git: {}", e)),
         }
     }
+
+    /// Parse the owner and repo from a remote url, e.g.: `git@github.com:owner/repo.git` or
+    /// `https://gitlab.acme.com/group/subgroup/repo`. The owner contains the nested groups of
+    /// hosts like GitLab.
+    fn from_remote_url(url: &str) -> Result<Self> {
+        let url = url.trim().trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+
+        let path = match url.split_once("://") {
+            // E.g.: `https://host/owner/repo` or `ssh://git@host:22/owner/repo`
+            Some((_, rest)) => rest.split_once('/').map(|(_, path)| path),
+            // E.g.: `git@host:owner/repo`
+            None => url.split_once(':').map(|(_, path)| path),
+        };
+
+        match path.and_then(|path| path.rsplit_once('/')) {
+            Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() => {
+                Ok(Self::new(owner.to_string(), repo.to_string()))
+            }
+            _ => Err(eyre!("Could not parse git remote url")),
+        }
+    }
+}
+
+impl std::fmt::Display for Repo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.org, self.repo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_remote_urls() {
+        for (url, expected) in [
+            ("git@github.com:acme/widget.git", "acme/widget"),
+            ("https://github.com/acme/widget.git", "acme/widget"),
+            (
+                "https://gitlab.acme.com/group/subgroup/widget",
+                "group/subgroup/widget",
+            ),
+            (
+                "ssh://git@gitea.acme.com:2222/acme/widget.git",
+                "acme/widget",
+            ),
+        ] {
+            assert_eq!(Repo::from_remote_url(url).unwrap().to_string(), expected);
+        }
+
+        assert!(Repo::from_remote_url("widget").is_err());
+    }
 }
@@ -0,0 +1,243 @@
+use crate::package::PackageJSON;
+use std::str::FromStr;
+
+/// The formats the workspace graph can be printed in
+#[derive(Debug, Clone, Copy)]
+pub enum GraphFormat {
+    /// Every package with the packages that depend on it nested below it, in release order
+    Tree,
+
+    /// The graph in the DOT language of Graphviz, e.g.: `changelog graph --format dot | dot -Tsvg`
+    Dot,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(GraphFormat::Tree),
+            "dot" => Ok(GraphFormat::Dot),
+            _ => Err(format!(
+                "Invalid graph format: {}, expected one of \"tree\" or \"dot\"",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GraphNode {
+    name: String,
+    version: String,
+
+    /// Whether the unreleased section of the package contains entries
+    changed: bool,
+
+    /// The packages of the workspace this package depends on
+    dependencies: Vec<usize>,
+}
+
+/// The dependencies between the packages of a workspace
+#[derive(Debug, Clone)]
+pub struct Graph {
+    nodes: Vec<GraphNode>,
+}
+
+impl Graph {
+    /// Build the graph of the packages, the root package of the monorepo is not part of it
+    pub fn new(packages: &[PackageJSON], changed: impl Fn(&PackageJSON) -> bool) -> Self {
+        let packages: Vec<PackageJSON> = PackageJSON::sort_by_dependencies(
+            packages
+                .iter()
+                .filter(|package| !package.is_root())
+                .cloned()
+                .collect(),
+        );
+
+        let nodes = packages
+            .iter()
+            .map(|package| GraphNode {
+                name: package.name().to_string(),
+                version: package.version().to_string(),
+                changed: changed(package),
+                dependencies: packages
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| package.depends_on(other.name()))
+                    .map(|(index, _)| index)
+                    .collect(),
+            })
+            .collect();
+
+        Self { nodes }
+    }
+
+    /// The packages that depend on the package at the index
+    fn dependents(&self, index: usize) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|other| self.nodes[*other].dependencies.contains(&index))
+            .collect()
+    }
+
+    /// Render the packages without dependencies in the workspace at the top level, with the
+    /// packages that depend on them nested below them. Releasing from the top down releases the
+    /// dependencies first.
+    pub fn tree(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        let mut visited: Vec<usize> = vec![];
+
+        // Packages in a dependency cycle don't have a top level package, they are rendered at the
+        // top level themselves
+        let roots = (0..self.nodes.len())
+            .filter(|index| self.nodes[*index].dependencies.is_empty())
+            .chain(0..self.nodes.len());
+
+        for index in roots {
+            if visited.contains(&index) {
+                continue;
+            }
+
+            self.render_tree(index, "", None, &mut vec![], &mut visited, &mut lines);
+        }
+
+        lines.join("\n")
+    }
+
+    fn render_tree(
+        &self,
+        index: usize,
+        indent: &str,
+        is_last: Option<bool>,
+        path: &mut Vec<usize>,
+        visited: &mut Vec<usize>,
+        lines: &mut Vec<String>,
+    ) {
+        let node = &self.nodes[index];
+        let (branch, child_indent) = match is_last {
+            None => ("", indent.to_string()),
+            Some(true) => ("└── ", format!("{}    ", indent)),
+            Some(false) => ("├── ", format!("{}│   ", indent)),
+        };
+
+        let is_cycle = path.contains(&index);
+        lines.push(format!(
+            "{}{}{} {}{}{}",
+            indent,
+            branch,
+            node.name,
+            node.version,
+            match node.changed {
+                true => " (unreleased changes)",
+                false => "",
+            },
+            match is_cycle {
+                true => " (cycle)",
+                false => "",
+            }
+        ));
+
+        if is_cycle {
+            return;
+        }
+
+        visited.push(index);
+        path.push(index);
+
+        let dependents = self.dependents(index);
+        for (i, dependent) in dependents.iter().enumerate() {
+            self.render_tree(
+                *dependent,
+                &child_indent,
+                Some(i == dependents.len() - 1),
+                path,
+                visited,
+                lines,
+            );
+        }
+
+        path.pop();
+    }
+
+    /// Render the graph in the DOT language, the edges point from a package to its dependencies
+    /// and the packages with unreleased changes are highlighted.
+    pub fn dot(&self) -> String {
+        let mut lines = vec!["digraph workspace {".to_string()];
+        lines.push("  node [shape=box];".to_string());
+
+        for node in &self.nodes {
+            lines.push(format!(
+                "  \"{}\" [label=\"{}\\n{}\"{}];",
+                node.name,
+                node.name,
+                node.version,
+                match node.changed {
+                    true => ", style=filled, fillcolor=\"#fde68a\"",
+                    false => "",
+                }
+            ));
+        }
+
+        for node in &self.nodes {
+            for dependency in &node.dependencies {
+                lines.push(format!(
+                    "  \"{}\" -> \"{}\";",
+                    node.name, self.nodes[*dependency].name
+                ));
+            }
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages() -> serde_json::Result<Vec<PackageJSON>> {
+        [
+            r#"{"name": "app", "version": "2.0.0", "dependencies": {"ui": "^1.0.0", "core": "^1.0.0"}}"#,
+            r#"{"name": "ui", "version": "1.0.0", "dependencies": {"core": "workspace:^"}}"#,
+            r#"{"name": "core", "version": "1.0.0"}"#,
+        ]
+        .iter()
+        .map(|contents| serde_json::from_str(contents))
+        .collect()
+    }
+
+    #[test]
+    fn it_should_render_the_workspace_graph() -> serde_json::Result<()> {
+        let graph = Graph::new(&packages()?, |package| package.name() == "ui");
+
+        assert_eq!(
+            graph.tree(),
+            [
+                "core 1.0.0",
+                "├── ui 1.0.0 (unreleased changes)",
+                "│   └── app 2.0.0",
+                "└── app 2.0.0",
+            ]
+            .join("\n")
+        );
+
+        assert_eq!(
+            graph.dot(),
+            [
+                "digraph workspace {",
+                "  node [shape=box];",
+                "  \"core\" [label=\"core\\n1.0.0\"];",
+                "  \"ui\" [label=\"ui\\n1.0.0\", style=filled, fillcolor=\"#fde68a\"];",
+                "  \"app\" [label=\"app\\n2.0.0\"];",
+                "  \"ui\" -> \"core\";",
+                "  \"app\" -> \"core\";",
+                "  \"app\" -> \"ui\";",
+                "}",
+            ]
+            .join("\n")
+        );
+
+        Ok(())
+    }
+}
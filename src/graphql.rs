@@ -1,20 +1,150 @@
-use reqwest::header::{HeaderValue, CONTENT_TYPE, USER_AGENT};
+use reqwest::header::{HeaderValue, ACCEPT, CONTENT_TYPE, USER_AGENT};
+use serde_json::json;
+use std::sync::OnceLock;
 
-pub fn graphql(data: serde_json::Value) -> Result<serde_json::Value, String> {
-    let json = reqwest::blocking::Client::new()
+/// The scopes granted to the token, `None` for tokens that don't report their scopes (e.g.:
+/// fine-grained personal access tokens or GitHub App tokens).
+static GRANTED_SCOPES: OnceLock<Result<Option<Vec<String>>, String>> = OnceLock::new();
+
+fn token() -> Result<String, String> {
+    std::env::var("GITHUB_API_TOKEN").map_err(|_| {
+        "GITHUB_API_TOKEN is not set, create a token at https://github.com/settings/tokens"
+            .to_string()
+    })
+}
+
+fn request(data: &serde_json::Value) -> Result<reqwest::blocking::Response, String> {
+    reqwest::blocking::Client::new()
         .post("https://api.github.com/graphql")
-        .bearer_auth(std::env::var("GITHUB_API_TOKEN").expect("GITHUB_API_TOKEN not set"))
+        .bearer_auth(token()?)
         .header(USER_AGENT, HeaderValue::from_static("reqwest"))
         .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
         .body(data.to_string())
         .send()
-        .unwrap()
+        .map_err(|e| format!("Couldn't reach the GitHub API: {}", e))
+}
+
+/// Whether the granted scopes include the required scope, either directly or via a parent scope.
+fn has_scope(granted: &[String], required: &str) -> bool {
+    granted.iter().any(|scope| {
+        scope == required
+            || matches!(
+                (scope.as_str(), required),
+                ("repo", "public_repo") | ("write:discussion", "read:discussion")
+            )
+    })
+}
+
+/// Validate that the token works and has the required scopes, so that we can show an actionable
+/// error instead of failing on an unexpected response later on. The token is only checked once.
+pub fn validate_token(scopes: &[&str], purpose: &str) -> Result<(), String> {
+    let granted = GRANTED_SCOPES.get_or_init(|| {
+        let response = request(&json!({ "query": "query { viewer { login } }" }))?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("GITHUB_API_TOKEN is invalid or expired".to_string());
+        }
+
+        Ok(response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|header| header.to_str().ok())
+            .map(|header| {
+                header
+                    .split(',')
+                    .map(|scope| scope.trim().to_string())
+                    .filter(|scope| !scope.is_empty())
+                    .collect()
+            }))
+    });
+
+    match granted {
+        Ok(Some(granted)) => match scopes.iter().find(|scope| !has_scope(granted, scope)) {
+            Some(scope) => Err(format!(
+                "GITHUB_API_TOKEN lacks `{}` needed for {}",
+                scope, purpose
+            )),
+            None => Ok(()),
+        },
+        Ok(None) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn graphql(data: serde_json::Value) -> Result<serde_json::Value, String> {
+    let json = request(&data)?
         .json::<serde_json::Value>()
-        .unwrap();
+        .map_err(|e| format!("Invalid response from the GitHub API: {}", e))?;
 
     if let Some(errors) = json["errors"].as_array() {
-        return Err(errors[0]["message"].as_str().unwrap().to_string());
+        return Err(errors[0]["message"]
+            .as_str()
+            .unwrap_or("Unknown GitHub API error")
+            .to_string());
+    }
+
+    if let Some(message) = json["message"].as_str() {
+        return Err(format!("GitHub API error: {}", message));
     }
 
     Ok(json)
 }
+
+/// Call the REST API, for the things the GraphQL API can't do (e.g.: creating releases). Returns
+/// `None` when the resource doesn't exist.
+pub fn rest(
+    method: reqwest::Method,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<Option<serde_json::Value>, String> {
+    let mut request = reqwest::blocking::Client::new()
+        .request(method, format!("https://api.github.com{}", path))
+        .bearer_auth(token()?)
+        .header(USER_AGENT, HeaderValue::from_static("reqwest"))
+        .header(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+
+    if let Some(body) = body {
+        request = request
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(body.to_string());
+    }
+
+    let response = request
+        .send()
+        .map_err(|e| format!("Couldn't reach the GitHub API: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    let status = response.status();
+    let json = response
+        .json::<serde_json::Value>()
+        .map_err(|e| format!("Invalid response from the GitHub API: {}", e))?;
+
+    if !status.is_success() {
+        return Err(format!(
+            "GitHub API error: {}",
+            json["message"].as_str().unwrap_or(status.as_str())
+        ));
+    }
+
+    Ok(Some(json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_accept_parent_scopes() {
+        let granted = vec!["repo".to_string(), "write:discussion".to_string()];
+
+        assert!(has_scope(&granted, "public_repo"));
+        assert!(has_scope(&granted, "read:discussion"));
+        assert!(!has_scope(&granted, "read:org"));
+    }
+}
@@ -0,0 +1,210 @@
+use color_eyre::eyre::{eyre, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Every hook that we install contains this line, so that we never overwrite (or remove) a hook
+/// that someone else wrote
+const MARKER: &str = "# changelog: managed hook";
+
+/// The git hooks that can remind you about a missing changelog entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Checks the staged files of every commit
+    PrepareCommitMsg,
+
+    /// Checks the commits that are pushed, compared to the upstream branch
+    PrePush,
+}
+
+impl FromStr for Hook {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prepare-commit-msg" => Ok(Hook::PrepareCommitMsg),
+            "pre-push" => Ok(Hook::PrePush),
+            _ => Err(format!(
+                "Invalid hook: {}, expected one of \"prepare-commit-msg\" or \"pre-push\"",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HookOptions {
+    /// Fail the commit or push instead of only printing a reminder
+    pub block: bool,
+
+    /// The paths that require an entry when they change, e.g.: `src/`
+    pub paths: Vec<String>,
+
+    /// The changelog filename, the changelogs of every package in a monorepo are checked
+    pub filename: String,
+
+    /// The ref that `pre-push` compares against when the branch has no upstream
+    pub base: String,
+}
+
+impl Hook {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PrepareCommitMsg => "prepare-commit-msg",
+            Hook::PrePush => "pre-push",
+        }
+    }
+
+    /// The shell script of the hook. It only relies on git, so that it keeps working for people
+    /// that don't have the changelog CLI installed.
+    pub fn script(&self, options: &HookOptions) -> String {
+        let paths = options
+            .paths
+            .iter()
+            .map(|path| quote(path))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let changelog = quote(&format!("*{}", options.filename));
+        let exit_code = if options.block { 1 } else { 0 };
+
+        let check = match self {
+            Hook::PrepareCommitMsg => format!(
+                r#"# Merges and amended commits were already checked
+case "$2" in
+  merge|commit) exit 0 ;;
+esac
+
+if [ -z "$(git diff --cached --name-only -- {paths})" ]; then
+  exit 0
+fi
+
+if git diff --cached -- {changelog} | grep -q '^+[-*] '; then
+  exit 0
+fi
+
+if grep -qi '\[skip changelog\]' "$1"; then
+  exit 0
+fi
+"#,
+                paths = paths,
+                changelog = changelog,
+            ),
+            Hook::PrePush => format!(
+                r#"base=$(git rev-parse --abbrev-ref --symbolic-full-name "@{{upstream}}" 2>/dev/null || echo {base})
+
+if [ -z "$(git diff --name-only "$base"...HEAD -- {paths})" ]; then
+  exit 0
+fi
+
+if git diff "$base"...HEAD -- {changelog} | grep -q '^+[-*] '; then
+  exit 0
+fi
+
+if git log --format=%B "$base"..HEAD | grep -qi '\[skip changelog\]'; then
+  exit 0
+fi
+"#,
+                base = quote(&options.base),
+                paths = paths,
+                changelog = changelog,
+            ),
+        };
+
+        format!(
+            r#"#!/bin/sh
+{marker}, remove it with `changelog install-hooks --uninstall`
+
+{check}
+echo "changelog: {paths} changed without a new Unreleased entry in {filename}" >&2
+echo "changelog: add one with \`changelog add\`, or add [skip changelog] to the commit message" >&2
+exit {exit_code}
+"#,
+            marker = MARKER,
+            check = check,
+            paths = options.paths.join(", "),
+            filename = options.filename,
+            exit_code = exit_code,
+        )
+    }
+
+    /// Install the hook in the hooks directory, a hook that we installed before is replaced
+    pub fn install(&self, hooks_dir: &Path, options: &HookOptions) -> Result<PathBuf> {
+        let path = hooks_dir.join(self.name());
+        if path.exists() && !is_managed(&path) {
+            return Err(eyre!(
+                "There is already a {} hook at {}, remove it first",
+                self.name(),
+                path.display()
+            ));
+        }
+
+        fs::create_dir_all(hooks_dir)?;
+        fs::write(&path, self.script(options))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        Ok(path)
+    }
+
+    /// Remove the hook from the hooks directory, returns the path of the removed hook
+    pub fn uninstall(&self, hooks_dir: &Path) -> Result<Option<PathBuf>> {
+        let path = hooks_dir.join(self.name());
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        if !is_managed(&path) {
+            return Err(eyre!(
+                "The {} hook at {} was not installed by changelog, remove it manually",
+                self.name(),
+                path.display()
+            ));
+        }
+
+        fs::remove_file(&path)?;
+        Ok(Some(path))
+    }
+}
+
+fn is_managed(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|contents| contents.contains(MARKER))
+        .unwrap_or(false)
+}
+
+/// Quote a value for the shell
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_not_replace_hooks_of_others() {
+        let dir = std::env::temp_dir().join(format!("changelog-hooks-{}", uuid::Uuid::new_v4()));
+        let options = HookOptions {
+            block: true,
+            paths: vec!["src/".to_string()],
+            filename: "CHANGELOG.md".to_string(),
+            base: "origin/main".to_string(),
+        };
+
+        let path = Hook::PrePush.install(&dir, &options).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("exit 1"));
+        assert!(Hook::PrePush.install(&dir, &options).is_ok());
+        assert_eq!(Hook::PrePush.uninstall(&dir).unwrap(), Some(path.clone()));
+        assert_eq!(Hook::PrePush.uninstall(&dir).unwrap(), None);
+
+        fs::write(&path, "#!/bin/sh\nnpm test\n").unwrap();
+        assert!(Hook::PrePush.install(&dir, &options).is_err());
+        assert!(Hook::PrePush.uninstall(&dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,44 @@
+//! Make CHANGELOG.md changes easier.
+//!
+//! This is the library behind the `changelog` CLI, so that other tools (e.g.: your own release
+//! automation) can read and change a `CHANGELOG.md` file without shelling out to the CLI. Nothing
+//! in here prints or exits the process, failures are returned as errors.
+//!
+//! ```
+//! use changelog::{config::Config, Changelog};
+//!
+//! let contents = "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n- Nothing yet!\n";
+//! let mut changelog =
+//!     Changelog::from_contents(&std::env::temp_dir(), "CHANGELOG.md", contents, &Config::default())?;
+//!
+//! changelog.add_list_item_to_section("Added", "Support for feeds")?;
+//!
+//! assert_eq!(changelog.notes(None)?.trim(), "### Added\n\n- Support for feeds");
+//! # Ok::<(), color_eyre::eyre::Error>(())
+//! ```
+//!
+//! The GitHub resolvers in [`github`] turn links to pull requests, issues, commits and
+//! discussions into entries, they require a `GITHUB_API_TOKEN`.
+
+pub mod announce;
+pub mod cargo;
+pub mod changelog;
+pub mod config;
+pub mod date;
+pub mod diff;
+pub mod git;
+pub mod github;
+pub mod graph;
+mod graphql;
+pub mod hooks;
+pub mod list_format;
+pub mod markdown;
+pub mod metadata;
+mod migrate;
+pub mod package;
+mod sanitize;
+pub mod version_file;
+
+pub use crate::changelog::Changelog;
+pub use crate::markdown::{ast::Node, tokens::MarkdownToken};
+pub use crate::package::{PackageJSON, SemVer};
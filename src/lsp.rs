@@ -0,0 +1,400 @@
+use changelog::changelog::Changelog;
+use changelog::config::Config;
+use color_eyre::eyre::{eyre, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// A minimal language server for the changelog file, speaking JSON-RPC over stdin/stdout. It
+/// provides diagnostics, code actions to move entries between sections and completion of version
+/// references.
+pub struct LanguageServer<'a> {
+    pwd: &'a Path,
+    filename: &'a str,
+    config: &'a Config,
+    documents: HashMap<String, String>,
+}
+
+impl<'a> LanguageServer<'a> {
+    pub fn new(pwd: &'a Path, filename: &'a str, config: &'a Config) -> Self {
+        Self {
+            pwd,
+            filename,
+            config,
+            documents: HashMap::new(),
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        let stdin = std::io::stdin();
+        let mut stdin = stdin.lock();
+
+        while let Some(message) = read_message(&mut stdin)? {
+            let result = match message["method"].as_str().unwrap_or_default() {
+                "exit" => return Ok(()),
+                method => self.handle(method, &message["params"])?,
+            };
+
+            // Only requests have an id and expect a response, notifications don't
+            if !message["id"].is_null() {
+                write_message(&json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "result": result.unwrap_or(Value::Null),
+                }))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a request or a notification, only requests have a result
+    fn handle(&mut self, method: &str, params: &Value) -> Result<Option<Value>> {
+        Ok(match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "codeActionProvider": true,
+                    "completionProvider": { "triggerCharacters": ["["] }
+                },
+                "serverInfo": { "name": "changelog" }
+            })),
+            "shutdown" => Some(Value::Null),
+            "textDocument/didOpen" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update(uri, text)?;
+                None
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                // We only support full document syncs
+                if let Some(text) = params["contentChanges"][0]["text"].as_str() {
+                    self.update(uri, text)?;
+                }
+                None
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                None
+            }
+            "textDocument/codeAction" => Some(self.code_actions(params)),
+            "textDocument/completion" => Some(self.completions(params)),
+            _ => None,
+        })
+    }
+
+    fn changelog(&self, text: &str) -> Result<Changelog> {
+        Changelog::from_contents(self.pwd, self.filename, text, self.config)
+    }
+
+    fn update(&mut self, uri: &str, text: &str) -> Result<()> {
+        self.documents.insert(uri.to_string(), text.to_string());
+
+        let diagnostics: Vec<Value> = match self.changelog(text) {
+            Ok(changelog) => diagnostics(&changelog, text)
+                .into_iter()
+                .map(|(line, message)| {
+                    json!({
+                        "range": range(line, 0, line, text.lines().nth(line).map_or(0, |l| l.len())),
+                        "severity": 2,
+                        "source": "changelog",
+                        "message": message,
+                    })
+                })
+                .collect(),
+            Err(e) => vec![json!({
+                "range": range(0, 0, 0, 0),
+                "severity": 1,
+                "source": "changelog",
+                "message": e.to_string(),
+            })],
+        };
+
+        write_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }))
+    }
+
+    fn code_actions(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let line = params["range"]["start"]["line"]
+            .as_u64()
+            .unwrap_or_default() as usize;
+        let text = match self.documents.get(uri) {
+            Some(text) => text,
+            None => return json!([]),
+        };
+
+        let current = match section_at(text, line) {
+            Some(section) => section,
+            None => return json!([]),
+        };
+
+        let line_count = text.lines().count();
+
+        Value::Array(
+            self.config
+                .sections
+                .iter()
+                .filter(|section| !section.eq_ignore_ascii_case(&current))
+                .filter_map(|section| {
+                    let updated = move_entry(text, line, section)?;
+
+                    Some(json!({
+                        "title": format!("Move to ### {}", section),
+                        "kind": "refactor.move",
+                        "edit": {
+                            "changes": {
+                                uri: [{
+                                    "range": range(0, 0, line_count + 1, 0),
+                                    "newText": updated,
+                                }]
+                            }
+                        }
+                    }))
+                })
+                .collect(),
+        )
+    }
+
+    fn completions(&self, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let versions = match self.documents.get(uri).map(|text| self.changelog(text)) {
+            Some(Ok(changelog)) => changelog.versions(None),
+            _ => vec![],
+        };
+
+        Value::Array(
+            std::iter::once("unreleased".to_string())
+                .chain(versions)
+                .map(|version| json!({ "label": version, "kind": 18 }))
+                .collect(),
+        )
+    }
+}
+
+fn range(start_line: usize, start_char: usize, end_line: usize, end_char: usize) -> Value {
+    json!({
+        "start": { "line": start_line, "character": start_char },
+        "end": { "line": end_line, "character": end_char },
+    })
+}
+
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(length) = header.strip_prefix("Content-Length:") {
+            content_length = Some(length.trim().parse()?);
+        }
+    }
+
+    let mut body = vec![0; content_length.ok_or_else(|| eyre!("Missing Content-Length"))?];
+    reader.read_exact(&mut body)?;
+
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn write_message(message: &Value) -> Result<()> {
+    let body = message.to_string();
+
+    print!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+    std::io::stdout().flush()?;
+
+    Ok(())
+}
+
+/// The diagnostics of the changelog as a list of line numbers and messages
+fn diagnostics(changelog: &Changelog, text: &str) -> Vec<(usize, String)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let find_line = |predicate: &dyn Fn(&str) -> bool| {
+        lines
+            .iter()
+            .position(|line| predicate(line))
+            .unwrap_or_default()
+    };
+
+    let issues = changelog.link_issues();
+    let mut diagnostics: Vec<(usize, String)> = vec![];
+
+    for (name, _) in &issues.orphans {
+        diagnostics.push((
+            find_line(&|line| line.starts_with(&format!("[{}]:", name))),
+            format!("Reference link [{}] is not used anywhere", name),
+        ));
+    }
+
+    for name in &issues.missing {
+        diagnostics.push((
+            find_line(&|line| line.starts_with(&format!("## [{}]", name))),
+            format!("Heading [{}] doesn't have a reference link", name),
+        ));
+    }
+
+    // Duplicate entries within the same section
+    let mut seen: Vec<(usize, usize, &str)> = vec![];
+    let (mut version, mut section) = (0, 0);
+    for (idx, line) in lines.iter().enumerate() {
+        if line.starts_with("## ") {
+            version = idx;
+        } else if line.starts_with("### ") {
+            section = idx;
+        } else if let Some(entry) = entry_text(line) {
+            if seen.contains(&(version, section, entry.trim())) {
+                diagnostics.push((idx, "Duplicate entry in this section".to_string()));
+            } else {
+                seen.push((version, section, entry.trim()));
+            }
+        }
+    }
+
+    diagnostics.sort();
+    diagnostics
+}
+
+/// The text of the entry on the line, if the line is an entry, e.g.: `- Some entry`
+fn entry_text(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+}
+
+/// The name of the section that contains the entry on the given line
+fn section_at(text: &str, line: usize) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    entry_text(lines.get(line)?)?;
+
+    lines[..line]
+        .iter()
+        .rev()
+        .take_while(|line| !line.starts_with("## "))
+        .find_map(|line| line.strip_prefix("### "))
+        .map(|section| section.trim().to_string())
+}
+
+/// Move the entry on the given line to another section within the same version, the section is
+/// created if it doesn't exist yet. Returns the updated text.
+fn move_entry(text: &str, line: usize, target: &str) -> Option<String> {
+    let mut lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+    let entry = lines.get(line)?.clone();
+
+    // The boundaries of the version that contains the entry
+    let start = (0..line).rev().find(|idx| lines[*idx].starts_with("## "))?;
+    let end = (line + 1..lines.len())
+        .find(|idx| lines[*idx].starts_with("## ") || lines[*idx].starts_with('['))
+        .unwrap_or(lines.len());
+
+    // Remove the entry, and its section if it became empty
+    lines.remove(line);
+    let source = (start..line)
+        .rev()
+        .find(|idx| lines[*idx].starts_with("### "))?;
+    let source_end = (source + 1..end - 1)
+        .find(|idx| lines[*idx].starts_with('#'))
+        .unwrap_or(end - 1);
+    let mut end = end - 1;
+    if !lines[source + 1..source_end]
+        .iter()
+        .any(|line| entry_text(line).is_some())
+    {
+        lines.drain(source..source_end);
+        end -= source_end - source;
+    }
+
+    let heading = format!("### {}", target);
+    match (start..end).find(|idx| lines[*idx].eq_ignore_ascii_case(&heading)) {
+        Some(section) => {
+            let last_entry = (section + 1..end)
+                .take_while(|idx| !lines[*idx].starts_with('#'))
+                .filter(|idx| entry_text(&lines[*idx]).is_some() || lines[*idx].starts_with("  "))
+                .last()
+                .unwrap_or(section + 1);
+            lines.insert(last_entry + 1, entry);
+        }
+        None => {
+            let mut insert_at = end;
+            while insert_at > start + 1 && lines[insert_at - 1].trim().is_empty() {
+                insert_at -= 1;
+            }
+
+            for (offset, line) in ["".to_string(), heading, "".to_string(), entry]
+                .into_iter()
+                .enumerate()
+            {
+                lines.insert(insert_at + offset, line);
+            }
+        }
+    }
+
+    Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_move_entries_between_sections() {
+        let text = "## [Unreleased]\n\n### Added\n\n- A\n- B\n\n### Fixed\n\n- C\n\n## [1.0.0]\n";
+
+        assert_eq!(section_at(text, 5), Some("Added".to_string()));
+        assert_eq!(
+            move_entry(text, 5, "Fixed").unwrap(),
+            "## [Unreleased]\n\n### Added\n\n- A\n\n### Fixed\n\n- C\n- B\n\n## [1.0.0]\n"
+        );
+        assert_eq!(
+            move_entry(text, 9, "Changed").unwrap(),
+            "## [Unreleased]\n\n### Added\n\n- A\n- B\n\n### Changed\n\n- C\n\n## [1.0.0]\n"
+        );
+    }
+
+    #[test]
+    fn it_should_tolerate_partial_references_while_typing() -> Result<()> {
+        let config = Config::default();
+        let mut server = LanguageServer::new(Path::new("."), "CHANGELOG.md", &config);
+        let uri = "file:///CHANGELOG.md";
+        let text = "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [1.0.0] - 2022-01-01\n\n- Initial release\n\n[unreleased]: https://github.com/acme/widget/compare/v1.0.0...HEAD\n[1.0.0]: https://github.com/acme/widget/releases/tag/v1.0.0\n";
+
+        server.handle(
+            "textDocument/didOpen",
+            &json!({ "textDocument": { "uri": uri, "text": text } }),
+        )?;
+        server.handle(
+            "textDocument/didChange",
+            &json!({
+                "textDocument": { "uri": uri },
+                "contentChanges": [{ "text": format!("{}[", text) }],
+            }),
+        )?;
+
+        let completions = server.handle(
+            "textDocument/completion",
+            &json!({ "textDocument": { "uri": uri } }),
+        )?;
+        assert_eq!(
+            completions,
+            Some(json!([
+                { "label": "unreleased", "kind": 18 },
+                { "label": "1.0.0", "kind": 18 },
+            ]))
+        );
+
+        Ok(())
+    }
+}
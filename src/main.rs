@@ -1,28 +1,59 @@
-mod changelog;
-mod git;
-mod github;
-mod graphql;
-mod list_format;
-mod markdown;
+mod actions;
+mod api;
+mod lsp;
 mod npm;
 mod output;
-mod package;
 mod rich_edit;
-
-use crate::changelog::{Amount, Changelog};
-use crate::git::Git;
-use crate::github::github_info::GitHubInfo;
-use crate::list_format::conjunction;
-use crate::markdown::{ast::Node, tokens::MarkdownToken};
-use crate::npm::{Npm, Options};
-use crate::output::{output, output_indented, output_title};
-use crate::package::{PackageJSON, SemVer};
-use crate::rich_edit::rich_edit;
-use clap::{Parser, Subcommand};
+mod ui;
+
+use crate::api::{Api, ApiDocument};
+use crate::lsp::LanguageServer;
+use crate::npm::{Access, Npm, Options, PackageManager, PublishOptions};
+use crate::output::{output, output_indented, output_title, output_warning, ColorChoice};
+use crate::rich_edit::{rich_edit, Edit};
+use crate::ui::Dashboard;
+use changelog::announce::Announcement;
+use changelog::cargo;
+use changelog::changelog::{Amount, Changelog, ExportFormat, ImportFormat, UMBRELLA_SECTION};
+use changelog::config::{Config, Position, SortOrder};
+use changelog::date::{DateLocale, Timezone};
+use changelog::diff::{Diff, Entry};
+use changelog::git::{CommitOptions, Git};
+use changelog::github::{
+    autolink::autolink,
+    github_info::GitHubInfo,
+    release::{Release, Sync},
+    repo::Repo,
+};
+use changelog::graph::{Graph, GraphFormat};
+use changelog::hooks::{Hook, HookOptions};
+use changelog::list_format::conjunction;
+use changelog::metadata;
+use changelog::package::{ManifestKind, PackageJSON, SemVer};
+use changelog::{markdown, Node};
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+use color_eyre::config::{HookBuilder, Theme};
 use color_eyre::eyre::{eyre, Result};
 use colored::*;
-use dialoguer::MultiSelect;
-use std::{collections::HashMap, fmt::Debug, fs, path::PathBuf};
+use dialoguer::{Confirm, MultiSelect, Select};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    fs,
+    io::{IsTerminal, Read},
+    path::Path,
+    sync::Arc,
+};
+
+/// The escape hatch of `changelog verify-pr`, for pull requests that don't need an entry
+const SKIP_CHANGELOG: &str = "[skip changelog]";
+
+/// The git config key of the packages that were picked in the last interactive prompt
+const LAST_SCOPES: &str = "changelog.lastScopes";
+
+/// The section of `changelog add` when no section is picked
+const DEFAULT_SECTION: &str = "Added";
 
 /// Make CHANGELOG.md changes easier
 #[derive(Parser, Debug)]
@@ -36,8 +67,8 @@ struct Cli {
     #[clap(short, long, default_value = "CHANGELOG.md", global = true)]
     filename: String,
 
-    /// Used in monorepos. Operate on these packages only. You can also pass multiple occurrences.
-    /// If none are passed, an interactive prompt will be shown.
+    /// Used in monorepos. Operate on these packages (or scope groups) only. You can also pass
+    /// multiple occurrences. If none are passed, an interactive prompt will be shown.
     #[clap(
         short,
         long = "scope",
@@ -47,151 +78,519 @@ struct Cli {
     )]
     scopes: Vec<String>,
 
+    /// Used in monorepos. Operate on every package, without showing the interactive prompt.
+    #[clap(long, global = true, conflicts_with = "SCOPE")]
+    all_scopes: bool,
+
+    /// Used in monorepos. Operate on the packages that were picked in the last interactive
+    /// prompt, without showing the prompt again.
+    #[clap(long, global = true, conflicts_with_all = &["SCOPE", "all-scopes"])]
+    last_scopes: bool,
+
+    /// The timezone used for the dates of new versions, which can be one of: "local", "utc" or a
+    /// named timezone like "Europe/Brussels"
+    #[clap(long, global = true)]
+    timezone: Option<Timezone>,
+
+    /// Never prompt or open an editor, fail with an error instead. This is the default when stdin
+    /// is not a terminal, e.g.: in CI.
+    #[clap(long, global = true)]
+    non_interactive: bool,
+
+    /// When to use colors, which can be one of: "auto", "always" or "never". Colors are disabled
+    /// automatically when the output is not a terminal, or when the NO_COLOR env var is set.
+    #[clap(long, default_value = "auto", global = true)]
+    color: ColorChoice,
+
+    /// Write the key results (e.g.: the version, the tag and the path to the notes) to the outputs
+    /// of the current GitHub Actions step, and emit annotations for notices and errors
+    #[clap(long, global = true)]
+    github_output: bool,
+
     /// The subcommand to run
     #[clap(subcommand)]
     command: Commands,
 }
 
+#[derive(Args, Debug)]
+struct CommitArgs {
+    /// Skip the pre-commit and commit-msg hooks when committing
+    #[clap(long)]
+    no_verify: bool,
+
+    /// Add a `Signed-off-by` trailer to the commits
+    #[clap(long)]
+    signoff: bool,
+
+    /// Pass an extra argument to `git commit`, e.g.: `-o=--author="Bot <bot@acme.com>"`. You can
+    /// also pass multiple occurrences
+    #[clap(short = 'o', long = "git-option", multiple_occurrences = true)]
+    git_options: Vec<String>,
+}
+
+impl CommitArgs {
+    fn options(&self) -> CommitOptions {
+        CommitOptions {
+            no_verify: self.no_verify,
+            signoff: self.signoff,
+            args: self.git_options.clone(),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct EntryOptions {
+    /// Links to the commits, prs, issues, ... Their titles are fetched concurrently.
+    #[clap(name = "LINK", conflicts_with = "message")]
+    links: Vec<String>,
+
+    /// A manual message you want to add, use "-" to read the message(s) from stdin. You can also
+    /// pass multiple occurrences.
+    #[clap(
+        short,
+        long = "message",
+        name = "message",
+        multiple_occurrences = true,
+        conflicts_with = "LINK"
+    )]
+    messages: Vec<String>,
+
+    /// Whether or not to commit the changes
+    #[clap(short, long)]
+    commit: bool,
+
+    /// Whether you want to edit the (automated) message after it got fetched from GitHub
+    #[clap(short, long)]
+    edit: bool,
+
+    /// Keep the entries within the section sorted, which can be one of: "alphabetical" or
+    /// "link-number"
+    #[clap(long)]
+    sort: Option<SortOrder>,
+
+    /// Where to insert the new entry within the section, which can be one of: "top" or "bottom"
+    #[clap(long)]
+    position: Option<Position>,
+
+    /// Mark the entry as a breaking change
+    #[clap(long)]
+    breaking: bool,
+
+    /// Who owns the entry, e.g.: "@robin". Defaults to the git user when reviews are enabled
+    #[clap(long)]
+    by: Option<String>,
+
+    #[clap(flatten)]
+    commit_args: CommitArgs,
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Initialize a new CHANGELOG.md file, if it doesn't exist yet
-    Init,
-
-    /// Add a new entry to the changelog in the "Added" section
-    Add {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+    Init {
+        /// Migrate an existing (hand written) CHANGELOG.md file instead, e.g.: normalize the
+        /// dates in the version headings to ISO dates
+        #[clap(long)]
+        migrate: bool,
 
-        /// The section name to add the entry to
-        #[clap(hide = true, default_value = "Added")]
-        name: String,
+        /// How to interpret ambiguous numeric dates like "05/03/2021" when migrating, which can be
+        /// one of: "dmy" (day first) or "mdy" (month first)
+        #[clap(long, default_value = "dmy", requires = "migrate")]
+        date_locale: DateLocale,
+    },
 
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
+    /// Add a new entry to the changelog, you are asked for the section (defaults to "Added")
+    Add {
+        #[clap(flatten)]
+        entry: EntryOptions,
 
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
+        /// The section to add the entry to, e.g.: "Security". You are asked to pick one when it is
+        /// omitted
+        #[clap(long = "section")]
+        name: Option<String>,
     },
 
     /// Add a new entry to the changelog in the "Fixed" section
     Fix {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        entry: EntryOptions,
 
         /// The section name to add the entry to
-        #[clap(hide = true, default_value = "Fixed")]
+        #[clap(long = "section", hide = true, default_value = "Fixed")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Changed" section
     Change {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        entry: EntryOptions,
 
         /// The section name to add the entry to
-        #[clap(hide = true, default_value = "Changed")]
+        #[clap(long = "section", hide = true, default_value = "Changed")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Deprecated" section
     Deprecate {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        entry: EntryOptions,
 
         /// The section name to add the entry to
-        #[clap(hide = true, default_value = "Deprecated")]
+        #[clap(long = "section", hide = true, default_value = "Deprecated")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Add a new entry to the changelog in the "Removed" section
     Remove {
-        /// A link to the commit, pr, issue, ...
-        #[clap(conflicts_with = "message")]
-        link: Option<String>,
-
-        /// A manual message you want to add
-        #[clap(short, long, conflicts_with = "link")]
-        message: Option<String>,
+        #[clap(flatten)]
+        entry: EntryOptions,
 
         /// The section name to add the entry to
-        #[clap(hide = true, default_value = "Removed")]
+        #[clap(long = "section", hide = true, default_value = "Removed")]
         name: String,
-
-        /// Whether or not to commit the changes
-        #[clap(short, long)]
-        commit: bool,
-
-        /// Whether you want to edit the (automated) message after it got fetched from GitHub
-        #[clap(short, long)]
-        edit: bool,
     },
 
     /// Release a new version
     Release {
-        /// The version of the release, which can be one of: "major", "minor", "patch", "infer"
-        /// (infer from current package.json version) or an explicit version number like "1.2.3"
+        /// The version of the release, which can be one of: "major", "minor", "patch",
+        /// "premajor", "preminor", "prepatch", "prerelease", "infer" (infer from current
+        /// package.json version) or an explicit version number like "1.2.3"
         #[clap(default_value = "infer")]
         version: String,
 
+        /// The prefix of the pre-release identifier for the pre-release strategies, e.g.: "beta"
+        #[clap(long)]
+        preid: Option<String>,
+
         /// Whether or not to run `npm version <version>` (which in turn updates package.json and
         /// creates a new git tag)
         #[clap(long)]
         with_npm: bool,
+
+        /// Whether or not to update the version in `Cargo.toml` (and refresh `Cargo.lock` via
+        /// `cargo check`), commit the changes and create a new git tag
+        #[clap(long, conflicts_with = "with-npm")]
+        with_cargo: bool,
+
+        /// Write the notes of the released version to this file as well, e.g.:
+        /// ".changelog/latest.md"
+        #[clap(long)]
+        notes_file: Option<String>,
+
+        /// The name of the release, used for the "{name}" placeholder in the release heading
+        #[clap(long)]
+        name: Option<String>,
+
+        /// Also release the root package of a monorepo with this version (an umbrella version),
+        /// which lists the package versions that were released in this run. Can be one of:
+        /// "major", "minor", "patch", "infer" or an explicit version number like "1.2.3"
+        #[clap(long)]
+        umbrella: Option<String>,
+
+        /// Release the version, even if the unreleased section doesn't contain any entries. A
+        /// "No significant changes." note is added to the release instead
+        #[clap(long)]
+        allow_empty: bool,
+
+        /// Also release private packages (`"private": true` in `package.json`, or
+        /// `publish = false` in `Cargo.toml`), which are skipped by default
+        #[clap(long)]
+        include_private: bool,
+
+        /// Sign the release commits and tags with your GPG or SSH key
+        #[clap(long)]
+        sign: bool,
+
+        /// Create annotated tags with the notes of the released version as the message
+        #[clap(long)]
+        annotate: bool,
+
+        /// The name of the git tags, `{name}` and `{version}` are replaced, e.g.:
+        /// "release-{version}"
+        #[clap(long)]
+        tag_format: Option<String>,
+
+        /// Release without showing a preview and asking for confirmation first
+        #[clap(short, long)]
+        yes: bool,
+
+        /// Used in monorepos. Also release the packages that depend on the released packages,
+        /// with this version ("patch" by default), e.g.: `--dependents=minor`. Without it, the
+        /// dependents are only offered in the interactive preview.
+        #[clap(
+            long,
+            min_values = 0,
+            require_equals = true,
+            default_missing_value = "patch"
+        )]
+        dependents: Option<String>,
+
+        /// Release even when the working tree has uncommitted changes unrelated to the changelog
+        #[clap(long)]
+        force: bool,
+
+        #[clap(flatten)]
+        commit_args: CommitArgs,
+
+        /// The npm registry to use, takes precedence over the "publishConfig.registry" of the
+        /// package.json file
+        #[clap(long)]
+        registry: Option<String>,
+
+        /// Publish the released packages after the version bump, commit and tag succeeded
+        #[clap(long, requires = "with-npm")]
+        publish: bool,
+
+        /// The access level of published packages, either "public" or "restricted"
+        #[clap(long, requires = "publish")]
+        access: Option<Access>,
+
+        /// The one-time password to publish with when your account has 2FA enabled, you're
+        /// prompted for it otherwise
+        #[clap(long, requires = "publish")]
+        otp: Option<String>,
+
+        /// Publish with a signed provenance statement (requires a supported CI environment)
+        #[clap(long, requires = "publish")]
+        provenance: bool,
+
+        /// Publish under this dist-tag instead of "latest"
+        #[clap(long, requires = "publish")]
+        dist_tag: Option<String>,
+
+        /// The package manager used for `--with-npm`, one of "npm", "yarn" or "pnpm". It is
+        /// detected from the lockfile by default
+        #[clap(long)]
+        package_manager: Option<PackageManager>,
     },
 
     /// Get the release notes of a specific version (or unreleased)
     Notes {
         /// The version you want to get the notes from. Should be a valid semver version or one of
-        /// "unreleased" or "latest".
+        /// "unreleased", "latest", "previous", "latest-<n>" or "first".
         version: Option<String>,
+
+        /// Exit with a non-zero exit code when the version doesn't exist or has no entries
+        #[clap(long)]
+        fail_if_empty: bool,
+
+        /// Translate the notes with the translation command configured for this locale, e.g.:
+        /// "fr"
+        #[clap(long)]
+        locale: Option<String>,
+
+        /// Combine the notes of all versions after this one, e.g.: the version a user upgrades
+        /// from. The entries are grouped per section and annotated with their version
+        #[clap(long, conflicts_with_all = &["version", "locale"])]
+        from: Option<String>,
+
+        /// The last version of the combined notes, defaults to the latest version
+        #[clap(long, requires = "from")]
+        to: Option<String>,
+
+        /// Render the latest N released versions, including their headings and links
+        #[clap(long, conflicts_with_all = &["version", "locale", "from"])]
+        last: Option<usize>,
+
+        /// Start the notes with the heading of the version, e.g.: "## [1.2.3] - 2024-05-01"
+        #[clap(long, conflicts_with_all = &["from", "last"])]
+        include_heading: bool,
+
+        /// Strip the markdown syntax, e.g.: for Slack, emails or terminals that don't render
+        /// markdown. Links become "title (url)"
+        #[clap(long, conflicts_with = "github")]
+        plain: bool,
+
+        /// Format the notes for a GitHub Release body: links to pull requests, issues and
+        /// commits are auto-linked (e.g.: "#123"), reference links are dropped and sections
+        /// become "##" headings
+        #[clap(long)]
+        github: bool,
+    },
+
+    /// Format the changelog according to the config
+    Fmt {
+        /// Sort the entries within each section, which can be one of: "alphabetical" or
+        /// "link-number"
+        #[clap(long)]
+        sort: Option<SortOrder>,
+    },
+
+    /// Show which entries changed compared to another git ref
+    Check {
+        /// The git ref to compare against
+        #[clap(long, default_value = "HEAD")]
+        base: String,
+    },
+
+    /// Export the changelog to another format, e.g.: an Atom feed of the releases
+    Export {
+        /// The format to export to, which can be one of: "atom" or "json"
+        #[clap(long, default_value = "atom")]
+        format: ExportFormat,
+
+        /// Write the export to this file instead of stdout
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+
+    /// Import entries from stdin, either in the format of `changelog export --format json` or as
+    /// a list of `{ "section": "Added", "message": "..." }` objects
+    Import {
+        /// The format to import from, which can be: "json"
+        #[clap(long, default_value = "json")]
+        format: ImportFormat,
+    },
+
+    /// Used in monorepos. Show how the packages depend on each other, and which of them have
+    /// unreleased changes, to decide in which order to release them
+    Graph {
+        /// The format of the graph, which can be one of: "tree" or "dot"
+        #[clap(long, default_value = "tree")]
+        format: GraphFormat,
+    },
+
+    /// Find reference links that no heading uses, and headings without a reference link
+    GrepLinks {
+        /// Remove the unused reference links and generate the missing ones
+        #[clap(long)]
+        fix: bool,
+    },
+
+    /// List the unreleased entries that are pending review, see the "review" config option
+    Review {
+        /// Approve the pending entries that contain this text
+        #[clap(long)]
+        approve: Option<String>,
+    },
+
+    /// Show whether the Unreleased section contains any changes
+    Unreleased {
+        /// Don't print anything, exit with 0 when there are unreleased changes and with 1 when
+        /// there is nothing to release
+        #[clap(long)]
+        check: bool,
+    },
+
+    /// List the commits since the last tag that are not referenced by any unreleased entry
+    Unlogged,
+
+    /// Add the versions of existing git tags that are missing from the changelog, with the commit
+    /// subjects between the tags as their entries
+    Backfill,
+
+    /// Add the versions of published GitHub Releases that are missing from the changelog, with the
+    /// notes of the release as their entries
+    ImportReleases,
+
+    /// Create or update the GitHub Release of every version in the changelog, so that GitHub
+    /// mirrors the changelog
+    SyncReleases,
+
+    /// Compare the notes of a version with its GitHub Release and report any drift
+    VerifyRelease {
+        /// The version you want to verify, e.g.: "1.2.3" or "latest"
+        version: String,
+    },
+
+    /// Verify that the current branch added an unreleased entry compared to a base ref, useful as
+    /// a CI check on pull requests. Commits that contain "[skip changelog]" skip the check
+    VerifyPr {
+        /// The git ref to compare against
+        #[clap(long, default_value = "origin/main")]
+        base: String,
+
+        /// Also look for "[skip changelog]" in this text, e.g.: the title and body of the pull
+        /// request
+        #[clap(long)]
+        message: Option<String>,
+    },
+
+    /// Install a git hook that reminds you (or blocks you) when commits change the code without a
+    /// new unreleased entry
+    InstallHooks {
+        /// The hook to install, which can be one of: "prepare-commit-msg" or "pre-push"
+        #[clap(long, default_value = "pre-push")]
+        hook: Hook,
+
+        /// Fail the commit or push instead of only printing a reminder
+        #[clap(long)]
+        block: bool,
+
+        /// The paths that require an entry when they change. You can also pass multiple
+        /// occurrences
+        #[clap(
+            long = "path",
+            name = "PATH",
+            default_value = "src/",
+            multiple_occurrences = true
+        )]
+        paths: Vec<String>,
+
+        /// The git ref that the pre-push hook compares against when the branch has no upstream
+        #[clap(long, default_value = "origin/main")]
+        base: String,
+
+        /// Remove the hook again
+        #[clap(long, conflicts_with_all = &["block", "PATH", "base"])]
+        uninstall: bool,
+    },
+
+    /// Combine another changelog into this one, e.g.: the changelog of a package that moves into
+    /// this repository. Versions are aligned by their number
+    Merge {
+        /// The changelog file to combine into this one
+        file: String,
+    },
+
+    /// Merge two versions of the changelog entry by entry, used as a git merge driver. Register it
+    /// with `changelog merge-driver --install`
+    MergeDriver {
+        /// The changelog that both branches started from (%O)
+        #[clap(required_unless_present = "install")]
+        base: Option<String>,
+
+        /// The changelog of the current branch (%A), the result is written to this file
+        #[clap(required_unless_present = "install")]
+        ours: Option<String>,
+
+        /// The changelog of the other branch (%B)
+        #[clap(required_unless_present = "install")]
+        theirs: Option<String>,
+
+        /// Register the merge driver in the git config and the `.gitattributes` file
+        #[clap(long)]
+        install: bool,
+    },
+
+    /// Post the notes of a released version to the webhooks of the "announce" config option, e.g.:
+    /// a Slack or Discord channel
+    Announce {
+        /// The version you want to announce, e.g.: "1.2.3" or "latest"
+        #[clap(default_value = "latest")]
+        version: String,
+
+        /// Print the payloads instead of posting them
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Drive the changelog with JSON commands, useful for bots and other tools
+    Api {
+        /// Read a JSON command (or a list of commands) from stdin, e.g.:
+        /// {"command": "add", "section": "Fixed", "message": "..."} or
+        /// {"command": "release", "version": "1.2.3"}
+        #[clap(long, required = true)]
+        stdin: bool,
     },
 
+    /// Run a language server for the changelog over stdin/stdout, for editor integrations
+    Lsp,
+
+    /// Browse the versions and their entries in an interactive dashboard, to add, edit, move and
+    /// delete entries or to release a new version
+    Ui,
+
     /// Get a list of all versions
     List {
         /// Amount of versions to show
@@ -204,23 +603,153 @@ enum Commands {
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    color_eyre::install()?;
-
+fn main() -> Result<()> {
     let args = Cli::parse();
 
-    // Resolve the current working directory
-    let pwd = fs::canonicalize(&args.pwd)?;
+    let color = args.color.enabled();
+    colored::control::set_override(color);
+    match color {
+        true => color_eyre::install()?,
+        false => HookBuilder::default().theme(Theme::new()).install()?,
+    }
+
+    let github_output = args.github_output;
+    let result = run(args);
+    if let (true, Err(err)) = (github_output, &result) {
+        actions::annotate("error", &err.to_string());
+    }
+
+    result
+}
+
+fn run(args: Cli) -> Result<()> {
+    // Prompts (and editors) would wait for input forever without a terminal
+    let interactive =
+        !args.non_interactive && std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+
+    // Resolve the current working directory, inside a package of a monorepo we operate on the
+    // monorepo with that package as the scope
+    let (pwd, detected_scope) = match PackageJSON::find_workspace(&fs::canonicalize(&args.pwd)?)? {
+        Some((root, package)) => (root.pwd().to_path_buf(), Some(package)),
+        None => (fs::canonicalize(&args.pwd)?, None),
+    };
+
+    // Resolve the config
+    let mut config = Config::from_directory(&pwd)?;
+
+    if let Some(timezone) = args.timezone {
+        config.timezone = timezone;
+    }
 
     // Resolve the package.json manifest file
-    let root_package = PackageJSON::from_directory(&pwd)?;
+    let mut root_package = PackageJSON::from_directory(&pwd)?;
+
+    // Projects without a manifest track their version in the version files instead
+    if root_package.kind() == ManifestKind::None {
+        if let Some(version_file) = config.version_files.first() {
+            *root_package.version_mut() = version_file.read(&pwd)?.parse()?;
+        }
+    }
+
+    // The API is not interactive, the scopes are part of the commands themselves
+    if let Commands::Api { .. } = &args.command {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+
+        let document: ApiDocument = serde_json::from_str(&input)?;
+        let api = Api::new(
+            &pwd,
+            &args.filename,
+            &config,
+            match root_package.is_monorepo() {
+                true => root_package.packages()?,
+                false => vec![],
+            },
+        );
+
+        let results: Vec<_> = document
+            .commands()
+            .into_iter()
+            .map(|command| api.execute(command))
+            .collect();
+
+        println!("{}", serde_json::to_string_pretty(&results)?);
+
+        return Ok(());
+    }
+
+    // The language server works on the documents that the editor opens
+    if let Commands::Lsp = &args.command {
+        return LanguageServer::new(&pwd, &args.filename, &config).run();
+    }
+
+    // The graph always shows every package of the workspace
+    if let Commands::Graph { format } = &args.command {
+        if !root_package.is_monorepo() {
+            return Err(eyre!("The graph is only available in monorepos"));
+        }
+
+        let graph = Graph::new(&root_package.packages()?, |package| {
+            Changelog::new(package.pwd(), &args.filename, &config)
+                .map(|changelog| changelog.has_changes(Some(package)))
+                .unwrap_or(false)
+        });
+
+        match format {
+            GraphFormat::Tree => output(graph.tree()),
+            GraphFormat::Dot => println!("{}", graph.dot()),
+        }
+
+        return Ok(());
+    }
 
     // Resolve the current scopes
     let scopes: Option<Vec<PackageJSON>> = if root_package.is_monorepo() {
         let options = root_package.packages()?;
+        let repo = Git::new(Some(&pwd))?;
+
+        if !args.all_scopes
+            && !args.last_scopes
+            && args.scopes.is_empty()
+            && detected_scope.is_none()
+            && !interactive
+        {
+            return Err(eyre!(
+                "Pass the package(s) to work on with {} or {}, they can't be picked interactively",
+                "--scope".blue().bold(),
+                "--all-scopes".blue().bold()
+            ));
+        }
+
+        if args.all_scopes {
+            Some(options)
+        } else if args.last_scopes {
+            let last_scopes = repo.config(LAST_SCOPES).ok_or_else(|| {
+                eyre!(
+                    "No packages were picked before, pick them once without {}",
+                    "--last-scopes".blue().bold()
+                )
+            })?;
+            let last_scopes: Vec<&str> = last_scopes.split(',').collect();
+            let resolved_scopes: Vec<PackageJSON> = options
+                .into_iter()
+                .filter(|package| last_scopes.contains(&package.name()))
+                .collect();
+
+            if resolved_scopes.is_empty() {
+                return Err(eyre!(
+                    "None of the previously picked packages exist anymore"
+                ));
+            }
+
+            Some(resolved_scopes)
+        } else if let (true, Some(package)) = (args.scopes.is_empty(), detected_scope) {
+            Some(vec![package])
+        } else if args.scopes.is_empty() {
+            // Pre-check the packages that were picked last time
+            let last_scopes = repo.config(LAST_SCOPES).unwrap_or_default();
+            let last_scopes: Vec<&str> = last_scopes.split(',').collect();
 
-        if args.scopes.is_empty() {
             let resolved_scopes: Vec<PackageJSON> = MultiSelect::new()
                 .with_prompt("Select the package(s) to work on")
                 .items(
@@ -229,6 +758,12 @@ async fn main() -> Result<()> {
                         .map(|package| package.display_name())
                         .collect::<Vec<_>>(),
                 )
+                .defaults(
+                    &options
+                        .iter()
+                        .map(|package| last_scopes.contains(&package.name()))
+                        .collect::<Vec<_>>(),
+                )
                 .clear(true)
                 .interact()
                 .map(|indexes| {
@@ -242,13 +777,42 @@ async fn main() -> Result<()> {
                 return Err(eyre!("No packages selected"));
             }
 
+            if repo.is_git_repo() {
+                repo.set_config(
+                    LAST_SCOPES,
+                    &resolved_scopes
+                        .iter()
+                        .map(|package| package.name())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                )?;
+            }
+
             Some(resolved_scopes)
         } else {
+            let scopes = config.expand_scopes(&args.scopes);
+            let unknown: Vec<&str> = scopes
+                .iter()
+                .filter(|scope| !options.iter().any(|package| package.name().eq(*scope)))
+                .map(|scope| scope.as_str())
+                .collect();
+
+            if !unknown.is_empty() {
+                return Err(eyre!(
+                    "Couldn't find the package(s) or scope group(s): {}",
+                    unknown.join(", ").blue().bold()
+                ));
+            }
+
             let resolved_scopes: Vec<PackageJSON> = options
                 .into_iter()
-                .filter(|package| args.scopes.iter().any(|scope| package.name().eq(scope)))
+                .filter(|package| scopes.iter().any(|scope| package.name().eq(scope)))
                 .collect();
 
+            if resolved_scopes.is_empty() {
+                return Err(eyre!("No packages selected"));
+            }
+
             Some(resolved_scopes)
         }
     } else {
@@ -256,14 +820,27 @@ async fn main() -> Result<()> {
     };
 
     match &args.command {
-        Commands::Init => {
+        Commands::Init {
+            migrate,
+            date_locale,
+        } => {
             match scopes {
                 Some(scopes) => {
-                    let mut messages: Vec<_> = vec![];
-                    for scope in scopes {
-                        let mut changelog = Changelog::new(scope.pwd(), &args.filename)?;
-                        messages.push(changelog.init()?);
-                    }
+                    let (filename, config, migrate, date_locale) = (
+                        args.filename.clone(),
+                        config.clone(),
+                        *migrate,
+                        *date_locale,
+                    );
+                    let messages = map_scopes(&scopes, move |scope| {
+                        let mut changelog = Changelog::new(scope.pwd(), &filename, &config)?;
+                        match migrate {
+                            true => changelog.migrate(date_locale),
+                            false => changelog.init(),
+                        }
+                    })?
+                    .into_iter()
+                    .collect::<Result<Vec<_>>>()?;
 
                     output(
                         messages
@@ -274,154 +851,314 @@ async fn main() -> Result<()> {
                     )
                 }
                 None => {
-                    let mut changelog = Changelog::new(&pwd, &args.filename)?;
-                    output(changelog.init()?);
+                    let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+                    output(match migrate {
+                        true => changelog.migrate(*date_locale)?,
+                        false => changelog.init()?,
+                    });
                 }
             }
 
             Ok(())
         }
-        Commands::Add {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Fix {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Change {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Remove {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        }
-        | Commands::Deprecate {
-            link,
-            name,
-            message,
-            commit,
-            edit,
-        } => {
-            match &scopes {
-                Some(scopes) => {
-                    let mut output_messages: HashMap<PathBuf, Vec<String>> = HashMap::default();
+        Commands::Add { .. }
+        | Commands::Fix { .. }
+        | Commands::Change { .. }
+        | Commands::Remove { .. }
+        | Commands::Deprecate { .. } => {
+            let (entry, name) = match &args.command {
+                Commands::Add { entry, name } => (entry, name.clone()),
+                Commands::Fix { entry, name }
+                | Commands::Change { entry, name }
+                | Commands::Remove { entry, name }
+                | Commands::Deprecate { entry, name } => (entry, Some(name.clone())),
+                _ => unreachable!(),
+            };
+            let EntryOptions {
+                links,
+                messages,
+                commit,
+                edit,
+                sort,
+                position,
+                breaking,
+                by,
+                commit_args,
+            } = entry;
+            let commit_options = commit_args.options();
+
+            if *edit && !interactive {
+                output_warning("Skipped editing the entry, there is no terminal".to_string());
+            }
+            let edit = *edit && interactive;
 
-                    for package in scopes {
-                        let mut changelog = Changelog::new(package.pwd(), &args.filename)?;
-
-                        let messages = if let Some(message) = message {
-                            changelog.add_list_item_to_section(
-                                name,
-                                &message.to_string(),
-                                *edit,
-                                Some(package),
-                            );
-                            vec![message.to_string()]
-                        } else if let Some(link) = link {
-                            let data: GitHubInfo = link.parse().unwrap();
-                            changelog.add_list_item_to_section(
-                                name,
-                                &data.to_string(),
-                                *edit,
-                                Some(package),
-                            );
-                            vec![data.to_string()]
-                        } else {
-                            let preface = &format!(
-                                include_str!("./fixtures/add_entry.txt"),
-                                name.to_lowercase(),
-                            );
+            if sort.is_some() {
+                config.sort = *sort;
+            }
 
-                            let data = match rich_edit(Some(preface)) {
-                                Some(data) => {
-                                    let data = data.trim();
-                                    let data: Vec<_> = data
-                                        .lines()
-                                        .map(|line| line.trim())
-                                        .filter(|line| !line.is_empty())
-                                        .filter(|line| !line.starts_with('#'))
-                                        .map(|line| line.to_string())
-                                        .collect();
-
-                                    for line in &data {
-                                        changelog.add_list_item_to_section(
-                                            name,
-                                            line,
-                                            *edit,
-                                            Some(package),
-                                        );
-                                    }
-
-                                    if data.is_empty() {
-                                        None
-                                    } else {
-                                        Some(data)
-                                    }
-                                }
-                                None => None,
-                            };
+            if let Some(position) = position {
+                config.position = *position;
+            }
 
-                            data.unwrap_or_else(|| {
-                                output(format!(
-                                    "No {}, {} or {} provided, run `{}` for more info",
-                                    "<LINK>".blue().bold(),
-                                    "<COMMIT HASH>".blue().bold(),
-                                    "--message".blue().bold(),
-                                    format!(
-                                        "changelog {} --help",
-                                        match &args.command {
-                                            Commands::Add { .. } => "add",
-                                            Commands::Fix { .. } => "fix",
-                                            Commands::Change { .. } => "change",
-                                            Commands::Remove { .. } => "remove",
-                                            Commands::Deprecate { .. } => "deprecate",
-                                            _ => unreachable!(),
-                                        }
-                                    )
-                                    .blue()
-                                    .bold()
-                                ));
+            // Only ask for the section when an entry doesn't end up in a section on its own
+            let section = || match &name {
+                Some(name) => Ok(name.to_string()),
+                None => pick_section(&config, interactive),
+            };
+            // Other tools can pipe the entries in, one per line
+            let from_stdin = match (messages.as_slice(), links.is_empty()) {
+                ([message], _) if message == "-" => true,
+                ([], true) => !std::io::stdin().is_terminal(),
+                _ => false,
+            };
 
-                                std::process::exit(1);
-                            })
-                        };
+            // The section and the text of every entry
+            let entries: Vec<(String, String)> = if from_stdin {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input)?;
 
-                        output_messages.insert(package.pwd().to_path_buf(), messages);
+                let lines = entry_lines(&input);
+                if lines.is_empty() {
+                    return Err(eyre!("Couldn't read any entries from stdin"));
+                }
 
-                        changelog.persist()?;
+                let name = section()?;
+                lines.into_iter().map(|line| (name.clone(), line)).collect()
+            } else if !messages.is_empty() {
+                let name = section()?;
+                messages
+                    .iter()
+                    .map(|message| (name.clone(), message.to_string()))
+                    .collect()
+            } else if !links.is_empty() {
+                let command = match args.command {
+                    Commands::Fix { .. } => "fix",
+                    Commands::Change { .. } => "change",
+                    Commands::Remove { .. } => "remove",
+                    Commands::Deprecate { .. } => "deprecate",
+                    _ => "add",
+                };
+
+                // Fetch the titles of all the links at the same time
+                let data: Vec<Result<GitHubInfo, String>> = concurrently(
+                    links.to_vec(),
+                    |link| link.to_string(),
+                    |link| link.parse::<GitHubInfo>(),
+                )?;
+
+                let rendered = links
+                    .iter()
+                    .zip(data)
+                    .map(|(link, data)| {
+                        let data = data.map_err(|e| eyre!("{}: {}", link, e))?;
+
+                        Ok(config.render_entry(&data, link, command))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let name = match rendered.iter().any(|(section, _)| section.is_none()) {
+                    true => section()?,
+                    false => String::new(),
+                };
+                rendered
+                    .into_iter()
+                    .map(|(section, entry)| (section.unwrap_or_else(|| name.clone()), entry))
+                    .collect()
+            } else {
+                let name = section()?;
+                let preface = &format!(
+                    include_str!("./fixtures/add_entry.txt"),
+                    name.to_lowercase()
+                );
+
+                let data = match interactive.then(|| rich_edit(Some(preface))).flatten() {
+                    Some(Edit::Saved(data)) => {
+                        let data = entry_lines(&data);
+
+                        if data.is_empty() {
+                            None
+                        } else {
+                            Some(data)
+                        }
                     }
-
-                    if *commit {
+                    Some(Edit::Unchanged) => {
+                        output(
+                            "The editor was closed without changes, no entry was added."
+                                .to_string(),
+                        );
+                        std::process::exit(1);
+                    }
+                    Some(Edit::Failed(path)) => {
+                        output(format!(
+                            "The editor exited with an error, your changes are kept at: {}",
+                            path.display().to_string().white().dimmed()
+                        ));
+                        std::process::exit(1);
+                    }
+                    None => None,
+                };
+
+                data.unwrap_or_else(|| {
+                    output(format!(
+                        "No {}, {} or {} provided, run `{}` for more info",
+                        "<LINK>".blue().bold(),
+                        "<COMMIT HASH>".blue().bold(),
+                        "--message".blue().bold(),
+                        format!(
+                            "changelog {} --help",
+                            match &args.command {
+                                Commands::Add { .. } => "add",
+                                Commands::Fix { .. } => "fix",
+                                Commands::Change { .. } => "change",
+                                Commands::Remove { .. } => "remove",
+                                Commands::Deprecate { .. } => "deprecate",
+                                _ => unreachable!(),
+                            }
+                        )
+                        .blue()
+                        .bold()
+                    ));
+
+                    std::process::exit(1);
+                })
+                .into_iter()
+                .map(|line| (name.clone(), line))
+                .collect()
+            };
+
+            // Link bare issue references in manual messages
+            let entries = match (links.is_empty(), config.autolink) {
+                (true, true) => match Repo::from_git_repo(&pwd) {
+                    Ok(repo) => entries
+                        .into_iter()
+                        .map(|(section, entry)| (section, autolink(&entry, &repo)))
+                        .collect(),
+                    Err(_) => entries,
+                },
+                _ => entries,
+            };
+
+            // Record who owns the entries, and mark them for review
+            let owner = by.clone().or_else(|| match config.review {
+                true => Git::new(Some(&pwd)).ok().and_then(|repo| {
+                    repo.config("github.user")
+                        .map(|user| format!("@{}", user))
+                        .or_else(|| repo.config("user.name"))
+                }),
+                false => None,
+            });
+            let entries: Vec<(String, String)> = entries
+                .into_iter()
+                .flat_map(|(section, entry)| {
+                    // Breaking changes are either prefixed or moved to their own section
+                    let (section, entries) = match breaking {
+                        true => config.breaking.apply(&section, vec![entry]),
+                        false => (section, vec![entry]),
+                    };
+
+                    entries
+                        .into_iter()
+                        .map(move |entry| (section.clone(), entry))
+                })
+                .map(|(section, entry)| {
+                    let entry = match &owner {
+                        Some(owner) => metadata::set(&entry, "by", owner),
+                        None => entry,
+                    };
+
+                    match config.review {
+                        true => (section, metadata::set(&entry, "review", "pending")),
+                        false => (section, entry),
+                    }
+                })
+                .collect();
+
+            let mut sections: Vec<&str> = vec![];
+            for (section, _) in &entries {
+                if !sections.contains(&section.as_str()) {
+                    sections.push(section);
+                }
+            }
+            let summary = match entries.len() {
+                1 => format!(
+                    "Added a new entry to the {} section",
+                    sections[0].blue().bold()
+                ),
+                amount => format!(
+                    "Added {} new entries to the {} {}",
+                    amount,
+                    conjunction(
+                        &sections
+                            .iter()
+                            .map(|section| section.blue().bold())
+                            .collect::<Vec<_>>()
+                    ),
+                    match sections.len() {
+                        1 => "section",
+                        _ => "sections",
+                    }
+                ),
+            };
+
+            match &scopes {
+                Some(scopes) => {
+                    // Returns the duplicate entries that were skipped
+                    let (filename, package_config, package_entries, edit) =
+                        (args.filename.clone(), config.clone(), entries.clone(), edit);
+                    let add_entries = move |package: &PackageJSON| -> Result<Vec<String>> {
+                        let mut changelog =
+                            Changelog::new(package.pwd(), &filename, &package_config)?;
+                        let mut skipped = vec![];
+
+                        for (section, entry) in &package_entries {
+                            let entry = match edit {
+                                true => edit_entry(section, entry, Some(package))
+                                    .unwrap_or_else(|| entry.to_string()),
+                                false => entry.to_string(),
+                            };
+
+                            if !changelog.add_list_item_to_section(section, &entry)? {
+                                skipped.push(entry);
+                            }
+                        }
+
+                        changelog.persist()?;
+                        Ok(skipped)
+                    };
+
+                    // The editor can only be opened for one package at a time
+                    let skipped = match edit {
+                        true => scopes.iter().map(add_entries).collect::<Vec<_>>(),
+                        false => map_scopes(scopes, add_entries)?,
+                    };
+                    for skipped in skipped {
+                        for entry in skipped? {
+                            output_warning(format!("Skipped duplicate entry: {}", entry));
+                        }
+                    }
+
+                    if *commit {
                         // Commit the CHANGELOG.md file
                         let g = Git::new(Some(&pwd))?;
 
                         for package in scopes {
-                            let path = package.pwd().join(&args.filename);
+                            let path = package.pwd().join(
+                                config
+                                    .for_package(package.pwd())?
+                                    .filename
+                                    .unwrap_or_else(|| args.filename.clone()),
+                            );
                             if let Some(path) = path.to_str() {
                                 g.add(path)?;
                             }
                         }
 
-                        g.commit("update changelog")?;
+                        g.commit(&config.commit_message(None, None), &commit_options)?;
                     }
 
                     output(format!(
-                        "Added a new entry to the {} section {}:",
-                        name.blue().bold(),
+                        "{} {}:",
+                        summary,
                         format!(
                             "({})",
                             &conjunction(
@@ -435,22 +1172,16 @@ async fn main() -> Result<()> {
                     for package in scopes {
                         output_indented(format!("{}", package.name().white().dimmed()));
                         eprintln!();
-                        let messages = output_messages.get(&package.pwd().to_path_buf()).unwrap();
-                        let changelog = Changelog::new(package.pwd(), &args.filename)?;
+                        let changelog = Changelog::new(package.pwd(), &args.filename, &config)?;
 
                         if let Some(node) =
                             changelog.get_contents_of_section_scope(None, Some(package))
                         {
-                            let mut text = node.to_string();
-
-                            for message in messages {
-                                text = text.replace(
-                                    &format!("- {}", message),
-                                    &format!("- {}", message.green().bold()),
-                                );
-                            }
-
-                            output_indented(text);
+                            output_indented(highlight_entries(
+                                node.to_string(),
+                                changelog.bullet_marker(),
+                                &entries,
+                            ));
                             eprintln!()
                         } else {
                             output_indented("No changes".white().dimmed().italic().to_string());
@@ -459,86 +1190,28 @@ async fn main() -> Result<()> {
                     }
                 }
                 None => {
-                    let mut changelog = Changelog::new(&pwd, &args.filename)?;
-
-                    let messages = if let Some(message) = message {
-                        changelog.add_list_item_to_section(name, &message.to_string(), *edit, None);
-                        vec![message.to_string()]
-                    } else if let Some(link) = link {
-                        let data: GitHubInfo = link.parse().unwrap();
-                        changelog.add_list_item_to_section(name, &data.to_string(), *edit, None);
-                        vec![data.to_string()]
-                    } else {
-                        let preface = &format!(
-                            include_str!("./fixtures/add_entry.txt"),
-                            name.to_lowercase()
-                        );
-
-                        let data = match rich_edit(Some(preface)) {
-                            Some(data) => {
-                                let data = data.trim();
-                                let data: Vec<_> = data
-                                    .lines()
-                                    .map(|line| line.trim())
-                                    .filter(|line| !line.is_empty())
-                                    .filter(|line| !line.starts_with('#'))
-                                    .map(|line| line.to_string())
-                                    .collect();
-
-                                for line in &data {
-                                    changelog.add_list_item_to_section(name, line, *edit, None);
-                                }
+                    let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
 
-                                if data.is_empty() {
-                                    None
-                                } else {
-                                    Some(data)
-                                }
-                            }
-                            None => None,
+                    for (section, entry) in &entries {
+                        let entry = match edit {
+                            true => edit_entry(section, entry, None)
+                                .unwrap_or_else(|| entry.to_string()),
+                            false => entry.to_string(),
                         };
 
-                        data.unwrap_or_else(|| {
-                            output(format!(
-                                "No {}, {} or {} provided, run `{}` for more info",
-                                "<LINK>".blue().bold(),
-                                "<COMMIT HASH>".blue().bold(),
-                                "--message".blue().bold(),
-                                format!(
-                                    "changelog {} --help",
-                                    match &args.command {
-                                        Commands::Add { .. } => "add",
-                                        Commands::Fix { .. } => "fix",
-                                        Commands::Change { .. } => "change",
-                                        Commands::Remove { .. } => "remove",
-                                        Commands::Deprecate { .. } => "deprecate",
-                                        _ => unreachable!(),
-                                    }
-                                )
-                                .blue()
-                                .bold()
-                            ));
-
-                            std::process::exit(1);
-                        })
-                    };
+                        if !changelog.add_list_item_to_section(section, &entry)? {
+                            output_warning(format!("Skipped duplicate entry: {}", entry));
+                        }
+                    }
 
-                    output(format!(
-                        "Added a new entry to the {} section:",
-                        name.blue().bold()
-                    ));
+                    output(format!("{}:", summary));
 
                     if let Some(node) = changelog.get_contents_of_section(&None) {
-                        let mut text = node.to_string();
-
-                        for message in messages {
-                            text = text.replace(
-                                &format!("- {}", message),
-                                &format!("- {}", message.green().bold()),
-                            );
-                        }
-
-                        output_indented(text);
+                        output_indented(highlight_entries(
+                            node.to_string(),
+                            changelog.bullet_marker(),
+                            &entries,
+                        ));
                         eprintln!()
                     }
 
@@ -547,108 +1220,501 @@ async fn main() -> Result<()> {
                     if *commit {
                         // Commit the CHANGELOG.md file
                         Git::new(Some(&pwd))?
-                            .add(changelog.file_path_str())?
-                            .commit("update changelog")?;
+                            .add(changelog.file_path_str()?)?
+                            .commit(&config.commit_message(None, None), &commit_options)?;
                     }
                 }
             };
 
             Ok(())
         }
-        Commands::Notes { version } => {
+        Commands::Notes {
+            version,
+            fail_if_empty,
+            locale,
+            from,
+            to,
+            last,
+            include_heading,
+            plain,
+            github,
+        } => {
+            let mut empty = false;
+            let label = match (last, from, version) {
+                (Some(last), _, _) => format!("the last {} versions", last),
+                (None, Some(from), _) => format!(
+                    "{}...{}",
+                    from.to_lowercase(),
+                    to.as_deref().unwrap_or("latest").to_lowercase()
+                ),
+                (None, None, Some(version)) => version.to_lowercase(),
+                (None, None, None) => "latest".to_string(),
+            };
+
+            // Whether the notes are empty, and the notes themselves. It owns what it needs, so that
+            // the notes of every scope can be rendered in their own task.
+            let notes = {
+                let (version, locale, from, to, last) = (
+                    version.clone(),
+                    locale.clone(),
+                    from.clone(),
+                    to.clone(),
+                    *last,
+                );
+                let (plain, github, include_heading) = (*plain, *github, *include_heading);
+
+                let repo = Repo::from_git_repo(&pwd).ok();
+                let render = move |notes: String| match (plain, github) {
+                    (true, _) => notes
+                        .parse::<Node>()
+                        .map(|node| markdown::plain::render(&node))
+                        .unwrap_or(notes),
+                    (_, true) => notes
+                        .parse::<Node>()
+                        .map(|node| markdown::github::render(&node, repo.as_ref()))
+                        .unwrap_or(notes),
+                    _ => notes,
+                };
+
+                Arc::new(move |changelog: &Changelog| match (last, &from) {
+                    (Some(last), _) => match changelog.last_notes(last) {
+                        notes if notes.is_empty() => {
+                            (true, "No versions yet".white().dimmed().to_string())
+                        }
+                        notes => (false, render(notes)),
+                    },
+                    (None, Some(from)) => match changelog.notes_between(from, to.as_deref()) {
+                        Ok(notes) if notes.is_empty() => (
+                            true,
+                            "No changes in this range".white().dimmed().to_string(),
+                        ),
+                        Ok(notes) => (false, render(notes)),
+                        Err(err) => (true, err.to_string().red().to_string()),
+                    },
+                    (None, None) => (
+                        !changelog.has_notes(version.as_ref()),
+                        match &locale {
+                            Some(locale) => changelog.translate_notes(version.as_ref(), locale),
+                            None => changelog.notes(version.as_ref()),
+                        }
+                        .map(|notes| match changelog.version_heading(version.as_ref()) {
+                            Some(heading) if include_heading => {
+                                format!("## {}\n\n{}", heading, notes.trim())
+                            }
+                            _ => notes,
+                        })
+                        .map(&render)
+                        .unwrap_or_else(|err| err.to_string().red().to_string()),
+                    ),
+                })
+            };
+
             match scopes {
                 Some(scopes) => {
-                    for package in scopes {
-                        let message = Changelog::new(package.pwd(), &args.filename)?
-                            .notes(version.as_ref())
-                            .unwrap_or_else(|err| err.to_string().red().to_string());
+                    let mut notes_paths = serde_json::Map::new();
+                    let (filename, package_config) = (args.filename.clone(), config.clone());
+                    let scope_notes = notes.clone();
+                    let results = map_scopes(&scopes, move |package| {
+                        Changelog::new(package.pwd(), &filename, &package_config)
+                            .map(|changelog| scope_notes(&changelog))
+                    })?;
+
+                    for (package, result) in scopes.iter().zip(results) {
+                        let (is_empty, message) = result?;
+                        empty |= is_empty;
+
+                        if args.github_output && !is_empty {
+                            let path = actions::write_notes(
+                                &format!("{}-{}", package.name(), label),
+                                &message,
+                            )?;
+                            notes_paths.insert(
+                                package.name().to_string(),
+                                path.display().to_string().into(),
+                            );
+                        }
 
                         output_title(
-                            match version {
-                                Some(version) => format!(
-                                    "Notes for {}, {}",
-                                    package.name().white().dimmed(),
-                                    version.to_lowercase().blue()
-                                ),
-                                None => format!(
-                                    "Notes for {}, {}",
-                                    package.name().white().dimmed(),
-                                    "latest".blue()
-                                ),
-                            },
+                            format!(
+                                "Notes for {}, {}",
+                                package.name().white().dimmed(),
+                                label.blue()
+                            ),
                             message,
                         )
                     }
+
+                    if args.github_output {
+                        actions::set_output(
+                            "notes-paths",
+                            &serde_json::Value::Object(notes_paths).to_string(),
+                        )?;
+                    }
                 }
                 None => {
-                    let message = Changelog::new(&pwd, &args.filename)?
-                        .notes(version.as_ref())
-                        .unwrap_or_else(|err| err.to_string().red().to_string());
-
-                    output_title(
-                        match version {
-                            Some(version) => format!("Notes for {}", version.to_lowercase().blue()),
-                            None => format!("Notes for {}", "latest".blue()),
-                        },
-                        message,
-                    )
+                    let changelog = Changelog::new(&pwd, &args.filename, &config)?;
+                    let (is_empty, message) = notes(&changelog);
+                    empty = is_empty;
+
+                    if args.github_output {
+                        let heading = match (last, from) {
+                            (None, None) => changelog.version_heading(version.as_ref()),
+                            _ => None,
+                        };
+                        if let Some(heading) = heading {
+                            actions::set_output("version", &heading_version(&heading))?;
+                        }
+                        if !is_empty {
+                            let path = actions::write_notes(&label, &message)?;
+                            actions::set_output("notes-path", &path.display().to_string())?;
+                        }
+                    }
+
+                    output_title(format!("Notes for {}", label.blue()), message)
+                }
+            }
+
+            if args.github_output {
+                actions::set_output("empty", &empty.to_string())?;
+            }
+
+            if *fail_if_empty && empty {
+                if args.github_output {
+                    actions::annotate("error", &format!("There are no notes for {}", label));
                 }
+                std::process::exit(1);
             }
 
             Ok(())
         }
-        Commands::Release { version, with_npm } => {
+        Commands::Release {
+            version,
+            preid,
+            with_npm,
+            with_cargo,
+            notes_file,
+            name,
+            umbrella,
+            allow_empty,
+            include_private,
+            sign,
+            annotate,
+            tag_format,
+            yes,
+            dependents,
+            force,
+            commit_args,
+            registry,
+            publish,
+            access,
+            otp,
+            provenance,
+            dist_tag,
+            package_manager,
+        } => {
+            let package_manager = package_manager.unwrap_or_else(|| PackageManager::detect(&pwd));
+            // Pre-releases are published under their preid (or "next") instead of "latest"
+            let publish_options = |version: &SemVer| PublishOptions {
+                access: *access,
+                tag: dist_tag.clone().or_else(|| version.dist_tag()),
+                otp: otp.clone(),
+                provenance: *provenance,
+                interactive,
+            };
+
+            let commit_options = commit_args.options();
+
+            // Unrelated work would be swept into the release commit otherwise
+            let mut filenames = vec![args.filename.clone()];
+            for package in scopes.iter().flatten() {
+                filenames.extend(config.for_package(package.pwd())?.filename);
+            }
+            let repo = Git::new(Some(&pwd))?;
+            if !force
+                && repo.is_git_repo()
+                && !repo.is_clean(|path| {
+                    filenames
+                        .iter()
+                        .any(|filename| is_changelog_file(path, filename))
+                })?
+            {
+                return Err(eyre!(
+                    "The working tree has uncommitted changes, commit or stash them first (or use {})",
+                    "--force".blue().bold()
+                ));
+            }
+
+            if notes_file.is_some() {
+                config.notes_file = notes_file.clone();
+            }
+
+            if name.is_some() {
+                config.release.name = name.clone();
+            }
+
+            if *allow_empty {
+                config.release.allow_empty = true;
+            }
+
+            if *sign {
+                config.release.sign = true;
+            }
+
+            if *annotate {
+                config.release.annotate = true;
+            }
+
+            if tag_format.is_some() {
+                config.release.tag_format = tag_format.clone();
+            }
+
             match &scopes {
                 Some(scopes) => {
-                    let repo = Git::new(Some(&pwd))?;
+                    let repo = Git::new(Some(&pwd))?.with_signing(config.release.sign);
                     let mut changelog_commit_messages: Vec<String> = vec![];
                     let mut output_messages: Vec<String> = vec![];
+                    let mut released: Vec<String> = vec![];
+                    let mut github_releases: Vec<serde_json::Value> = vec![];
+
+                    // In lerna's fixed mode, all packages are released with the same version
+                    let fixed_version = root_package
+                        .fixed_version()
+                        .map(|mut fixed_version| fixed_version.change_to(version, preid.as_deref()))
+                        .transpose()?;
+
+                    // Dependencies are released before the packages that depend on them, so that
+                    // the dependents can mention the new versions. The packages are picked and
+                    // confirmed one by one first, because the dependents depend on the versions of
+                    // the earlier releases.
+                    let workspace = root_package.packages()?;
+                    let mut queue: VecDeque<PackageJSON> =
+                        PackageJSON::sort_by_dependencies(scopes.clone()).into();
+                    let mut handled: Vec<String> = vec![];
+                    let mut dependents_versions: HashMap<String, String> = HashMap::new();
+                    let mut dependency_updates: HashMap<String, Vec<String>> = HashMap::new();
+                    let mut planned: Vec<(PackageJSON, Changelog, SemVer)> = vec![];
+
+                    while let Some(package) = queue.pop_front() {
+                        handled.push(package.name().to_string());
+
+                        // The root package is released as the umbrella version instead
+                        if umbrella.is_some() && package.is_root() {
+                            continue;
+                        }
 
-                    for package in scopes {
-                        let mut changelog = Changelog::new(package.pwd(), &args.filename)?;
+                        let mut changelog = Changelog::new(package.pwd(), &args.filename, &config)?;
+                        for entry in dependency_updates.get(package.name()).into_iter().flatten() {
+                            changelog.add_list_item_to_section("Changed", entry)?;
+                        }
 
-                        let pwd_str = package.pwd().to_str().unwrap();
+                        let strategy = dependents_versions.get(package.name()).unwrap_or(version);
                         let mut package = package.clone();
-                        let package_version = package.version_mut();
-                        let version = package_version.change_to(version)?;
+                        let version = match &fixed_version {
+                            Some(fixed_version) => fixed_version.clone(),
+                            None => package
+                                .version_mut()
+                                .change_to(strategy, preid.as_deref())?,
+                        };
+
+                        if package.is_private() && !include_private {
+                            output_messages.push(
+                                format!("- Skipping private package {}", package.name())
+                                    .dimmed()
+                                    .to_string(),
+                            );
+                            continue;
+                        }
+
+                        // Only release the packages that actually changed
+                        if !config.release.allow_empty && !changelog.has_changes(Some(&package)) {
+                            output_messages.push(
+                                format!("- No changes for {}", package.name())
+                                    .dimmed()
+                                    .to_string(),
+                            );
+                            continue;
+                        }
+
+                        if changelog.requires_major_bump(&version) {
+                            output_warning(format!(
+                                "{} contains breaking changes, but {} is not a major release",
+                                package.name(),
+                                version.to_string().blue().bold()
+                            ));
+                        }
 
-                        // TODO: Only release when things changed?
-                        // if !changelog.has_changes(&scope) {
-                        //     continue;
-                        // }
+                        if !confirm_release(
+                            &changelog,
+                            &version,
+                            Some(&package),
+                            *yes,
+                            interactive,
+                        )? {
+                            output_messages
+                                .push(format!("- Skipped {}", package.name().white().dimmed()));
+                            continue;
+                        }
 
                         output_messages.push(format!(
                             "- Releasing {} for {}",
                             version.to_string().green().bold(),
                             package.name().white().dimmed()
                         ));
-                        changelog.release(&version, Some(&package))?;
+                        released.push(format!("`{}@{}`", package.name(), version));
 
-                        // Add the CHANGELOG.md file, so that we can commit it later.
-                        repo.add(changelog.file_path_str())?;
+                        for dependent in package.dependents(&workspace) {
+                            if dependent.is_root() {
+                                continue;
+                            }
+
+                            let updates = dependency_updates
+                                .entry(dependent.name().to_string())
+                                .or_default();
+                            updates.push(format!(
+                                "Updated dependency `{}` to `v{}`",
+                                package.name(),
+                                version
+                            ));
+
+                            if updates.len() > 1
+                                || handled.iter().any(|name| name == dependent.name())
+                                || queue.iter().any(|queued| queued.name() == dependent.name())
+                            {
+                                continue;
+                            }
+
+                            // Without `--dependents`, the dependents are offered in the preview
+                            let strategy = match (dependents, *yes || !interactive) {
+                                (Some(strategy), _) => strategy.clone(),
+                                (None, false) => "patch".to_string(),
+                                (None, true) => {
+                                    output_messages.push(
+                                        format!(
+                                            "- {} depends on {}, release it as well with --dependents",
+                                            dependent.name(),
+                                            package.name()
+                                        )
+                                        .dimmed()
+                                        .to_string(),
+                                    );
+                                    continue;
+                                }
+                            };
+
+                            dependents_versions.insert(dependent.name().to_string(), strategy);
+                            queue.push_back(dependent.clone());
+                        }
+                        queue = PackageJSON::sort_by_dependencies(queue.into()).into();
+
+                        planned.push((package, changelog, version));
+                    }
+
+                    // The release of the changelogs, their notes and translations don't touch git,
+                    // so every package is released in its own task. Adding, committing and tagging
+                    // happens one package at a time afterwards.
+                    let github_output = args.github_output;
+                    let planned = concurrently(
+                        planned,
+                        |(package, _, _)| package.name().to_string(),
+                        move |(package, mut changelog, version)| {
+                            changelog.release(&version, Some(&package))?;
+                            changelog.write_notes_file(&version)?;
+                            let github_release = match github_output {
+                                true => Some(github_release(
+                                    &changelog,
+                                    &version,
+                                    Some(package.name()),
+                                    changelog.config(),
+                                )?),
+                                false => None,
+                            };
+                            let translations = changelog.write_translations(&version)?;
 
-                        if *with_npm {
-                            Npm::new(Some(pwd_str))?.version_options(
+                            Ok((package, changelog, version, github_release, translations))
+                        },
+                    )?
+                    .into_iter()
+                    .collect::<Result<Vec<_>>>()?;
+
+                    for (package, changelog, version, github_release, translations) in planned {
+                        github_releases.extend(github_release);
+                        for path in translations {
+                            repo.add(path.to_str().unwrap())?;
+                        }
+
+                        let pwd_str = package.pwd().to_str().unwrap();
+
+                        // Add the CHANGELOG.md file, so that we can commit it later.
+                        repo.add(changelog.file_path_str()?)?;
+
+                        // Crates of a Cargo workspace aren't npm packages
+                        if *with_npm && package.kind() == ManifestKind::Npm {
+                            let npm = Npm::new(Some(pwd_str))?
+                                .with_registry(registry.as_deref().or_else(|| package.registry()))
+                                .with_package_manager(package_manager);
+                            npm.version_options(
                                 &version,
                                 Options {
                                     no_git_tag_version: true,
                                 },
                             )?;
 
-                            // Add the `package-lock.json` file
-                            let pkg_lock = pwd.join("package-lock.json");
+                            // Point the other packages of the workspace at the new version
+                            for other in &workspace {
+                                if let Some(path) =
+                                    other.update_dependency(package.name(), &version)?
+                                {
+                                    repo.add(path.to_str().unwrap())?;
+                                }
+                            }
+
+                            // Add the lockfile
+                            let pkg_lock = pwd.join(package_manager.lockfile());
                             if pkg_lock.exists() {
                                 repo.add(pkg_lock.to_str().unwrap())?;
                             }
 
-                            // Add the `package.json` file
-                            repo.add(pwd.join("package.json").to_str().unwrap())?;
+                            // Add the `package.json` file of the package
+                            repo.add(package.pwd().join("package.json").to_str().unwrap())?;
 
                             // Commit
-                            repo.commit(&format!("{} - {}", &version, &package.name()))?;
+                            repo.commit(
+                                &config.commit_message(
+                                    Some(&version.to_string()),
+                                    Some(package.name()),
+                                ),
+                                &commit_options,
+                            )?;
 
                             // Generate a tag
-                            repo.tag(&format!("{}@v{}", &package.name(), &version))?;
+                            repo.tag(
+                                &changelog
+                                    .config()
+                                    .release
+                                    .tag(Some(package.name()), &version.to_string()),
+                                tag_notes(&changelog, &version, changelog.config())?.as_deref(),
+                            )?;
+
+                            if *publish && !package.is_private() {
+                                npm.publish(&publish_options(&version))?;
+                            }
+                        } else if *with_cargo && package.kind() == ManifestKind::Cargo {
+                            release_cargo(&repo, package.pwd(), &version)?;
+
+                            repo.commit(
+                                &config.commit_message(
+                                    Some(&version.to_string()),
+                                    Some(package.name()),
+                                ),
+                                &commit_options,
+                            )?;
+                            repo.tag(
+                                &changelog
+                                    .config()
+                                    .release
+                                    .tag(Some(package.name()), &version.to_string()),
+                                tag_notes(&changelog, &version, changelog.config())?.as_deref(),
+                            )?;
                         } else {
                             changelog_commit_messages.push(format!(
                                 "- Released `{}` for `{}`",
@@ -658,38 +1724,192 @@ async fn main() -> Result<()> {
                         }
                     }
 
+                    if let Some(fixed_version) = &fixed_version {
+                        if !released.is_empty() {
+                            repo.add(
+                                root_package
+                                    .set_fixed_version(fixed_version)?
+                                    .to_str()
+                                    .unwrap(),
+                            )?;
+
+                            match with_npm {
+                                true => {
+                                    repo.commit(
+                                        &config
+                                            .commit_message(Some(&fixed_version.to_string()), None),
+                                        &commit_options,
+                                    )?;
+                                }
+                                false => changelog_commit_messages
+                                    .push(format!("- Released `{}` in lerna.json", fixed_version)),
+                            }
+                        }
+                    }
+
+                    if let Some(umbrella) = umbrella {
+                        let mut root = root_package.clone();
+                        let version = root.version_mut().change_to(umbrella, preid.as_deref())?;
+                        let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+
+                        // Declining the umbrella release still commits the package releases
+                        let confirmed =
+                            release_umbrella(&mut changelog, &version, &released, |changelog| {
+                                confirm_release(changelog, &version, None, *yes, interactive)
+                            })?;
+
+                        if confirmed {
+                            output_messages.push(format!(
+                                "- Releasing {} for {}",
+                                version.to_string().green().bold(),
+                                root.display_name()
+                            ));
+                            changelog.write_notes_file(&version)?;
+                            if args.github_output {
+                                github_releases
+                                    .push(github_release(&changelog, &version, None, &config)?);
+                            }
+                            for path in changelog.write_translations(&version)? {
+                                repo.add(path.to_str().unwrap())?;
+                            }
+                            repo.add(changelog.file_path_str()?)?;
+
+                            if *with_npm {
+                                let npm = Npm::new(pwd.to_str())?
+                                    .with_registry(registry.as_deref().or_else(|| root.registry()))
+                                    .with_package_manager(package_manager);
+                                npm.version_options(
+                                    &version,
+                                    Options {
+                                        no_git_tag_version: true,
+                                    },
+                                )?;
+
+                                let pkg_lock = pwd.join(package_manager.lockfile());
+                                if pkg_lock.exists() {
+                                    repo.add(pkg_lock.to_str().unwrap())?;
+                                }
+
+                                repo.add(pwd.join("package.json").to_str().unwrap())?;
+                                repo.commit(
+                                    &config.commit_message(Some(&version.to_string()), None),
+                                    &commit_options,
+                                )?;
+                                repo.tag(
+                                    &config.release.tag(None, &version.to_string()),
+                                    tag_notes(&changelog, &version, &config)?.as_deref(),
+                                )?;
+
+                                if *publish && !root.is_private() {
+                                    npm.publish(&publish_options(&version))?;
+                                }
+                            } else {
+                                changelog_commit_messages.push(format!(
+                                    "- Released `{}` for `{}`",
+                                    version,
+                                    root.name(),
+                                ));
+                            }
+                        } else {
+                            output_messages
+                                .push(format!("- Skipped {}", root.name().white().dimmed()));
+                        }
+                    }
+
                     // Commit the CHANGELOG.md file
                     if !changelog_commit_messages.is_empty() {
-                        let _ = &repo.commit(&format!(
-                            "update changelog\n\n{}",
-                            changelog_commit_messages.join("\n")
-                        ))?;
+                        let _ = &repo.commit(
+                            &format!(
+                                "{}\n\n{}",
+                                config.commit_message(None, None),
+                                changelog_commit_messages.join("\n")
+                            ),
+                            &commit_options,
+                        )?;
                     }
 
                     output(output_messages.join("\n"));
+
+                    if args.github_output {
+                        actions::set_output(
+                            "releases",
+                            &serde_json::Value::Array(github_releases).to_string(),
+                        )?;
+                    }
+
+                    if args.github_output && !released.is_empty() {
+                        actions::annotate(
+                            "notice",
+                            &format!(
+                                "Released {}",
+                                conjunction(
+                                    &released
+                                        .iter()
+                                        .map(|name| name.replace('`', ""))
+                                        .collect::<Vec<_>>()
+                                )
+                            ),
+                        );
+                    }
                 }
                 None => {
-                    let mut changelog = Changelog::new(&pwd, &args.filename)?;
+                    let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+
+                    let version = root_package
+                        .clone()
+                        .version_mut()
+                        .change_to(version, preid.as_deref())?;
+
+                    if changelog.requires_major_bump(&version) {
+                        output_warning(format!(
+                            "The unreleased changes contain breaking changes, but {} is not a major release",
+                            version.to_string().blue().bold()
+                        ));
+                    }
+
+                    if !confirm_release(&changelog, &version, None, *yes, interactive)? {
+                        output(format!("{}", "Release cancelled".white().dimmed()));
+                        return Ok(());
+                    }
 
-                    let version: SemVer = version.parse()?;
                     output(format!("Releasing {}", &version.to_string().green().bold()));
                     changelog.release(&version, None)?;
+                    changelog.write_notes_file(&version)?;
+
+                    if args.github_output {
+                        let release = github_release(&changelog, &version, None, &config)?;
+                        for key in ["version", "tag", "notes-path"] {
+                            actions::set_output(key, release[key].as_str().unwrap_or_default())?;
+                        }
+                        actions::annotate("notice", &format!("Released {}", version));
+                    }
+                    let mut changed_files = changelog.write_translations(&version)?;
+
+                    for version_file in &config.version_files {
+                        changed_files.push(version_file.write(&pwd, &version)?);
+                    }
 
                     if *with_npm {
                         // Commit the CHANGELOG.md file
-                        let repo = Git::new(Some(&pwd))?;
-                        repo.add(changelog.file_path_str())?;
+                        let repo = Git::new(Some(&pwd))?.with_signing(config.release.sign);
+                        repo.add(changelog.file_path_str()?)?;
+                        for path in changed_files {
+                            repo.add(path.to_str().unwrap())?;
+                        }
 
                         // Execute npm version <version>
-                        Npm::new(Some(&args.pwd))?.version_options(
+                        let npm = Npm::new(pwd.to_str())?
+                            .with_registry(registry.as_deref().or_else(|| root_package.registry()))
+                            .with_package_manager(package_manager);
+                        npm.version_options(
                             &version,
                             Options {
                                 no_git_tag_version: true,
                             },
                         )?;
 
-                        // Add the `package-lock.json` file
-                        let pkg_lock = pwd.join("package-lock.json");
+                        // Add the lockfile
+                        let pkg_lock = pwd.join(package_manager.lockfile());
                         if pkg_lock.exists() {
                             repo.add(pkg_lock.to_str().unwrap())?;
                         }
@@ -698,41 +1918,1213 @@ async fn main() -> Result<()> {
                         repo.add(pwd.join("package.json").to_str().unwrap())?;
 
                         // Commit the version
-                        repo.commit(&version.to_string())?;
+                        repo.commit(
+                            &config.commit_message(Some(&version.to_string()), None),
+                            &commit_options,
+                        )?;
 
                         // Let's create a tag!
-                        repo.tag(&format!("v{}", &version))?;
+                        repo.tag(
+                            &config.release.tag(None, &version.to_string()),
+                            tag_notes(&changelog, &version, &config)?.as_deref(),
+                        )?;
+
+                        if *publish {
+                            npm.publish(&publish_options(&version))?;
+                        }
+                    } else if *with_cargo {
+                        let repo = Git::new(Some(&pwd))?.with_signing(config.release.sign);
+                        repo.add(changelog.file_path_str()?)?;
+                        for path in changed_files {
+                            repo.add(path.to_str().unwrap())?;
+                        }
+
+                        release_cargo(&repo, &pwd, &version)?;
+
+                        repo.commit(
+                            &config.commit_message(Some(&version.to_string()), None),
+                            &commit_options,
+                        )?;
+                        repo.tag(
+                            &config.release.tag(None, &version.to_string()),
+                            tag_notes(&changelog, &version, &config)?.as_deref(),
+                        )?;
                     }
                 }
             }
 
             Ok(())
         }
-        Commands::List { amount, all } => {
-            let amount = match &all {
-                true => Amount::All,
-                false => *amount,
+        Commands::Fmt { sort } => {
+            if sort.is_some() {
+                config.sort = *sort;
+            }
+
+            match scopes {
+                Some(scopes) => {
+                    let mut messages: Vec<_> = vec![];
+                    for scope in scopes {
+                        let mut changelog = Changelog::new(scope.pwd(), &args.filename, &config)?;
+                        messages.push(changelog.format()?);
+                    }
+
+                    output(
+                        messages
+                            .iter()
+                            .map(|msg| format!("- {}", msg))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    )
+                }
+                None => {
+                    let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+                    output(changelog.format()?);
+                }
+            }
+
+            Ok(())
+        }
+        Commands::Check { base } => {
+            let render = |diffs: Vec<Diff>| match diffs.is_empty() {
+                true => "No changes".white().dimmed().italic().to_string(),
+                false => diffs
+                    .iter()
+                    .map(|diff| diff.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
             };
 
             match scopes {
                 Some(scopes) => {
                     for package in scopes {
-                        let message = Changelog::new(package.pwd(), &args.filename)?
-                            .list(amount)
+                        let message = Changelog::new(package.pwd(), &args.filename, &config)?
+                            .diff_with_ref(base)
+                            .map(render)
                             .unwrap_or_else(|err| err.to_string().red().to_string());
 
                         output_title(
-                            format!("Releases for {}", package.name().white().dimmed()),
+                            format!(
+                                "Changes since {} for {}",
+                                base.blue(),
+                                package.name().white().dimmed()
+                            ),
                             message,
                         )
                     }
                 }
                 None => {
-                    output(Changelog::new(&pwd, &args.filename)?.list(amount)?);
+                    let diffs =
+                        Changelog::new(&pwd, &args.filename, &config)?.diff_with_ref(base)?;
+
+                    output_title(format!("Changes since {}", base.blue()), render(diffs));
                 }
             }
 
             Ok(())
         }
+        Commands::GrepLinks { fix } => {
+            let mut found = false;
+            let changelogs = match scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|package| {
+                        Ok((
+                            Some(package.name().to_string()),
+                            Changelog::new(package.pwd(), &args.filename, &config)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                None => vec![(None, Changelog::new(&pwd, &args.filename, &config)?)],
+            };
+
+            for (name, mut changelog) in changelogs {
+                let issues = match fix {
+                    true => changelog.fix_links()?,
+                    false => changelog.link_issues(),
+                };
+
+                if *fix && !issues.is_empty() {
+                    changelog.persist()?;
+                }
+
+                found |= !issues.is_empty();
+
+                let message = match issues.is_empty() {
+                    true => "No issues found".green().to_string(),
+                    false => issues
+                        .orphans
+                        .iter()
+                        .map(|(name, link)| format!("- [{}]: {} {}", name, link, "(unused)".red()))
+                        .chain(
+                            issues.missing.iter().map(|name| {
+                                format!("- [{}] {}", name, "(missing reference)".yellow())
+                            }),
+                        )
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                };
+
+                output_title(
+                    match (name, fix) {
+                        (Some(name), true) => format!("Fixed links for {}", name.white().dimmed()),
+                        (Some(name), false) => format!("Links for {}", name.white().dimmed()),
+                        (None, true) => "Fixed links".to_string(),
+                        (None, false) => "Links".to_string(),
+                    },
+                    message,
+                );
+            }
+
+            if found && !fix {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::Review { approve } => {
+            let mut pending = false;
+            let changelogs = match scopes {
+                Some(scopes) => scopes
+                    .iter()
+                    .map(|package| {
+                        Ok((
+                            Some(package.name().to_string()),
+                            Changelog::new(package.pwd(), &args.filename, &config)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                None => vec![(None, Changelog::new(&pwd, &args.filename, &config)?)],
+            };
+
+            for (name, mut changelog) in changelogs {
+                if let Some(query) = approve {
+                    if !changelog.approve(query).is_empty() {
+                        changelog.persist()?;
+                    }
+                }
+
+                let entries = changelog.review_entries();
+                let message = match entries.is_empty() {
+                    true => "No entries to review".white().dimmed().to_string(),
+                    false => entries
+                        .iter()
+                        .map(|entry| {
+                            let status = metadata::get(&entry.text, "review").unwrap_or_default();
+                            pending |= status == "pending";
+
+                            format!(
+                                "- {} {}{}",
+                                metadata::parse(&entry.text).0,
+                                match status.as_str() {
+                                    "approved" => "(approved)".green(),
+                                    _ => format!("({})", status).yellow(),
+                                },
+                                metadata::get(&entry.text, "by")
+                                    .map(|by| format!(" by {}", by).white().dimmed().to_string())
+                                    .unwrap_or_default()
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                };
+
+                output_title(
+                    match name {
+                        Some(name) => format!("Review for {}", name.white().dimmed()),
+                        None => "Review".to_string(),
+                    },
+                    message,
+                );
+            }
+
+            if pending {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::Backfill => {
+            let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+            let repo = Git::new(Some(&pwd))?;
+
+            let mut tags: Vec<(SemVer, String)> = repo
+                .tags()?
+                .into_iter()
+                .filter_map(|tag| {
+                    let version = config.release.version_from_tag(None, &tag)?.parse().ok()?;
+                    Some((version, tag))
+                })
+                .collect();
+            tags.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            let mut added = vec![];
+            let mut previous: Option<&str> = None;
+            for (version, tag) in &tags {
+                // The commits that only update the changelog or bump the version are noise
+                let noise = [
+                    config.commit_message(None, None),
+                    config.commit_message(Some(&version.to_string()), None),
+                ];
+                let subjects: Vec<String> = repo
+                    .commits_between(previous, tag)?
+                    .into_iter()
+                    .map(|(_, subject)| subject)
+                    .filter(|subject| !noise.contains(subject))
+                    .collect();
+                let date = NaiveDate::parse_from_str(&repo.date(tag)?, "%Y-%m-%d")?;
+
+                if changelog.backfill(version, &date, &subjects) {
+                    added.push(version.to_string());
+                }
+
+                previous = Some(tag);
+            }
+
+            if added.is_empty() {
+                output("Every tag already has a version in the changelog".to_string());
+                return Ok(());
+            }
+
+            // The reference links can only be generated for GitHub repositories
+            if Repo::from_git_repo(&pwd).is_ok() {
+                changelog.fix_links()?;
+            }
+            changelog.persist()?;
+
+            output(format!(
+                "Backfilled {} version(s): {}",
+                added.len(),
+                added
+                    .iter()
+                    .map(|version| version.blue().bold().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+
+            Ok(())
+        }
+        Commands::ImportReleases => {
+            let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+            let repo = Repo::from_git_repo(&pwd)?;
+
+            let releases = Release::all(&repo).map_err(|e| eyre!(e))?;
+
+            let mut added = vec![];
+            for release in &releases {
+                let version: SemVer = match config
+                    .release
+                    .version_from_tag(None, &release.tag)
+                    .and_then(|version| version.parse().ok())
+                {
+                    Some(version) => version,
+                    None => {
+                        output_warning(format!(
+                            "Skipping release {}, its tag is not a version",
+                            release.tag.blue().bold()
+                        ));
+                        continue;
+                    }
+                };
+                let date = NaiveDate::parse_from_str(&release.date, "%Y-%m-%d")?;
+
+                if changelog.import_release(&version, &date, &release.body) {
+                    added.push(version.to_string());
+                }
+            }
+
+            if added.is_empty() {
+                output("Every GitHub Release already has a version in the changelog".to_string());
+                return Ok(());
+            }
+
+            changelog.fix_links()?;
+            changelog.persist()?;
+
+            output(format!(
+                "Imported {} release(s): {}",
+                added.len(),
+                added
+                    .iter()
+                    .map(|version| version.blue().bold().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+
+            Ok(())
+        }
+        Commands::SyncReleases => {
+            let changelog = Changelog::new(&pwd, &args.filename, &config)?;
+            let repo = Repo::from_git_repo(&pwd)?;
+            let tags = Git::new(Some(&pwd))?.tags()?;
+
+            let versions: Vec<SemVer> = changelog
+                .versions(None)
+                .iter()
+                .filter_map(|version| version.parse().ok())
+                .collect();
+
+            let mut synced = (0, 0, 0);
+            for version in &versions {
+                let tag = config.release.tag(None, &version.to_string());
+
+                // GitHub would create a missing tag from the default branch
+                if !tags.contains(&tag) {
+                    output_warning(format!(
+                        "Skipping version {}, tag {} doesn't exist",
+                        version.to_string().blue().bold(),
+                        tag.blue().bold()
+                    ));
+                    continue;
+                }
+
+                let notes = changelog.notes(Some(&version.to_string()))?;
+                match Release::sync(&repo, &tag, &notes, version.dist_tag().is_some())
+                    .map_err(|e| eyre!(e))?
+                {
+                    Sync::Created => {
+                        synced.0 += 1;
+                        output_indented(format!("Created {}", tag.green().bold()));
+                    }
+                    Sync::Updated => {
+                        synced.1 += 1;
+                        output_indented(format!("Updated {}", tag.yellow().bold()));
+                    }
+                    Sync::Unchanged => synced.2 += 1,
+                }
+            }
+
+            output(format!(
+                "Synced GitHub Releases: {} created, {} updated, {} unchanged",
+                synced.0, synced.1, synced.2
+            ));
+
+            Ok(())
+        }
+        Commands::Export { format, output } => {
+            let changelog = Changelog::new(&pwd, &args.filename, &config)?;
+
+            let contents = match format {
+                ExportFormat::Atom => changelog.atom(
+                    Repo::from_git_repo(&pwd)
+                        .ok()
+                        .map(|repo| format!("https://github.com/{}", repo))
+                        .as_deref(),
+                ),
+                ExportFormat::Json => serde_json::to_string_pretty(&changelog.export())? + "\n",
+            };
+
+            match output {
+                Some(path) => fs::write(pwd.join(path), contents)?,
+                None => print!("{}", contents),
+            }
+
+            Ok(())
+        }
+        Commands::Import { format } => {
+            let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+
+            let (entries, versions) = match format {
+                ImportFormat::Json => match serde_json::from_str(&input)? {
+                    entries @ serde_json::Value::Array(_) => (
+                        changelog.import_entries(serde_json::from_value(entries)?)?,
+                        0,
+                    ),
+                    export => changelog.import(serde_json::from_value(export)?)?,
+                },
+            };
+
+            // The reference links can only be generated for GitHub repositories
+            if versions > 0 && Repo::from_git_repo(&pwd).is_ok() {
+                changelog.fix_links()?;
+            }
+            changelog.persist()?;
+
+            output(format!(
+                "Imported {} entries and {} version(s)",
+                entries.to_string().blue().bold(),
+                versions.to_string().blue().bold()
+            ));
+
+            Ok(())
+        }
+        Commands::Unreleased { check } => {
+            let packages = match scopes {
+                Some(scopes) => scopes.into_iter().map(Some).collect(),
+                None => vec![None],
+            };
+
+            let mut found = false;
+            for package in packages {
+                let dir = package
+                    .as_ref()
+                    .map(|package| package.pwd())
+                    .unwrap_or(&pwd);
+                let changes =
+                    Changelog::new(dir, &args.filename, &config)?.has_changes(package.as_ref());
+
+                found |= changes;
+
+                if !check {
+                    let message = match changes {
+                        true => "There are unreleased changes".green().to_string(),
+                        false => "Nothing to release".white().dimmed().italic().to_string(),
+                    };
+
+                    output(match &package {
+                        Some(package) => format!("{} {}", package.name().white().dimmed(), message),
+                        None => message,
+                    });
+                }
+            }
+
+            if *check && !found {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::Unlogged => {
+            let mut found = false;
+            let packages = match scopes {
+                Some(scopes) => scopes.into_iter().map(Some).collect(),
+                None => vec![None],
+            };
+
+            for package in packages {
+                let (dir, pattern) = match &package {
+                    Some(package) if !package.is_root() => (
+                        package.pwd().to_path_buf(),
+                        Some(
+                            config
+                                .for_package(package.pwd())?
+                                .release
+                                .tag(Some(package.name()), "*"),
+                        ),
+                    ),
+                    Some(package) => (package.pwd().to_path_buf(), None),
+                    None => (pwd.clone(), None),
+                };
+
+                let changelog = Changelog::new(&dir, &args.filename, &config)?;
+                let repo = Git::new(Some(&dir))?;
+                let tag = repo.latest_tag(pattern.as_deref());
+                let commits = changelog.unlogged(&repo.commits_since(tag.as_deref())?);
+
+                found |= !commits.is_empty();
+
+                let since = tag.unwrap_or_else(|| "the first commit".to_string());
+                output_title(
+                    match &package {
+                        Some(package) => format!(
+                            "Unlogged commits for {} since {}",
+                            package.name().white().dimmed(),
+                            since.blue()
+                        ),
+                        None => format!("Unlogged commits since {}", since.blue()),
+                    },
+                    match commits.is_empty() {
+                        true => "Every commit has an entry".green().to_string(),
+                        false => commits
+                            .iter()
+                            .map(|(hash, subject)| format!("- {} {}", hash[..7].yellow(), subject))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    },
+                );
+            }
+
+            if found {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::Announce { version, dry_run } => {
+            if config.announce.is_empty() {
+                return Err(eyre!(
+                    "There are no webhooks to announce to, add them to the {} config option",
+                    "announce".blue().bold()
+                ));
+            }
+
+            let changelog = Changelog::new(&pwd, &args.filename, &config)?;
+            let name = changelog
+                .resolve_version(version, None)
+                .filter(|name| changelog.versions(None).contains(name))
+                .ok_or_else(|| eyre!("Couldn't find version: {}", version.blue().bold()))?;
+            let export = changelog.export();
+            let exported = export.versions.iter().find(|other| other.version == name);
+            let notes = changelog.notes(Some(&name))?;
+
+            let announcement = Announcement {
+                version: &name,
+                date: exported.and_then(|version| version.date.as_deref()),
+                link: exported.and_then(|version| version.link.as_deref()),
+                notes: &notes,
+            };
+
+            for webhook in &config.announce {
+                if *dry_run {
+                    let url = webhook
+                        .resolve_url()
+                        .unwrap_or_else(|_| webhook.url.clone());
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(
+                            &webhook.payload(webhook.kind(&url), &announcement)
+                        )?
+                    );
+                } else {
+                    webhook.post(&announcement)?;
+                }
+            }
+
+            if !dry_run {
+                output(format!(
+                    "Announced {} to {} webhook(s)",
+                    name.blue().bold(),
+                    config.announce.len()
+                ));
+            }
+
+            Ok(())
+        }
+        Commands::InstallHooks {
+            hook,
+            block,
+            paths,
+            base,
+            uninstall,
+        } => {
+            let hooks_dir = Git::new(Some(&pwd))?.hooks_dir()?;
+
+            if *uninstall {
+                match hook.uninstall(&hooks_dir)? {
+                    Some(path) => output(format!(
+                        "Removed the {} hook from {}",
+                        hook.name().blue().bold(),
+                        path.display().to_string().white().dimmed()
+                    )),
+                    None => output(format!(
+                        "There is no {} hook installed",
+                        hook.name().blue().bold()
+                    )),
+                }
+
+                return Ok(());
+            }
+
+            let path = hook.install(
+                &hooks_dir,
+                &HookOptions {
+                    block: *block,
+                    paths: paths.clone(),
+                    filename: args.filename.clone(),
+                    base: base.clone(),
+                },
+            )?;
+
+            output(format!(
+                "Installed the {} hook at {}, it {} when files in {} change without a new unreleased entry",
+                hook.name().blue().bold(),
+                path.display().to_string().white().dimmed(),
+                match block {
+                    true => "blocks",
+                    false => "reminds you",
+                },
+                conjunction(paths)
+            ));
+
+            Ok(())
+        }
+        Commands::Merge { file } => {
+            let other: Node = fs::read_to_string(file)
+                .map_err(|e| eyre!("Couldn't read {}: {}", file.blue().bold(), e))?
+                .trim_start_matches('\u{feff}')
+                .parse()?;
+
+            let mut changelog = Changelog::new(&pwd, &args.filename, &config)?;
+            let report = changelog.combine(&other);
+            changelog.persist()?;
+
+            output(format!(
+                "Merged {} into {}, with {} new version(s) and {} new entries",
+                file.blue().bold(),
+                changelog.relative_path()?.white().dimmed(),
+                report.versions.len(),
+                report.entries
+            ));
+
+            if !report.conflicts.is_empty() {
+                output_title(
+                    "Both changelogs disagree, the current changelog was kept for"
+                        .yellow()
+                        .to_string(),
+                    report
+                        .conflicts
+                        .iter()
+                        .map(|conflict| format!("- {}", conflict))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                );
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::MergeDriver {
+            base,
+            ours,
+            theirs,
+            install,
+        } => {
+            if *install {
+                let repo = Git::new(Some(&pwd))?;
+                repo.exec(vec![
+                    "config",
+                    "merge.changelog.name",
+                    "Merge changelogs entry by entry",
+                ])?;
+                repo.exec(vec![
+                    "config",
+                    "merge.changelog.driver",
+                    "changelog merge-driver %O %A %B",
+                ])?;
+
+                let attributes = pwd.join(".gitattributes");
+                let line = format!("{} merge=changelog", args.filename);
+                let contents = fs::read_to_string(&attributes).unwrap_or_default();
+                if !contents.lines().any(|existing| existing.trim() == line) {
+                    let separator = match contents.is_empty() || contents.ends_with('\n') {
+                        true => "",
+                        false => "\n",
+                    };
+                    fs::write(&attributes, format!("{}{}{}\n", contents, separator, line))?;
+                }
+
+                output(format!(
+                    "Registered the merge driver, commit {} so that everyone uses it. Everyone needs to run {} once, because git doesn't share its config",
+                    ".gitattributes".blue().bold(),
+                    "changelog merge-driver --install".blue().bold()
+                ));
+
+                return Ok(());
+            }
+
+            // Guaranteed by clap
+            let (base, ours, theirs) = (
+                base.as_deref().unwrap(),
+                Path::new(ours.as_deref().unwrap()),
+                theirs.as_deref().unwrap(),
+            );
+            let read = |path: &str| -> Result<Node> {
+                fs::read_to_string(path)?
+                    .trim_start_matches('\u{feff}')
+                    .parse()
+            };
+
+            let dir = match ours.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => pwd.clone(),
+            };
+            let mut changelog = Changelog::from_contents(
+                &dir,
+                &ours.file_name().unwrap_or_default().to_string_lossy(),
+                &fs::read_to_string(ours)?,
+                &config,
+            )?;
+
+            if changelog.merge(&read(base)?, &read(theirs)?) {
+                return changelog.persist();
+            }
+
+            // Fall back to a regular merge, with conflict markers
+            let status = std::process::Command::new("git")
+                .args(["merge-file", "-L", "ours", "-L", "base", "-L", "theirs"])
+                .arg(ours)
+                .args([base, theirs])
+                .status()?;
+
+            if !status.success() {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::VerifyPr { base, message } => {
+            let git = Git::new(Some(&pwd))?;
+            let skipped = git
+                .messages_between(base, "HEAD")?
+                .iter()
+                .chain(message)
+                .any(|message| message.to_lowercase().contains(SKIP_CHANGELOG));
+
+            if skipped {
+                output(format!(
+                    "Skipped the changelog check, found {}",
+                    SKIP_CHANGELOG.blue().bold()
+                ));
+                return Ok(());
+            }
+
+            let render = |entries: &[Entry]| {
+                entries
+                    .iter()
+                    .map(|entry| format!("{} {}", "+".green(), entry.text))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+
+            // In a monorepo a pull request usually only touches a single package, so a new entry
+            // in any of the scopes is enough.
+            let found = match scopes {
+                Some(scopes) => {
+                    let mut found = false;
+                    for package in scopes {
+                        let entries = Changelog::new(package.pwd(), &args.filename, &config)?
+                            .new_entries_since(base)?;
+                        if !entries.is_empty() {
+                            found = true;
+                            output_title(
+                                format!(
+                                    "New entries since {} for {}",
+                                    base.blue(),
+                                    package.name().white().dimmed()
+                                ),
+                                render(&entries),
+                            );
+                        }
+                    }
+                    found
+                }
+                None => {
+                    let entries =
+                        Changelog::new(&pwd, &args.filename, &config)?.new_entries_since(base)?;
+                    if !entries.is_empty() {
+                        output_title(
+                            format!("New entries since {}", base.blue()),
+                            render(&entries),
+                        );
+                    }
+                    !entries.is_empty()
+                }
+            };
+
+            if !found {
+                output(format!(
+                    "{} No new unreleased entry since {}, add one with `changelog add` or add {} to a commit message",
+                    "Missing changelog entry.".red().bold(),
+                    base.blue(),
+                    SKIP_CHANGELOG.blue()
+                ));
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::VerifyRelease { version } => {
+            let mut drifted = false;
+
+            match scopes {
+                Some(scopes) => {
+                    for package in scopes {
+                        let message = match Changelog::new(package.pwd(), &args.filename, &config)?
+                            .verify_release(version, Some(&package))
+                        {
+                            Ok(diffs) if diffs.is_empty() => "No drift found".green().to_string(),
+                            Ok(diffs) => {
+                                drifted = true;
+                                diffs
+                                    .iter()
+                                    .map(|diff| diff.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join("\n")
+                            }
+                            Err(err) => {
+                                drifted = true;
+                                err.to_string().red().to_string()
+                            }
+                        };
+
+                        output_title(
+                            format!(
+                                "Verify release {} for {}",
+                                version.blue(),
+                                package.name().white().dimmed()
+                            ),
+                            message,
+                        )
+                    }
+                }
+                None => {
+                    let diffs = Changelog::new(&pwd, &args.filename, &config)?
+                        .verify_release(version, None)?;
+
+                    if diffs.is_empty() {
+                        output(format!(
+                            "The GitHub Release for {} matches the changelog",
+                            version.blue().bold()
+                        ));
+                    } else {
+                        drifted = true;
+                        output_title(
+                            format!(
+                                "Drift between the changelog ({}) and the GitHub Release ({}) for {}",
+                                "+".green(),
+                                "-".red(),
+                                version.blue().bold()
+                            ),
+                            diffs
+                                .iter()
+                                .map(|diff| diff.to_string())
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        );
+                    }
+                }
+            }
+
+            if drifted {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        Commands::Ui => {
+            if !interactive {
+                return Err(eyre!("The dashboard needs an interactive terminal"));
+            }
+
+            Dashboard::new(
+                &pwd,
+                &config,
+                Changelog::new(&pwd, &args.filename, &config)?,
+            )
+            .run()
+        }
+        Commands::Api { .. } | Commands::Lsp | Commands::Graph { .. } => unreachable!(),
+        Commands::List { amount, all } => {
+            let amount = match &all {
+                true => Amount::All,
+                false => *amount,
+            };
+
+            match scopes {
+                Some(scopes) => {
+                    for package in scopes {
+                        let message = Changelog::new(package.pwd(), &args.filename, &config)?
+                            .list(amount)
+                            .unwrap_or_else(|err| err.to_string().red().to_string());
+
+                        output_title(
+                            format!("Releases for {}", package.name().white().dimmed()),
+                            message,
+                        )
+                    }
+                }
+                None => {
+                    output(Changelog::new(&pwd, &args.filename, &config)?.list(amount)?);
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Run the work for every scope in its own task, the results keep the order of the scopes so that
+/// the output can still be grouped per package.
+fn map_scopes<T: Send + 'static>(
+    scopes: &[PackageJSON],
+    work: impl Fn(&PackageJSON) -> T + Send + std::marker::Sync + 'static,
+) -> Result<Vec<T>> {
+    concurrently(
+        scopes.to_vec(),
+        |scope| scope.name().to_string(),
+        move |scope| work(&scope),
+    )
+}
+
+/// Run the work for every item in its own tokio task, the results keep the order of the items. The
+/// work blocks on the file system, git and the GitHub API (the blocking client of reqwest can't be
+/// used from async code), so the tasks run on the blocking thread pool of the runtime. A task that
+/// panics is an error that mentions the name of its item.
+fn concurrently<I: Send + 'static, T: Send + 'static>(
+    items: Vec<I>,
+    name: impl Fn(&I) -> String,
+    work: impl Fn(I) -> T + Send + std::marker::Sync + 'static,
+) -> Result<Vec<T>> {
+    let work = Arc::new(work);
+    let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+
+    runtime.block_on(async {
+        let tasks: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                let work = work.clone();
+                (name(&item), tokio::task::spawn_blocking(move || work(item)))
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for (name, task) in tasks {
+            results.push(
+                task.await
+                    .map_err(|e| eyre!("Couldn't process {}: {}", name, e))?,
+            );
+        }
+
+        Ok(results)
+    })
+}
+
+/// Highlight the new entries in the rendered section, the entries use the bullet marker of the
+/// changelog
+fn highlight_entries(text: String, marker: char, entries: &[(String, String)]) -> String {
+    entries.iter().fold(text, |text, (_, entry)| {
+        text.replace(
+            &format!("{} {}", marker, entry),
+            &format!("{} {}", marker, entry.green().bold()),
+        )
+    })
+}
+
+/// The version of a version heading, e.g.: `1.2.3` for `[1.2.3] - 2024-05-01`
+fn heading_version(heading: &str) -> String {
+    let name = heading.split(" - ").next().unwrap_or(heading).trim();
+    name.trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string()
+}
+
+/// The outputs of a released version for `--github-output`: its name, version, tag and the path
+/// to its notes
+fn github_release(
+    changelog: &Changelog,
+    version: &SemVer,
+    package: Option<&str>,
+    config: &Config,
+) -> Result<serde_json::Value> {
+    let notes = changelog.notes(Some(&version.to_string()))?;
+    let path = actions::write_notes(
+        &match package {
+            Some(package) => format!("{}-{}", package, version),
+            None => version.to_string(),
+        },
+        &notes,
+    )?;
+
+    Ok(serde_json::json!({
+        "name": package,
+        "version": version.to_string(),
+        "tag": config.release.tag(package, &version.to_string()),
+        "notes-path": path.display().to_string(),
+    }))
+}
+
+/// Whether the path is a changelog (or a localized changelog, or a file in `.changelog/`) that
+/// is expected to change when releasing
+fn is_changelog_file(path: &str, filename: &str) -> bool {
+    let path = Path::new(path);
+    let stem = filename.trim_end_matches(".md");
+
+    path.starts_with(".changelog")
+        || path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name == filename || (name.starts_with(stem) && name.ends_with(".md")))
+            .unwrap_or(false)
+}
+
+/// The notes used as the message of annotated tags, when enabled
+fn tag_notes(changelog: &Changelog, version: &SemVer, config: &Config) -> Result<Option<String>> {
+    match config.release.annotate {
+        true => changelog.notes(Some(&version.to_string())).map(Some),
+        false => Ok(None),
+    }
+}
+
+/// Bump the version in `Cargo.toml`, refresh the `Cargo.lock` file if there is one and stage both
+fn release_cargo(repo: &Git, pwd: &Path, version: &SemVer) -> Result<()> {
+    repo.add(cargo::set_version(pwd, version)?.to_str().unwrap())?;
+
+    if let Some(lockfile) = cargo::lockfile(pwd) {
+        cargo::check(pwd)?;
+        repo.add(lockfile.to_str().unwrap())?;
+    }
+
+    Ok(())
+}
+
+/// Every non-empty line is an entry, lines starting with a `#` are comments
+fn entry_lines(data: &str) -> Vec<String> {
+    data.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Open the entry in the editor, returns `None` when nothing (or nothing new) was saved
+fn edit_entry(name: &str, message: &str, scope: Option<&PackageJSON>) -> Option<String> {
+    let contents = &format!(
+        include_str!("./fixtures/edit_entry.txt"),
+        match scope {
+            Some(scope) => format!("# Current scope: '{}'\n\n", scope.name()),
+            None => "".to_string(),
+        },
+        message,
+        name.to_lowercase(),
+    );
+
+    match rich_edit(Some(contents)) {
+        Some(Edit::Saved(data)) => {
+            let data = data.trim();
+            let data = data
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .filter(|line| !line.starts_with('#'))
+                .map(|line| line.to_string())
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            if data.is_empty() {
+                None
+            } else {
+                Some(data)
+            }
+        }
+        Some(Edit::Failed(path)) => {
+            output_warning(format!(
+                "The editor exited with an error, your changes are kept at: {}",
+                path.display()
+            ));
+            None
+        }
+        Some(Edit::Unchanged) | None => None,
+    }
+}
+
+/// Ask for the section of a new entry, "Added" is preselected. Without a terminal, the entry is
+/// added to "Added".
+fn pick_section(config: &Config, interactive: bool) -> Result<String> {
+    let default = config
+        .section_rank(DEFAULT_SECTION)
+        .min(config.sections.len().saturating_sub(1));
+
+    if !interactive || config.sections.is_empty() {
+        return Ok(DEFAULT_SECTION.to_string());
+    }
+
+    let idx = Select::new()
+        .with_prompt("Select the section")
+        .items(&config.sections)
+        .default(default)
+        .clear(true)
+        .interact()?;
+
+    Ok(config.sections[idx].clone())
+}
+
+/// Add the released packages to the changelog of the root package, and release the umbrella
+/// version once it is confirmed. Returns whether it was released, the changelog is left untouched
+/// otherwise.
+fn release_umbrella(
+    changelog: &mut Changelog,
+    version: &SemVer,
+    released: &[String],
+    confirm: impl FnOnce(&Changelog) -> Result<bool>,
+) -> Result<bool> {
+    let mut umbrella = changelog.clone();
+    for entry in released {
+        umbrella.add_list_item_to_section(UMBRELLA_SECTION, entry)?;
+    }
+
+    if !confirm(&umbrella)? {
+        return Ok(false);
+    }
+
+    *changelog = umbrella;
+    changelog.release(version, None)?;
+
+    Ok(true)
+}
+
+/// Show a preview of the release and ask for confirmation, unless `--yes` is passed
+fn confirm_release(
+    changelog: &Changelog,
+    version: &SemVer,
+    scope: Option<&PackageJSON>,
+    yes: bool,
+    interactive: bool,
+) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+
+    if !interactive {
+        return Err(eyre!(
+            "Couldn't ask for confirmation, use {} to release without a preview",
+            "--yes".blue().bold()
+        ));
+    }
+
+    output_title(
+        format!("Preview of {}", version.to_string().blue().bold()),
+        changelog.preview_release(version, scope)?,
+    );
+
+    Confirm::new()
+        .with_prompt(match scope {
+            Some(scope) if !scope.is_root() => format!("Release {}?", scope.name()),
+            _ => "Release?".to_string(),
+        })
+        .default(false)
+        .interact()
+        .map_err(|_| {
+            eyre!(
+                "Couldn't ask for confirmation, use {} to release without a preview",
+                "--yes".blue().bold()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_leave_the_changelog_untouched_when_the_umbrella_release_is_declined() {
+        let dir = std::env::temp_dir().join(format!("changelog-umbrella-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let contents = "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n- Nothing yet!\n\n## [0.1.0] - 2022-01-01\n\n- Initial release\n\n[unreleased]: https://github.com/acme/mono/compare/v0.1.0...HEAD\n[0.1.0]: https://github.com/acme/mono/releases/tag/v0.1.0\n";
+        let mut changelog =
+            Changelog::from_contents(&dir, "CHANGELOG.md", contents, &Config::default()).unwrap();
+        let version: SemVer = "1.0.0".parse().unwrap();
+        let released = vec!["`@acme/ui@2.1.0`".to_string()];
+
+        let mut previewed = String::new();
+        let confirmed = release_umbrella(&mut changelog, &version, &released, |umbrella| {
+            previewed = umbrella.preview_release(&version, None)?;
+            Ok(false)
+        })
+        .unwrap();
+
+        assert!(!confirmed);
+        assert!(previewed.contains("- `@acme/ui@2.1.0`"));
+        assert!(!dir.join("CHANGELOG.md").exists());
+        assert!(!changelog.has_changes(None));
+
+        assert!(release_umbrella(&mut changelog, &version, &released, |_| Ok(true)).unwrap());
+        assert!(fs::read_to_string(dir.join("CHANGELOG.md"))
+            .unwrap()
+            .contains("- `@acme/ui@2.1.0`"));
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
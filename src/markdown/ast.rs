@@ -95,43 +95,70 @@ impl Node {
         result
     }
 
-    fn flatten(&self) -> Vec<&MarkdownToken> {
-        let mut result: Vec<&MarkdownToken> = vec![];
+    pub fn filter_nodes_mut<F>(&mut self, predicate: F) -> Vec<&mut Node>
+    where
+        Self: Sized,
+        F: Fn(&Node) -> bool + Copy,
+    {
+        let mut result: Vec<&mut Node> = vec![];
 
-        if let Some(MarkdownToken::UnorderedList) = self.data {
-            for child in &self.children {
-                result.extend(child.flatten());
-            }
+        if predicate(self) {
+            result.push(self);
+            return result;
+        }
+
+        for child in &mut self.children {
+            result.extend(child.filter_nodes_mut(predicate));
+        }
+
+        result
+    }
 
-            result.push(&MarkdownToken::BlankLine);
-        } else {
-            if let Some(data) = &self.data {
-                result.push(data);
+    /// Write the tokens of this node and its children, separated by newlines. Lists are followed
+    /// by a blank line. This streams directly into the writer instead of collecting all tokens.
+    fn write_tokens(&self, f: &mut std::fmt::Formatter<'_>, first: &mut bool) -> std::fmt::Result {
+        match &self.data {
+            Some(MarkdownToken::UnorderedList | MarkdownToken::OrderedList) => {
+                for child in &self.children {
+                    child.write_tokens(f, first)?;
+                }
+
+                write_token(f, &MarkdownToken::BlankLine, first)
             }
+            data => {
+                if let Some(data) = data {
+                    write_token(f, data, first)?;
+                }
+
+                for child in &self.children {
+                    child.write_tokens(f, first)?;
+                }
 
-            for child in &self.children {
-                result.extend(child.flatten())
+                Ok(())
             }
         }
-
-        result
     }
 }
 
 impl Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.flatten()
-                .iter()
-                .map(|token| token.to_string())
-                .collect::<Vec<_>>()
-                .join("\n")
-        )
+        self.write_tokens(f, &mut true)
     }
 }
 
+fn write_token(
+    f: &mut std::fmt::Formatter<'_>,
+    token: &MarkdownToken,
+    first: &mut bool,
+) -> std::fmt::Result {
+    if !*first {
+        f.write_str("\n")?;
+    }
+    *first = false;
+
+    Display::fmt(token, f)
+}
+
 impl FromStr for Node {
     type Err = Error;
 
@@ -156,16 +183,32 @@ fn parse(
             MarkdownToken::H1(_) | MarkdownToken::H2(_) | MarkdownToken::H3(_) => {
                 Node::new(Some(token.clone()), parse(tokens, Some(token)))
             }
-            MarkdownToken::ListItem(_, _) => {
+            MarkdownToken::ListItem(_, _, _) => {
                 let mut ul = Node::from_token(MarkdownToken::UnorderedList);
                 ul.add_child(Node::from_token(token.clone()));
 
-                while let Some(MarkdownToken::ListItem(_, _)) = &tokens.peek() {
+                while let Some(
+                    MarkdownToken::ListItem(_, _, _) | MarkdownToken::OrderedListItem(_, 1.., _),
+                ) = &tokens.peek()
+                {
                     ul.add_child(Node::from_token(tokens.next().unwrap().clone()));
                 }
 
                 ul
             }
+            MarkdownToken::OrderedListItem(_, _, _) => {
+                let mut ol = Node::from_token(MarkdownToken::OrderedList);
+                ol.add_child(Node::from_token(token.clone()));
+
+                while let Some(
+                    MarkdownToken::OrderedListItem(_, _, _) | MarkdownToken::ListItem(_, 1.., _),
+                ) = &tokens.peek()
+                {
+                    ol.add_child(Node::from_token(tokens.next().unwrap().clone()));
+                }
+
+                ol
+            }
             _ => Node::from_token(token.clone()),
         });
 
@@ -187,3 +230,31 @@ fn parse(
 
     root
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_keep_ordered_lists_intact() {
+        let input = "### Changed\n\n1. Update the config\n2. Run the migration\n   - Make a backup first\n3. Restart the server\n";
+        let node = Node::from_str(input).unwrap();
+
+        let ol = node
+            .find_node(|node| matches!(&node.data, Some(MarkdownToken::OrderedList)))
+            .unwrap();
+        assert_eq!(ol.children.len(), 4);
+        assert_eq!(node.to_string().trim(), input.trim());
+    }
+
+    #[test]
+    fn it_should_preserve_alternative_bullet_markers() {
+        let input = "### Added\n\n* Something new\n* Something else\n  + A detail\n\n### Fixed\n\n+ A fix\n";
+        let node = Node::from_str(input).unwrap();
+
+        let items =
+            node.filter_nodes(|node| matches!(&node.data, Some(MarkdownToken::ListItem(_, _, _))));
+        assert_eq!(items.len(), 4);
+        assert_eq!(node.to_string().trim(), input.trim());
+    }
+}
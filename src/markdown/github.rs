@@ -0,0 +1,91 @@
+use crate::github::repo::Repo;
+use crate::markdown::{ast::Node, tokens::MarkdownToken};
+use regex::{Captures, Regex};
+
+/// Render a (part of the) changelog the way GitHub renders Release bodies best: links to pull
+/// requests, issues and commits use GitHub's auto-linking (`#123`, full commit hashes), reference
+/// links are dropped and every heading moves up a level, so that sections become `##` headings.
+/// Links to other repositories keep their `owner/repo` prefix, e.g.: `acme/other#123`.
+pub fn render(node: &Node, repo: Option<&Repo>) -> String {
+    let mut node = node.clone();
+    rewrite(&mut node, repo);
+    node.to_string()
+}
+
+fn rewrite(node: &mut Node, repo: Option<&Repo>) {
+    node.children
+        .retain(|child| !matches!(&child.data, Some(MarkdownToken::Reference(_, _))));
+
+    node.data = match node.data.take() {
+        Some(MarkdownToken::H2(text)) => Some(MarkdownToken::H1(heading(&text))),
+        Some(MarkdownToken::H3(text)) => Some(MarkdownToken::H2(heading(&text))),
+        Some(MarkdownToken::Paragraph(text)) => Some(MarkdownToken::Paragraph(links(&text, repo))),
+        Some(MarkdownToken::ListItem(text, indent, marker)) => {
+            Some(MarkdownToken::ListItem(links(&text, repo), indent, marker))
+        }
+        Some(MarkdownToken::OrderedListItem(text, indent, number)) => Some(
+            MarkdownToken::OrderedListItem(links(&text, repo), indent, number),
+        ),
+        data => data,
+    };
+
+    for child in &mut node.children {
+        rewrite(child, repo);
+    }
+}
+
+/// Drop the brackets of the reference link in a version heading, e.g.: `[1.0.0] - 2022-01-01`
+fn heading(text: &str) -> String {
+    match text.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+        Some((name, rest)) if !rest.starts_with('(') => format!("{}{}", name, rest),
+        _ => text.to_string(),
+    }
+}
+
+/// Rewrite the links to pull requests, issues, discussions and commits to their auto-linked form
+fn links(text: &str, repo: Option<&Repo>) -> String {
+    let re = Regex::new(
+        r"\[[^\]]*\]\(https://github\.com/([^/\s)]+)/([^/\s)]+)/(pull|issues|discussions|commit)/([0-9a-fA-F]+)[^\s)]*\)",
+    )
+    .unwrap();
+
+    re.replace_all(text, |captures: &Captures| {
+        let prefix = match repo {
+            Some(repo) if repo.org == captures[1] && repo.repo == captures[2] => String::new(),
+            _ => format!("{}/{}", &captures[1], &captures[2]),
+        };
+
+        match &captures[3] {
+            "commit" if prefix.is_empty() => captures[4].to_string(),
+            "commit" => format!("{}@{}", prefix, &captures[4]),
+            _ => format!("{}#{}", prefix, &captures[4]),
+        }
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_should_render_github_release_bodies() {
+        let node = Node::from_str("## [1.0.0] - 2022-01-01\n\n### Fixed\n\n- Fix a bug ([#12](https://github.com/acme/widget/pull/12))\n- Fix a typo ([abcdef1](https://github.com/acme/widget/commit/abcdef1234567890))\n- Bump a dependency ([#3](https://github.com/acme/other/issues/3)) in [the docs](https://acme.com)\n\n[1.0.0]: https://github.com/acme/widget/releases/tag/v1.0.0\n").unwrap();
+        let repo = Repo::new("acme".to_string(), "widget".to_string());
+
+        assert_eq!(
+            render(&node, Some(&repo)).trim(),
+            [
+                "# 1.0.0 - 2022-01-01",
+                "",
+                "## Fixed",
+                "",
+                "- Fix a bug (#12)",
+                "- Fix a typo (abcdef1234567890)",
+                "- Bump a dependency (acme/other#3) in [the docs](https://acme.com)",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::markdown::{ast::Node, tokens::MarkdownToken};
+
+/// Render a (part of the) changelog to HTML. Only the markdown that shows up in changelogs is
+/// supported: headings, paragraphs, (nested) lists, links, inline code and bold or italic text.
+pub fn render(node: &Node) -> String {
+    let mut html = String::new();
+    write_node(&mut html, node);
+    html
+}
+
+fn write_node(html: &mut String, node: &Node) {
+    match &node.data {
+        Some(MarkdownToken::H1(text)) => html.push_str(&format!("<h1>{}</h1>\n", inline(text))),
+        Some(MarkdownToken::H2(text)) => html.push_str(&format!("<h2>{}</h2>\n", inline(text))),
+        Some(MarkdownToken::H3(text)) => html.push_str(&format!("<h3>{}</h3>\n", inline(text))),
+        Some(MarkdownToken::Paragraph(text)) => {
+            html.push_str(&format!("<p>{}</p>\n", inline(text.trim())))
+        }
+        Some(MarkdownToken::UnorderedList | MarkdownToken::OrderedList) => {
+            write_list(html, &node.children);
+            return;
+        }
+        Some(MarkdownToken::ListItem(text, _, _) | MarkdownToken::OrderedListItem(text, _, _)) => {
+            html.push_str(&format!("<li>{}</li>\n", inline(text)))
+        }
+        Some(MarkdownToken::Reference(_, _) | MarkdownToken::BlankLine) | None => {}
+    }
+
+    for child in &node.children {
+        write_node(html, child);
+    }
+}
+
+/// Write the items of a list, items that are indented more than the previous one start a nested
+/// list.
+fn write_list(html: &mut String, items: &[Node]) {
+    // The indentation and the closing tag of every open list
+    let mut open: Vec<(usize, &str)> = vec![];
+
+    for item in items {
+        let (text, indent, tag) = match &item.data {
+            Some(MarkdownToken::ListItem(text, indent, _)) => (text, *indent, "ul"),
+            Some(MarkdownToken::OrderedListItem(text, indent, _)) => (text, *indent, "ol"),
+            _ => continue,
+        };
+
+        while let Some((other, close)) = open.last() {
+            if *other <= indent {
+                break;
+            }
+            html.push_str(&format!("</li>\n</{}>\n", close));
+            open.pop();
+        }
+
+        match open.last() {
+            Some((other, _)) if *other == indent => html.push_str("</li>\n"),
+            _ => {
+                html.push_str(&format!("<{}>\n", tag));
+                open.push((indent, tag));
+            }
+        }
+
+        html.push_str(&format!("<li>{}", inline(text)));
+    }
+
+    while let Some((_, close)) = open.pop() {
+        html.push_str(&format!("</li>\n</{}>\n", close));
+    }
+}
+
+/// Render the inline markdown of a line of text
+fn inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut html = String::new();
+    let mut strong = false;
+    let mut emphasis = false;
+
+    let mut idx = 0;
+    while idx < chars.len() {
+        let rest: String = chars[idx..].iter().collect();
+
+        // Inline code, e.g.: `config.json`
+        if chars[idx] == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                html.push_str(&format!("<code>{}</code>", escape(&rest[1..end + 1])));
+                idx += rest[..end + 2].chars().count();
+                continue;
+            }
+        }
+
+        // Links, e.g.: [#123](https://github.com/...)
+        if chars[idx] == '[' {
+            if let Some((label, url, length)) = link(&rest) {
+                html.push_str(&format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape(url),
+                    inline(label)
+                ));
+                idx += length;
+                continue;
+            }
+        }
+
+        if rest.starts_with("**") || rest.starts_with("__") {
+            html.push_str(if strong { "</strong>" } else { "<strong>" });
+            strong = !strong;
+            idx += 2;
+            continue;
+        }
+
+        // A single `*` or `_` only emphasizes when it touches the text, e.g.: `*word*`, not `a * b`
+        // or `snake_case`
+        if (chars[idx] == '*' || chars[idx] == '_')
+            && (emphasis || chars.get(idx + 1).map(|c| !c.is_whitespace()) == Some(true))
+            && (chars[idx] == '*' || idx == 0 || !chars[idx - 1].is_alphanumeric())
+        {
+            html.push_str(if emphasis { "</em>" } else { "<em>" });
+            emphasis = !emphasis;
+            idx += 1;
+            continue;
+        }
+
+        html.push_str(&escape(&chars[idx].to_string()));
+        idx += 1;
+    }
+
+    if emphasis {
+        html.push_str("</em>");
+    }
+    if strong {
+        html.push_str("</strong>");
+    }
+
+    html
+}
+
+/// The label, url and length (in chars) of a link at the start of the text
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let (label, rest) = text[1..].split_once("](")?;
+    let (url, _) = rest.split_once(')')?;
+
+    Some((label, url, label.chars().count() + url.chars().count() + 4))
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_should_render_changelog_sections_to_html() {
+        let node = Node::from_str("### Fixed\n\n- Fix `<Foo />` in **all** [browsers](https://example.com) (#12)\n  - Only *some* of them\n- Keep snake_case\n\nSee the docs.\n").unwrap();
+
+        assert_eq!(
+            render(&node),
+            [
+                "<h3>Fixed</h3>",
+                "<ul>",
+                "<li>Fix <code>&lt;Foo /&gt;</code> in <strong>all</strong> <a href=\"https://example.com\">browsers</a> (#12)<ul>",
+                "<li>Only <em>some</em> of them</li>",
+                "</ul>",
+                "</li>",
+                "<li>Keep snake_case</li>",
+                "</ul>",
+                "<p>See the docs.</p>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
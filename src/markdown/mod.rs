@@ -1,2 +1,5 @@
 pub mod ast;
+pub mod github;
+pub mod html;
+pub mod plain;
 pub mod tokens;
@@ -0,0 +1,158 @@
+use crate::markdown::{ast::Node, tokens::MarkdownToken};
+
+/// Render a (part of the) changelog as plain text, e.g.: for Slack or emails. Headings lose their
+/// `#` markers, links become `label (url)` and emphasis, inline code and comments are removed.
+pub fn render(node: &Node) -> String {
+    let mut blocks: Vec<String> = vec![];
+    write_node(&mut blocks, node);
+    blocks.join("\n\n")
+}
+
+fn write_node(blocks: &mut Vec<String>, node: &Node) {
+    match &node.data {
+        Some(MarkdownToken::H1(text) | MarkdownToken::H2(text) | MarkdownToken::H3(text)) => {
+            blocks.push(inline(text))
+        }
+        Some(MarkdownToken::Paragraph(text)) => blocks.push(
+            text.trim()
+                .lines()
+                .map(|line| inline(line.trim()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        Some(MarkdownToken::UnorderedList | MarkdownToken::OrderedList) => {
+            blocks.push(
+                node.children
+                    .iter()
+                    .filter_map(|item| match &item.data {
+                        Some(MarkdownToken::ListItem(text, indent, _)) => {
+                            Some(format!("{}- {}", " ".repeat(*indent), inline(text)))
+                        }
+                        Some(MarkdownToken::OrderedListItem(text, indent, number)) => Some(
+                            format!("{}{}. {}", " ".repeat(*indent), number, inline(text)),
+                        ),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+            return;
+        }
+        Some(MarkdownToken::ListItem(text, _, _) | MarkdownToken::OrderedListItem(text, _, _)) => {
+            blocks.push(format!("- {}", inline(text)))
+        }
+        Some(MarkdownToken::Reference(_, _) | MarkdownToken::BlankLine) | None => {}
+    }
+
+    for child in &node.children {
+        write_node(blocks, child);
+    }
+}
+
+/// Strip the inline markdown of a line of text
+fn inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut plain = String::new();
+    let mut emphasis = false;
+
+    let mut idx = 0;
+    while idx < chars.len() {
+        let rest: String = chars[idx..].iter().collect();
+
+        // Hidden comments, e.g.: `<!-- changelog: by=@robin -->`
+        if rest.starts_with("<!--") {
+            if let Some(end) = rest.find("-->") {
+                idx += rest[..end + 3].chars().count();
+                continue;
+            }
+        }
+
+        // Inline code, e.g.: `config.json`
+        if chars[idx] == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                plain.push_str(&rest[1..end + 1]);
+                idx += rest[..end + 2].chars().count();
+                continue;
+            }
+        }
+
+        // Links, e.g.: [#123](https://github.com/...)
+        if chars[idx] == '[' {
+            if let Some((label, url, length)) = link(&rest) {
+                let label = inline(label);
+                match label == url {
+                    true => plain.push_str(url),
+                    false => plain.push_str(&format!("{} ({})", label, url)),
+                }
+                idx += length;
+                continue;
+            }
+
+            // Reference links, e.g.: the `[1.0.0]` in `[1.0.0] - 2022-01-01`
+            if let Some(end) = rest.find(']') {
+                plain.push_str(&inline(&rest[1..end]));
+                idx += rest[..end + 1].chars().count();
+                continue;
+            }
+        }
+
+        if rest.starts_with("**") || rest.starts_with("__") {
+            idx += 2;
+            continue;
+        }
+
+        // A single `*` or `_` only emphasizes when it touches the text, e.g.: `*word*`, not `a * b`
+        // or `snake_case`
+        if (chars[idx] == '*' || chars[idx] == '_')
+            && (emphasis || chars.get(idx + 1).map(|c| !c.is_whitespace()) == Some(true))
+            && (chars[idx] == '*' || idx == 0 || !chars[idx - 1].is_alphanumeric())
+        {
+            emphasis = !emphasis;
+            idx += 1;
+            continue;
+        }
+
+        plain.push(chars[idx]);
+        idx += 1;
+    }
+
+    plain.trim_end().to_string()
+}
+
+/// The label, url and length (in chars) of a link at the start of the text
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let (label, rest) = text[1..].split_once("](")?;
+    let (url, _) = rest.split_once(')')?;
+    if label.contains(']') {
+        return None;
+    }
+
+    Some((label, url, label.chars().count() + url.chars().count() + 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_should_render_changelog_sections_as_plain_text() {
+        let node = Node::from_str("## [1.0.0] - 2022-01-01\n\n### Fixed\n\n- Fix `<Foo />` in **all** [browsers](https://example.com) ([#12](https://github.com/a/b/pull/12)) <!-- changelog: by=@robin -->\n  - Only *some* of them, see [https://example.com](https://example.com)\n- Keep snake_case\n\nSee the docs.\n\n[1.0.0]: https://github.com/a/b/releases/tag/v1.0.0\n").unwrap();
+
+        assert_eq!(
+            render(&node),
+            [
+                "1.0.0 - 2022-01-01",
+                "",
+                "Fixed",
+                "",
+                "- Fix <Foo /> in all browsers (https://example.com) (#12 (https://github.com/a/b/pull/12))",
+                "  - Only some of them, see https://example.com",
+                "- Keep snake_case",
+                "",
+                "See the docs.",
+            ]
+            .join("\n")
+        );
+    }
+}
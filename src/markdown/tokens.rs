@@ -7,7 +7,10 @@ pub enum MarkdownToken {
     H3(String),
     Paragraph(String),
     UnorderedList,
-    ListItem(String, usize),
+    /// The text, the indentation and the bullet marker (`-`, `*` or `+`)
+    ListItem(String, usize, char),
+    OrderedList,
+    OrderedListItem(String, usize, usize),
     Reference(String, String),
     BlankLine,
 }
@@ -17,45 +20,70 @@ impl MarkdownToken {
     pub fn lex(contents: &str) -> Vec<MarkdownToken> {
         contents
             .split("\n\n")
-            .filter(|line| !line.is_empty())
-            .flat_map(|group| match &group.trim()[..1] {
-                "#" | "-" | "[" => group
-                    .lines()
-                    .map(|line| {
-                        let spaces = line.chars().take_while(|c| c.is_whitespace()).count();
-                        let l = line.trim_start();
-                        match l {
-                            line if line.starts_with("# ") => {
-                                MarkdownToken::H1(line[2..].to_string())
-                            }
-                            line if line.starts_with("## ") => {
-                                MarkdownToken::H2(line[3..].to_string())
-                            }
-                            line if line.starts_with("### ") => {
-                                MarkdownToken::H3(line[4..].to_string())
-                            }
-                            line if line.starts_with("- ") => {
-                                MarkdownToken::ListItem(line[2..].to_string(), spaces)
-                            }
-                            line if line.starts_with('[') => {
-                                let mut parts = line.split(": ");
-                                let name = parts.next().unwrap();
-                                let link = parts.next().unwrap();
-                                MarkdownToken::Reference(
-                                    name[1..(name.len() - 1)].to_string(),
-                                    link.to_string(),
-                                )
-                            }
-                            _ => MarkdownToken::Paragraph(l.to_string()),
-                        }
-                    })
-                    .collect(),
+            .filter(|line| !line.trim().is_empty())
+            .flat_map(|group| match group.trim().chars().next() {
+                _ if is_ordered_list_item(group.trim()) || is_bullet_list_item(group.trim()) => {
+                    group.lines().map(lex_line).collect()
+                }
+                Some('#' | '-' | '[') => group.lines().map(lex_line).collect(),
                 _ => vec![MarkdownToken::Paragraph(group.to_string())],
             })
             .collect()
     }
 }
 
+/// Convert a single line to a proper MarkdownToken
+fn lex_line(line: &str) -> MarkdownToken {
+    let spaces = line.chars().take_while(|c| c.is_whitespace()).count();
+    let l = line.trim_start();
+    match l {
+        line if line.starts_with("# ") => MarkdownToken::H1(line[2..].to_string()),
+        line if line.starts_with("## ") => MarkdownToken::H2(line[3..].to_string()),
+        line if line.starts_with("### ") => MarkdownToken::H3(line[4..].to_string()),
+        line if is_bullet_list_item(line) => {
+            MarkdownToken::ListItem(line[2..].to_string(), spaces, line.chars().next().unwrap())
+        }
+        line if line.starts_with('[') => match reference(line) {
+            Some((name, link)) => MarkdownToken::Reference(name.to_string(), link.to_string()),
+            None => MarkdownToken::Paragraph(l.to_string()),
+        },
+        line => match ordered_list_item(line) {
+            Some((number, rest)) => {
+                MarkdownToken::OrderedListItem(rest.to_string(), spaces, number)
+            }
+            None => MarkdownToken::Paragraph(l.to_string()),
+        },
+    }
+}
+
+/// Whether the line looks like `- Some item`, `* Some item` or `+ Some item`
+fn is_bullet_list_item(line: &str) -> bool {
+    ["- ", "* ", "+ "]
+        .iter()
+        .any(|marker| line.starts_with(marker))
+}
+
+/// Whether the line looks like `1. Some item`
+fn is_ordered_list_item(line: &str) -> bool {
+    ordered_list_item(line).is_some()
+}
+
+/// The number and the text of a line like `1. Some item`, numbers that don't fit are not a list
+fn ordered_list_item(line: &str) -> Option<(usize, &str)> {
+    line.split_once(". ")
+        .filter(|(number, _)| number.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|(number, rest)| number.parse::<usize>().ok().map(|number| (number, rest)))
+}
+
+/// The name and the link of a line like `[name]: link`, half typed references are not a reference
+fn reference(line: &str) -> Option<(&str, &str)> {
+    line.split_once(": ").and_then(|(name, link)| {
+        name.strip_prefix('[')
+            .and_then(|name| name.strip_suffix(']'))
+            .map(|name| (name, link))
+    })
+}
+
 impl Display for MarkdownToken {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -64,11 +92,54 @@ impl Display for MarkdownToken {
             MarkdownToken::H3(line) => writeln!(f, "### {}", line),
             MarkdownToken::Paragraph(line) => writeln!(f, "{}", line),
             MarkdownToken::UnorderedList => Ok(()),
-            MarkdownToken::ListItem(line, indent) => {
-                write!(f, "{}- {}", " ".repeat(*indent), line)
+            MarkdownToken::ListItem(line, indent, marker) => {
+                write!(f, "{}{} {}", " ".repeat(*indent), marker, line)
+            }
+            MarkdownToken::OrderedList => Ok(()),
+            MarkdownToken::OrderedListItem(line, indent, number) => {
+                write!(f, "{}{}. {}", " ".repeat(*indent), number, line)
             }
             MarkdownToken::Reference(name, link) => write!(f, "[{}]: {}", name, link),
             MarkdownToken::BlankLine => write!(f, ""),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_lex_overflowing_numbers_as_paragraphs() {
+        assert_eq!(
+            MarkdownToken::lex("1. foo\n2. bar"),
+            vec![
+                MarkdownToken::OrderedListItem("foo".to_string(), 0, 1),
+                MarkdownToken::OrderedListItem("bar".to_string(), 0, 2),
+            ]
+        );
+        assert_eq!(
+            MarkdownToken::lex("99999999999999999999. foo"),
+            vec![MarkdownToken::Paragraph(
+                "99999999999999999999. foo".to_string()
+            )]
+        );
+        assert_eq!(
+            lex_line("  99999999999999999999. foo"),
+            MarkdownToken::Paragraph("99999999999999999999. foo".to_string())
+        );
+    }
+
+    #[test]
+    fn it_should_lex_malformed_blocks_as_paragraphs() {
+        assert_eq!(
+            MarkdownToken::lex("# Changelog\n\n   \n\nÉtat\n\n[unreleased\n["),
+            vec![
+                MarkdownToken::H1("Changelog".to_string()),
+                MarkdownToken::Paragraph("État".to_string()),
+                MarkdownToken::Paragraph("[unreleased".to_string()),
+                MarkdownToken::Paragraph("[".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+/// Entries can carry metadata in a hidden comment at the end, which doesn't show up in the
+/// rendered changelog, e.g.: `Fix a bug <!-- changelog: by=@robin review=pending -->`
+const PREFIX: &str = "<!-- changelog:";
+const SUFFIX: &str = "-->";
+
+/// Split an entry into its text and its metadata
+pub fn parse(entry: &str) -> (&str, Vec<(String, String)>) {
+    let start = match entry.rfind(PREFIX) {
+        Some(start) if entry.trim_end().ends_with(SUFFIX) => start,
+        _ => return (entry, vec![]),
+    };
+
+    let comment = entry[start + PREFIX.len()..].trim_end();
+    let comment = &comment[..comment.len() - SUFFIX.len()];
+
+    let mut metadata = vec![];
+    let mut rest = comment.trim();
+    while let Some((key, value)) = rest.split_once('=') {
+        let (value, remaining) = match value.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, remaining)) => (value, remaining),
+                None => (quoted, ""),
+            },
+            None => value.split_once(' ').unwrap_or((value, "")),
+        };
+
+        metadata.push((key.trim().to_string(), value.to_string()));
+        rest = remaining.trim();
+    }
+
+    (entry[..start].trim_end(), metadata)
+}
+
+/// Get the value of a metadata key of an entry
+pub fn get(entry: &str, key: &str) -> Option<String> {
+    parse(entry)
+        .1
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value)
+}
+
+/// Set a metadata key of an entry, keeping the other keys
+pub fn set(entry: &str, key: &str, value: &str) -> String {
+    let (text, mut metadata) = parse(entry);
+
+    match metadata.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value.to_string(),
+        None => metadata.push((key.to_string(), value.to_string())),
+    }
+
+    format!(
+        "{} {} {} {}",
+        text,
+        PREFIX,
+        metadata
+            .iter()
+            .map(
+                |(key, value)| match value.contains(char::is_whitespace) || value.is_empty() {
+                    true => format!("{}=\"{}\"", key, value),
+                    false => format!("{}={}", key, value),
+                }
+            )
+            .collect::<Vec<_>>()
+            .join(" "),
+        SUFFIX
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_read_and_write_entry_metadata() {
+        let entry = set("Fix a bug (#1)", "by", "Robin Malfait");
+        assert_eq!(
+            entry,
+            "Fix a bug (#1) <!-- changelog: by=\"Robin Malfait\" -->"
+        );
+
+        let entry = set(&entry, "review", "pending");
+        assert_eq!(get(&entry, "by"), Some("Robin Malfait".to_string()));
+        assert_eq!(get(&entry, "review"), Some("pending".to_string()));
+
+        let entry = set(&entry, "review", "approved");
+        assert_eq!(
+            entry,
+            "Fix a bug (#1) <!-- changelog: by=\"Robin Malfait\" review=approved -->"
+        );
+        assert_eq!(parse(&entry).0, "Fix a bug (#1)");
+        assert_eq!(parse("Plain entry"), ("Plain entry", vec![]));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::config::BREAKING_SECTION;
+use regex::Regex;
+
+/// Common section names of hand written changelogs, and the section they map to
+const SECTION_ALIASES: [(&str, &str); 7] = [
+    ("features", "Added"),
+    ("new features", "Added"),
+    ("feature", "Added"),
+    ("bug fixes", "Fixed"),
+    ("bugfixes", "Fixed"),
+    ("bug fix", "Fixed"),
+    ("fixes", "Fixed"),
+];
+
+/// Heuristically rewrite a hand written changelog into the structure we expect. Setext headings
+/// become ATX headings, version headings (at any level, with or without a `v` prefix, brackets or
+/// a link) become `## [1.2.3] - <date>` headings, and known section names (as headings, bold text
+/// or `Added:` lines) become `### <Section>` headings. Every heading gets surrounded by blank lines.
+pub fn normalize(contents: &str, sections: &[String]) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut output: Vec<String> = vec![];
+    let mut has_title = false;
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx].trim_end();
+        let next = lines.get(idx + 1).map(|line| line.trim());
+
+        // Setext headings, a line underlined with `===` or `---`
+        let setext = match next {
+            _ if line.trim().is_empty() || is_list_item(line) => None,
+            Some(next) if !next.is_empty() && next.chars().all(|c| c == '=') => Some(1),
+            Some(next) if next.len() > 1 && next.chars().all(|c| c == '-') => Some(2),
+            _ => None,
+        };
+
+        let heading = match setext {
+            Some(level) => {
+                idx += 1;
+                Some((level, line.trim()))
+            }
+            None => atx_heading(line),
+        };
+
+        let text = line.trim();
+        let section = match heading {
+            Some((_, text)) => section_name(text, sections),
+            None => text
+                .strip_prefix("**")
+                .and_then(|text| text.strip_suffix("**"))
+                .map(|text| text.trim_end_matches(':'))
+                .or_else(|| text.strip_suffix(':'))
+                .and_then(|text| section_name(text, sections)),
+        };
+
+        match (heading, section) {
+            (_, Some(section)) => push_heading(&mut output, format!("### {}", section)),
+            (Some((level, text)), None) => {
+                if text.to_lowercase().trim_matches(['[', ']']) == "unreleased" {
+                    push_heading(&mut output, "## [Unreleased]".to_string());
+                } else if let Some(heading) = version_heading(text) {
+                    push_heading(&mut output, format!("## {}", heading));
+                } else if level == 1 && !has_title {
+                    has_title = true;
+                    push_heading(&mut output, format!("# {}", text));
+                } else {
+                    push_heading(&mut output, format!("{} {}", "#".repeat(level), text));
+                }
+            }
+            (None, None) => {
+                // Collapse consecutive blank lines
+                if !(line.is_empty() && output.last().map(|l| l.is_empty()).unwrap_or(true)) {
+                    output.push(line.to_string());
+                }
+            }
+        }
+
+        idx += 1;
+    }
+
+    while output.last().map(|line| line.is_empty()).unwrap_or(false) {
+        output.pop();
+    }
+
+    output.join("\n") + "\n"
+}
+
+/// Group the notes of a GitHub Release per section. Headings, bold text or `Fixed:` lines with a
+/// known section name start that section, any other heading (e.g.: `What's Changed`) falls back to
+/// the default section. List items and loose paragraphs become entries, nested list items and the
+/// `New Contributors` and `Full Changelog` boilerplate are dropped.
+pub fn release_sections(
+    body: &str,
+    sections: &[String],
+    default: &str,
+) -> Vec<(String, Vec<String>)> {
+    let mut output: Vec<(String, Vec<String>)> = vec![];
+    let mut current = Some(default.to_string());
+
+    for line in body.lines() {
+        let text = line.trim();
+        if text.is_empty() || text.starts_with("**Full Changelog**") {
+            continue;
+        }
+
+        let heading = atx_heading(text).map(|(_, text)| text).or_else(|| {
+            text.strip_prefix("**")
+                .and_then(|text| text.strip_suffix("**"))
+                .map(|text| text.trim_end_matches(':'))
+                .or_else(|| text.strip_suffix(':').filter(|_| !is_list_item(text)))
+        });
+
+        if let Some(heading) = heading {
+            current = match section_name(heading, sections) {
+                Some(section) => Some(section),
+                None if heading.eq_ignore_ascii_case("new contributors") => None,
+                None => Some(default.to_string()),
+            };
+            continue;
+        }
+
+        // Nested list items are details of the entry above
+        if line.starts_with([' ', '\t']) && is_list_item(line) {
+            continue;
+        }
+
+        let (section, entry) = match &current {
+            Some(section) => (
+                section,
+                text.get(2..).filter(|_| is_list_item(text)).unwrap_or(text),
+            ),
+            None => continue,
+        };
+
+        match output.iter_mut().find(|(name, _)| name == section) {
+            Some((_, entries)) => entries.push(entry.trim().to_string()),
+            None => output.push((section.to_string(), vec![entry.trim().to_string()])),
+        }
+    }
+
+    output
+}
+
+/// Push a heading, surrounded by blank lines
+fn push_heading(output: &mut Vec<String>, heading: String) {
+    if output.last().map(|line| !line.is_empty()).unwrap_or(false) {
+        output.push(String::new());
+    }
+    output.push(heading);
+    output.push(String::new());
+}
+
+/// The level and text of an ATX heading like `## Some heading ##`
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+
+    match level {
+        1..=6 => Some((level, text.trim().trim_end_matches('#').trim())),
+        _ => None,
+    }
+}
+
+fn is_list_item(line: &str) -> bool {
+    matches!(line.trim_start().get(..2), Some("- " | "* " | "+ "))
+}
+
+/// Resolve the section a heading refers to, e.g.: `Bug Fixes` becomes `Fixed`
+fn section_name(text: &str, sections: &[String]) -> Option<String> {
+    let text = text.trim();
+    let lowercase = text.to_lowercase();
+
+    sections
+        .iter()
+        .map(|section| section.as_str())
+        .chain([BREAKING_SECTION])
+        .find(|section| section.to_lowercase() == lowercase)
+        .map(|section| section.to_string())
+        .or_else(|| {
+            SECTION_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == lowercase)
+                .map(|(_, section)| section.to_string())
+        })
+}
+
+/// Rewrite a heading that starts with a version, e.g.: `v1.2.3 (2021-03-05)`, `Version 1.2.3` or
+/// `[1.2.3](https://github.com/...) (2021-03-05)`, into `[1.2.3] - 2021-03-05`. The date itself is
+/// normalized later on.
+fn version_heading(text: &str) -> Option<String> {
+    let regex = Regex::new(
+        r"(?i)^(?:version\s+|release\s+)?\[?v?(\d+\.\d+(?:\.\d+)?(?:-[0-9a-z.-]+)?(?:\+[0-9a-z.-]+)?)\]?(.*)$",
+    )
+    .unwrap();
+
+    let captures = regex.captures(text)?;
+    let version = &captures[1];
+    let mut rest = captures[2].trim();
+
+    // A link to the release or to the comparison, e.g.: `[1.2.3](https://...)`
+    if let Some(link) = rest.strip_prefix('(').filter(|link| link.contains("://")) {
+        rest = link
+            .split_once(')')
+            .map(|(_, rest)| rest.trim())
+            .unwrap_or("");
+    }
+
+    let rest = rest
+        .trim_start_matches(['-', '–', '—'])
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+
+    Some(match rest.is_empty() {
+        true => format!("[{}]", version),
+        false => format!("[{}] - {}", version, rest),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_normalize_hand_written_changelogs() {
+        let sections = vec!["Added".to_string(), "Fixed".to_string()];
+        let contents = [
+            "Changelog",
+            "=========",
+            "",
+            "Unreleased",
+            "----------",
+            "* Something new",
+            "",
+            "## v1.2.0 (March 5, 2021)",
+            "**Features**",
+            "* A feature",
+            "",
+            "Bug fixes:",
+            "* A fix",
+            "",
+            "",
+            "# [1.1.0](https://github.com/acme/widget/compare/v1.0.0...v1.1.0) (2021-02-01)",
+            "### Bug Fixes",
+            "- Another fix",
+            "",
+            "### Version 1.0.0",
+            "Initial release",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            normalize(&contents, &sections),
+            [
+                "# Changelog",
+                "",
+                "## [Unreleased]",
+                "",
+                "* Something new",
+                "",
+                "## [1.2.0] - March 5, 2021",
+                "",
+                "### Added",
+                "",
+                "* A feature",
+                "",
+                "### Fixed",
+                "",
+                "* A fix",
+                "",
+                "## [1.1.0] - 2021-02-01",
+                "",
+                "### Fixed",
+                "",
+                "- Another fix",
+                "",
+                "## [1.0.0]",
+                "",
+                "Initial release",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn it_should_group_the_notes_of_github_releases() {
+        let sections = vec!["Added".to_string(), "Fixed".to_string()];
+        let body = [
+            "## What's Changed",
+            "* Improve the docs by @alice in https://github.com/acme/widget/pull/1",
+            "### Bug Fixes",
+            "- Fix a crash",
+            "  - Only on Windows",
+            "",
+            "**Features**",
+            "- A feature",
+            "",
+            "## New Contributors",
+            "* @alice made their first contribution",
+            "",
+            "**Full Changelog**: https://github.com/acme/widget/compare/v1.0.0...v1.1.0",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            release_sections(&body, &sections, "Changed"),
+            vec![
+                (
+                    "Changed".to_string(),
+                    vec![
+                        "Improve the docs by @alice in https://github.com/acme/widget/pull/1"
+                            .to_string()
+                    ]
+                ),
+                ("Fixed".to_string(), vec!["Fix a crash".to_string()]),
+                ("Added".to_string(), vec!["A feature".to_string()]),
+            ]
+        );
+    }
+}
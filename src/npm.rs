@@ -1,59 +1,283 @@
-use crate::SemVer;
+use changelog::SemVer;
 use color_eyre::eyre::{eyre, Result};
+use dialoguer::Input;
+use std::fmt::Display;
+use std::path::Path;
 use std::process::Command;
+use std::str::FromStr;
+
+/// The tool that is used to version (and publish) packages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Npm,
+    Yarn,
+    Pnpm,
+}
+
+impl PackageManager {
+    /// Detect the package manager from the lockfile in the directory or one of its parents,
+    /// defaults to npm.
+    pub fn detect(dir: &Path) -> Self {
+        dir.ancestors()
+            .find_map(|dir| {
+                [
+                    PackageManager::Pnpm,
+                    PackageManager::Yarn,
+                    PackageManager::Npm,
+                ]
+                .into_iter()
+                .find(|package_manager| dir.join(package_manager.lockfile()).exists())
+            })
+            .unwrap_or(PackageManager::Npm)
+    }
+
+    pub fn lockfile(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "package-lock.json",
+            PackageManager::Yarn => "yarn.lock",
+            PackageManager::Pnpm => "pnpm-lock.yaml",
+        }
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Pnpm => "pnpm",
+        }
+    }
+}
+
+impl FromStr for PackageManager {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "npm" => Ok(PackageManager::Npm),
+            "yarn" => Ok(PackageManager::Yarn),
+            "pnpm" => Ok(PackageManager::Pnpm),
+            _ => Err(format!(
+                "Invalid package manager: {}, expected one of \"npm\", \"yarn\" or \"pnpm\"",
+                s
+            )),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Npm {
     pwd: String,
+    registry: Option<String>,
+    package_manager: PackageManager,
 }
 
 pub struct Options {
     pub no_git_tag_version: bool,
 }
 
+/// Who can install a published scoped package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Public,
+    Restricted,
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::Public => write!(f, "public"),
+            Access::Restricted => write!(f, "restricted"),
+        }
+    }
+}
+
+impl FromStr for Access {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Access::Public),
+            "restricted" => Ok(Access::Restricted),
+            _ => Err(format!(
+                "Invalid access: {}, expected one of \"public\" or \"restricted\"",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PublishOptions {
+    pub access: Option<Access>,
+
+    /// The dist-tag to publish under, npm uses "latest" by default
+    pub tag: Option<String>,
+
+    /// The one-time password for accounts with 2FA enabled, you're prompted for it when npm asks
+    /// for one
+    pub otp: Option<String>,
+
+    /// Publish with a signed provenance statement, which links the package to the CI run that
+    /// built it
+    pub provenance: bool,
+
+    /// Whether we can prompt for a one-time password
+    pub interactive: bool,
+}
+
 impl Npm {
     pub fn new(pwd: Option<&str>) -> Result<Self> {
         match pwd {
             Some(pwd) => Ok(Npm {
                 pwd: pwd.to_string(),
+                registry: None,
+                package_manager: PackageManager::Npm,
             }),
             None => Ok(Npm {
                 pwd: std::env::current_dir()?.display().to_string(),
+                registry: None,
+                package_manager: PackageManager::Npm,
             }),
         }
     }
 
+    /// Run every npm command against this registry instead of the configured one. Scope
+    /// registries configured in `.npmrc` are still respected by npm itself.
+    pub fn with_registry(mut self, registry: Option<&str>) -> Self {
+        self.registry = registry.map(|registry| registry.to_string());
+        self
+    }
+
+    /// Run every command with this package manager instead of npm
+    pub fn with_package_manager(mut self, package_manager: PackageManager) -> Self {
+        self.package_manager = package_manager;
+        self
+    }
+
     pub fn version_options(&self, version: &SemVer, options: Options) -> Result<&Self> {
-        self.exec(vec![
-            "version",
-            &version.to_string(),
-            match options.no_git_tag_version {
-                true => "--no-git-tag-version",
-                false => "",
-            },
-        ])?;
+        let version = version.to_string();
+        let mut args = match (self.package_manager, self.is_yarn_berry()) {
+            // Yarn 2+ doesn't create commits or tags
+            (PackageManager::Yarn, true) => vec!["version", &version],
+            (PackageManager::Yarn, false) => vec!["version", "--new-version", &version],
+            (PackageManager::Npm | PackageManager::Pnpm, _) => vec!["version", &version],
+        };
+
+        if options.no_git_tag_version && !self.is_yarn_berry() {
+            args.push("--no-git-tag-version");
+        }
+
+        self.exec(args)?;
 
         Ok(self)
     }
 
-    pub fn exec(&self, args: Vec<&str>) -> Result<String> {
-        let mut cmd = Command::new("npm");
+    pub fn publish(&self, options: &PublishOptions) -> Result<&Self> {
+        let mut args = match (self.package_manager, self.is_yarn_berry()) {
+            (PackageManager::Yarn, true) => vec!["npm".to_string(), "publish".to_string()],
+            (PackageManager::Yarn, false) => {
+                vec!["publish".to_string(), "--non-interactive".to_string()]
+            }
+            (PackageManager::Npm | PackageManager::Pnpm, _) => vec!["publish".to_string()],
+        };
+
+        if let Some(access) = &options.access {
+            args.push(format!("--access={}", access));
+        }
+
+        if let Some(tag) = &options.tag {
+            args.push(format!("--tag={}", tag));
+        }
+
+        if let Some(otp) = &options.otp {
+            args.push(format!("--otp={}", otp));
+        }
+
+        if options.provenance {
+            args.push("--provenance".to_string());
+        }
+
+        match self.exec(args.iter().map(|arg| arg.as_str()).collect()) {
+            Ok(_) => Ok(self),
+
+            // Accounts with 2FA enabled need a one-time password to publish
+            Err(e)
+                if options.interactive
+                    && options.otp.is_none()
+                    && e.to_string().contains("EOTP") =>
+            {
+                let otp: String = Input::new()
+                    .with_prompt("One-time password")
+                    .interact_text()?;
+
+                self.publish(&PublishOptions {
+                    otp: Some(otp),
+                    ..options.clone()
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Yarn 2+ is configured via a `.yarnrc.yml` file, and has a different CLI than Yarn 1
+    fn is_yarn_berry(&self) -> bool {
+        self.package_manager == PackageManager::Yarn
+            && Path::new(&self.pwd)
+                .ancestors()
+                .any(|dir| dir.join(".yarnrc.yml").exists())
+    }
+
+    /// Run the package manager, a failing command is an error that includes its stderr
+    fn exec(&self, args: Vec<&str>) -> Result<String> {
+        let mut cmd = Command::new(self.package_manager.program());
 
         cmd.current_dir(&self.pwd);
 
-        for arg in args {
+        for arg in &args {
             cmd.arg(arg);
         }
 
+        if let Some(registry) = &self.registry {
+            cmd.arg(format!("--registry={}", registry));
+        }
+
         match cmd.output() {
-            Ok(output) => {
+            Ok(output) if output.status.success() => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 let stdout = stdout.trim();
                 let stdout = stdout.to_string();
 
                 Ok(stdout)
             }
+            Ok(output) => Err(eyre!(
+                "`{} {}` failed:\n{}",
+                self.package_manager.program(),
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
             Err(e) => Err(eyre!(e)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_detect_the_package_manager_from_the_lockfile() {
+        let dir = std::env::temp_dir().join(format!("changelog-{}", uuid::Uuid::new_v4()));
+        let package = dir.join("packages").join("ui");
+        std::fs::create_dir_all(&package).unwrap();
+
+        assert_eq!(PackageManager::detect(&package), PackageManager::Npm);
+
+        std::fs::write(dir.join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(PackageManager::detect(&package), PackageManager::Pnpm);
+
+        std::fs::write(package.join("yarn.lock"), "").unwrap();
+        assert_eq!(PackageManager::detect(&package), PackageManager::Yarn);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,47 @@
 use colored::*;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+/// When to use colors in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when stdout and stderr are terminals, and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR")
+                    .map(|value| value.is_empty())
+                    .unwrap_or(true)
+                    && std::io::stdout().is_terminal()
+                    && std::io::stderr().is_terminal()
+            }
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "Invalid color choice: {}, expected one of \"auto\", \"always\" or \"never\"",
+                s
+            )),
+        }
+    }
+}
 
 /// Small wrapper to have a nice output that is indented and contains a CHANGELOG header. Also
 /// playing with some eprintln so that piping it to another process or redirecting it to a file
@@ -18,6 +61,11 @@ pub fn output(str: String) {
     eprintln!()
 }
 
+pub fn output_warning(str: String) {
+    eprintln!();
+    eprintln!("  {} {}", " WARNING ".black().on_yellow().bold(), str);
+}
+
 pub fn output_title(title: String, message: String) {
     eprintln!();
 
@@ -1,13 +1,16 @@
+use crate::cargo::CargoToml;
 use color_eyre::eyre::{eyre, Error, Result};
 use colored::*;
-use glob::glob;
+use glob::{glob, Pattern};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 /// Semantic Versioning 2.0.0: https://semver.org
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, Default)]
 pub struct SemVer {
     /// Version when you make incompatible API changes
     major: u64,
@@ -21,6 +24,11 @@ pub struct SemVer {
     /// A pre-release version MAY be denoted by appending a hyphen and a series of dot separated
     /// identifiers immediately following the patch version.
     pre_release: Option<String>,
+
+    /// Build metadata MAY be denoted by appending a plus sign and a series of dot separated
+    /// identifiers immediately following the patch or pre-release version. Build metadata MUST be
+    /// ignored when determining version precedence.
+    build: Option<String>,
 }
 
 impl SemVer {
@@ -30,14 +38,41 @@ impl SemVer {
             minor,
             patch,
             pre_release,
+            build: None,
         }
     }
 
-    pub fn change_to(&mut self, version: &str) -> Result<Self, Error> {
+    pub fn major(&self) -> u64 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u64 {
+        self.minor
+    }
+
+    /// The npm dist-tag of a pre-release, e.g.: `beta` for `1.2.4-beta.0`, or `next` when there
+    /// is no preid. Normal versions use npm's default `latest` tag.
+    pub fn dist_tag(&self) -> Option<String> {
+        let pre_release = self.pre_release.as_ref()?;
+        let preid = pre_release.split('.').next().unwrap_or(pre_release);
+
+        Some(match preid.parse::<u64>() {
+            Ok(_) => "next".to_string(),
+            Err(_) => preid.to_string(),
+        })
+    }
+
+    /// Change the version according to the given strategy. The `preid` is used as the prefix of
+    /// the pre-release identifier for the pre-release strategies, e.g.: `beta` in `1.2.4-beta.0`.
+    pub fn change_to(&mut self, version: &str, preid: Option<&str>) -> Result<Self, Error> {
         let version = match version {
             "major" => self.new_major(),
             "minor" => self.new_minor(),
             "patch" => self.new_patch(),
+            "premajor" => self.new_major().with_pre_release(preid),
+            "preminor" => self.new_minor().with_pre_release(preid),
+            "prepatch" => self.new_patch().with_pre_release(preid),
+            "prerelease" => self.new_pre_release(preid),
             "infer" => self.clone(),
             _ => version.parse::<Self>()?,
         };
@@ -60,18 +95,118 @@ impl SemVer {
     fn new_patch(&self) -> Self {
         Self::new(self.major, self.minor, self.patch + 1, None)
     }
+
+    /// The first pre-release of this version, e.g.: `1.2.4-beta.0` or `1.2.4-0` without a preid
+    fn with_pre_release(mut self, preid: Option<&str>) -> Self {
+        self.pre_release = Some(match preid {
+            Some(preid) => format!("{}.0", preid),
+            None => "0".to_string(),
+        });
+        self
+    }
+
+    /// The next pre-release, same as npm: `1.2.4-beta.0` becomes `1.2.4-beta.1`, a different
+    /// preid starts over at `1.2.4-alpha.0`, and a normal version `1.2.3` becomes `1.2.4-beta.0`.
+    fn new_pre_release(&self, preid: Option<&str>) -> Self {
+        let pre_release = match &self.pre_release {
+            Some(pre_release) => pre_release,
+            None => return self.new_patch().with_pre_release(preid),
+        };
+
+        let (prefix, number) = match pre_release.rsplit_once('.') {
+            Some((prefix, number)) => (Some(prefix), number),
+            None => (None, pre_release.as_str()),
+        };
+
+        match (prefix, number.parse::<u64>()) {
+            (prefix, Ok(number)) if preid.is_none() || preid == prefix => {
+                let mut version = Self::new(self.major, self.minor, self.patch, None);
+                version.pre_release = Some(match prefix {
+                    Some(prefix) => format!("{}.{}", prefix, number + 1),
+                    None => (number + 1).to_string(),
+                });
+                version
+            }
+            _ if preid.is_none() => {
+                let mut version = Self::new(self.major, self.minor, self.patch, None);
+                version.pre_release = Some(format!("{}.0", pre_release));
+                version
+            }
+            _ => Self::new(self.major, self.minor, self.patch, None).with_pre_release(preid),
+        }
+    }
 }
 
 impl Display for SemVer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
         if let Some(pre_release) = &self.pre_release {
-            write!(
-                f,
-                "{}.{}.{}-{}",
-                self.major, self.minor, self.patch, pre_release
-            )
-        } else {
-            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+            write!(f, "-{}", pre_release)?;
+        }
+
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialEq for SemVer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemVer {}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemVer {
+    /// Precedence as defined by the spec, build metadata is ignored:
+    /// `1.0.0-alpha < 1.0.0-alpha.1 < 1.0.0-alpha.beta < 1.0.0-beta < 1.0.0-beta.2 < 1.0.0-beta.11
+    /// < 1.0.0-rc.1 < 1.0.0`
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                // A pre-release version has lower precedence than a normal version
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(a), Some(b)) => compare_pre_release(a, b),
+            })
+    }
+}
+
+/// Compare dot separated pre-release identifiers from left to right. Numeric identifiers are
+/// compared numerically and always have lower precedence than alphanumeric identifiers, which are
+/// compared lexically. A larger set of identifiers has a higher precedence if all the preceding
+/// identifiers are equal.
+fn compare_pre_release(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
         }
     }
 }
@@ -86,6 +221,10 @@ impl FromStr for SemVer {
             "patch" => Ok(PackageJSON::from_current_directory()?.version.new_patch()),
             "infer" => Ok(PackageJSON::from_current_directory()?.version),
             _ => {
+                let (s, build) = match s.split_once('+') {
+                    Some((s, build)) => (s, Some(build.to_owned())),
+                    None => (s, None),
+                };
                 let (s, pre_release) = match s.split_once('-') {
                     Some((s, pre_release)) => (s, Some(pre_release.to_owned())),
                     None => (s, None),
@@ -109,7 +248,10 @@ impl FromStr for SemVer {
                     }
                 };
 
-                Ok(Self::new(major, minor, patch, pre_release))
+                Ok(Self {
+                    build,
+                    ..Self::new(major, minor, patch, pre_release)
+                })
             }
         }
     }
@@ -125,6 +267,21 @@ impl<'de> Deserialize<'de> for SemVer {
     }
 }
 
+/// The kind of manifest a package is read from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestKind {
+    /// A `package.json` file
+    #[default]
+    Npm,
+
+    /// A `Cargo.toml` file
+    Cargo,
+
+    /// There is no manifest, the version is tracked in the version files of the config instead
+    None,
+}
+
+/// A package in the project, read from its `package.json` file or from its `Cargo.toml` file
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PackageJSON {
     // Meta data
@@ -132,16 +289,57 @@ pub struct PackageJSON {
     pwd: PathBuf,
     #[serde(skip)]
     is_root: bool,
+    #[serde(skip)]
+    kind: ManifestKind,
 
     // Actual PackageJSON data
     name: String,
     version: SemVer,
     workspaces: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    private: bool,
+    #[serde(rename = "publishConfig", skip_serializing_if = "Option::is_none")]
+    publish_config: Option<PublishConfig>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    dependencies: BTreeMap<String, String>,
+    #[serde(
+        rename = "devDependencies",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(
+        rename = "peerDependencies",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    peer_dependencies: BTreeMap<String, String>,
+    #[serde(
+        rename = "optionalDependencies",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    optional_dependencies: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PublishConfig {
+    registry: Option<String>,
 }
 
 impl PackageJSON {
+    /// Read the package from the `package.json` file in the directory, or from the `Cargo.toml`
+    /// file for Rust projects.
     pub fn from_directory(dir: &Path) -> Result<Self> {
         let package_json_path = dir.join("package.json");
+        if !package_json_path.exists() {
+            if dir.join("Cargo.toml").exists() {
+                return Self::from_cargo_toml(dir);
+            }
+
+            return Ok(Self::without_manifest(dir));
+        }
+
         let contents = std::fs::read_to_string(package_json_path)?;
         serde_json::from_str::<Self>(&contents)
             .map(|mut pkg| {
@@ -151,26 +349,90 @@ impl PackageJSON {
             .map_err(|e| eyre!(e))
     }
 
+    fn from_cargo_toml(dir: &Path) -> Result<Self> {
+        let manifest = CargoToml::from_directory(dir)?;
+
+        // A virtual workspace doesn't have a package, so we use the name of the directory
+        let name = match &manifest.package {
+            Some(package) => package.name.clone(),
+            None => dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("workspace")
+                .to_string(),
+        };
+
+        Ok(Self {
+            pwd: dir.to_path_buf(),
+            is_root: false,
+            kind: ManifestKind::Cargo,
+            private: manifest.is_private(),
+            dependencies: manifest.dependencies(),
+            version: manifest
+                .version(dir)
+                .unwrap_or_else(|| "0.0.0".to_string())
+                .parse()?,
+            workspaces: manifest.workspace.map(|workspace| {
+                workspace
+                    .members
+                    .into_iter()
+                    .chain(
+                        workspace
+                            .exclude
+                            .iter()
+                            .map(|exclude| format!("!{}", exclude)),
+                    )
+                    .collect()
+            }),
+            name,
+            publish_config: None,
+            ..Self::without_manifest(dir)
+        })
+    }
+
+    /// A project without a manifest, named after its directory
+    fn without_manifest(dir: &Path) -> Self {
+        Self {
+            pwd: dir.to_path_buf(),
+            is_root: false,
+            kind: ManifestKind::None,
+            name: dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("project")
+                .to_string(),
+            version: SemVer::default(),
+            workspaces: None,
+            private: false,
+            publish_config: None,
+            dependencies: BTreeMap::new(),
+            dev_dependencies: BTreeMap::new(),
+            peer_dependencies: BTreeMap::new(),
+            optional_dependencies: BTreeMap::new(),
+        }
+    }
+
     pub fn from_root(dir: &Path) -> Result<Self> {
-        let package_json_path = dir.join("package.json");
-        let contents = std::fs::read_to_string(package_json_path)?;
-        serde_json::from_str::<Self>(&contents)
-            .map(|mut pkg| {
-                pkg.pwd = dir.to_path_buf();
-                pkg
-            })
-            .map_err(|e| eyre!(e))
-            .map(|mut root| {
-                root.is_root = true;
-                root
-            })
+        Self::from_directory(dir).map(|mut root| {
+            root.is_root = true;
+            root
+        })
+    }
+
+    pub fn kind(&self) -> ManifestKind {
+        self.kind
     }
 
     pub fn display_name(&self) -> String {
+        let name = match self.private {
+            true => format!("{} {}", self.name, "(private)".italic().dimmed()),
+            false => self.name.to_owned(),
+        };
+
         if self.is_root {
-            format!("{} {}", self.name, "(root)".italic().dimmed())
+            format!("{} {}", name, "(root)".italic().dimmed())
         } else {
-            self.name.to_owned()
+            name
         }
     }
 
@@ -190,12 +452,180 @@ impl PackageJSON {
         self.is_root
     }
 
+    /// Private packages (or crates with `publish = false`) are not released by default
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    /// The registry from the `publishConfig` of the package, if any
+    pub fn registry(&self) -> Option<&str> {
+        self.publish_config
+            .as_ref()
+            .and_then(|publish_config| publish_config.registry.as_deref())
+    }
+
+    pub fn version(&self) -> &SemVer {
+        &self.version
+    }
+
     pub fn version_mut(&mut self) -> &mut SemVer {
         &mut self.version
     }
 
+    /// The version range of a dependency on another package, e.g.: `^1.2.3` or `workspace:^`.
+    /// Dev dependencies are not included, they are not part of the published package.
+    pub fn dependency_range(&self, name: &str) -> Option<&str> {
+        [
+            &self.dependencies,
+            &self.peer_dependencies,
+            &self.optional_dependencies,
+        ]
+        .iter()
+        .find_map(|dependencies| dependencies.get(name))
+        .map(|range| range.as_str())
+    }
+
+    /// Whether a release of the other package affects this package
+    pub fn depends_on(&self, name: &str) -> bool {
+        self.name != name && self.dependency_range(name).is_some()
+    }
+
+    /// The packages (of the workspace) that depend on this package
+    pub fn dependents<'a>(&self, packages: &'a [PackageJSON]) -> Vec<&'a PackageJSON> {
+        packages
+            .iter()
+            .filter(|package| package.depends_on(&self.name))
+            .collect()
+    }
+
+    /// Sort the packages so that dependencies come before the packages that depend on them, the
+    /// original order is kept otherwise. Packages in a dependency cycle keep their order as well.
+    pub fn sort_by_dependencies(packages: Vec<PackageJSON>) -> Vec<PackageJSON> {
+        let mut remaining = packages;
+        let mut sorted: Vec<PackageJSON> = Vec::with_capacity(remaining.len());
+
+        while !remaining.is_empty() {
+            let next = remaining
+                .iter()
+                .position(|package| {
+                    !remaining
+                        .iter()
+                        .any(|other| package.depends_on(other.name()))
+                })
+                .unwrap_or(0);
+
+            sorted.push(remaining.remove(next));
+        }
+
+        sorted
+    }
+
     pub fn is_monorepo(&self) -> bool {
-        self.workspaces.is_some()
+        self.workspaces().is_some()
+    }
+
+    /// The globs of the workspace packages, from the `workspaces` field, the
+    /// `pnpm-workspace.yaml` file, the `lerna.json` file or the `nx.json` file
+    fn workspaces(&self) -> Option<Vec<String>> {
+        self.workspaces
+            .clone()
+            .or_else(|| {
+                std::fs::read_to_string(self.pwd.join("pnpm-workspace.yaml"))
+                    .ok()
+                    .map(|contents| parse_pnpm_workspace(&contents))
+            })
+            .or_else(|| Lerna::from_directory(&self.pwd).and_then(|lerna| lerna.packages))
+            .or_else(|| Nx::from_directory(&self.pwd).map(|nx| nx.packages()))
+    }
+
+    /// The version that all packages share, when lerna is used in fixed mode
+    pub fn fixed_version(&self) -> Option<SemVer> {
+        Lerna::from_directory(&self.pwd)?
+            .version
+            .filter(|version| version != "independent")?
+            .parse()
+            .ok()
+    }
+
+    /// Update the shared version in the `lerna.json` file, returns the path of the file
+    pub fn set_fixed_version(&self, version: &SemVer) -> Result<PathBuf> {
+        let path = self.pwd.join("lerna.json");
+        let contents = std::fs::read_to_string(&path)?;
+        let current = self
+            .fixed_version()
+            .ok_or_else(|| eyre!("{} doesn't contain a fixed version", path.display()))?;
+
+        // Only replace the version, so that the formatting of the file is kept
+        let updated =
+            contents.replacen(&format!("\"{}\"", current), &format!("\"{}\"", version), 1);
+        std::fs::write(&path, updated)?;
+
+        Ok(path)
+    }
+
+    /// Update the version ranges of a dependency on another package of the workspace to its new
+    /// version, e.g.: `^1.2.3` or `workspace:^1.2.3`. Ranges like `workspace:^` or `*` are kept,
+    /// they don't mention a version. Returns the path of the `package.json` file when it changed.
+    pub fn update_dependency(&self, name: &str, version: &SemVer) -> Result<Option<PathBuf>> {
+        if self.kind != ManifestKind::Npm || self.name == name {
+            return Ok(None);
+        }
+
+        // Earlier releases of the same run could have changed the file already
+        let current = PackageJSON::from_directory(&self.pwd)?;
+        let path = self.pwd.join("package.json");
+        let contents = std::fs::read_to_string(&path)?;
+
+        // Only replace the ranges, so that the formatting of the file is kept
+        let mut updated = contents.clone();
+        for dependencies in [
+            &current.dependencies,
+            &current.dev_dependencies,
+            &current.peer_dependencies,
+            &current.optional_dependencies,
+        ] {
+            if let Some((range, bumped)) = dependencies
+                .get(name)
+                .and_then(|range| Some((range, bump_range(range, version)?)))
+            {
+                for separator in [": ", ":"] {
+                    updated = updated.replace(
+                        &format!("\"{}\"{}\"{}\"", name, separator, range),
+                        &format!("\"{}\"{}\"{}\"", name, separator, bumped),
+                    );
+                }
+            }
+        }
+
+        if updated == contents {
+            return Ok(None);
+        }
+
+        std::fs::write(&path, updated)?;
+        Ok(Some(path))
+    }
+
+    /// The root of the monorepo that contains the directory, together with the package that
+    /// contains it, e.g.: the monorepo and the `foo` package for `packages/foo/src`. Returns `None`
+    /// when the directory is not inside a package of a monorepo, e.g.: for the root itself.
+    pub fn find_workspace(dir: &Path) -> Result<Option<(PackageJSON, PackageJSON)>> {
+        for ancestor in dir.ancestors().skip(1) {
+            let root = match PackageJSON::from_root(ancestor) {
+                Ok(root) if root.is_monorepo() => root,
+                _ => continue,
+            };
+
+            // The most specific package wins, packages can be nested
+            let package = root
+                .packages()?
+                .into_iter()
+                .filter(|package| !package.is_root() && dir.starts_with(package.pwd()))
+                .max_by_key(|package| package.pwd().components().count());
+
+            return Ok(package.map(|package| (root, package)));
+        }
+
+        Ok(None)
     }
 
     pub fn packages(&self) -> Result<Vec<PackageJSON>> {
@@ -203,13 +633,26 @@ impl PackageJSON {
 
         let mut packages: Vec<PackageJSON> = vec![PackageJSON::from_root(base)?];
 
-        if let Some(workspaces) = &self.workspaces {
-            for workspace_glob in workspaces {
+        if let Some(workspaces) = self.workspaces() {
+            // Globs starting with a `!` exclude packages, e.g.: `!**/test/**`
+            let (excludes, includes): (Vec<_>, Vec<_>) = workspaces
+                .iter()
+                .partition(|workspace_glob| workspace_glob.starts_with('!'));
+            let excludes: Vec<Pattern> = excludes
+                .iter()
+                .map(|workspace_glob| workspace_path(base, &workspace_glob[1..]))
+                .collect::<Result<Vec<_>>>()?
+                .iter()
+                .filter_map(|path| Pattern::new(path).ok())
+                .collect();
+
+            for workspace_glob in includes {
                 packages.extend(
-                    glob(base.join(workspace_glob).to_str().unwrap())
-                        .expect("Failed to read glob pattern")
+                    glob(&workspace_path(base, workspace_glob)?)
+                        .map_err(|e| eyre!("Invalid workspace glob {}: {}", workspace_glob, e))?
                         .flatten()
                         .filter(|path| path.is_dir())
+                        .filter(|path| !excludes.iter().any(|exclude| exclude.matches_path(path)))
                         .filter_map(|path| PackageJSON::from_directory(&path).ok()),
                 )
             }
@@ -218,3 +661,298 @@ impl PackageJSON {
         Ok(packages)
     }
 }
+
+/// The glob of a workspace, relative to the root of the monorepo
+fn workspace_path(base: &Path, workspace_glob: &str) -> Result<String> {
+    let path = base.join(workspace_glob);
+    path.to_str()
+        .map(|path| path.to_string())
+        .ok_or_else(|| eyre!("The workspace path {} is not valid UTF-8", path.display()))
+}
+
+/// The relevant parts of a `lerna.json` file
+#[derive(Debug, Deserialize)]
+struct Lerna {
+    packages: Option<Vec<String>>,
+
+    /// Either a fixed version that all packages share, or "independent"
+    version: Option<String>,
+}
+
+impl Lerna {
+    fn from_directory(dir: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(dir.join("lerna.json")).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+}
+
+/// The relevant parts of a `nx.json` file
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Nx {
+    workspace_layout: Option<NxWorkspaceLayout>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NxWorkspaceLayout {
+    apps_dir: Option<String>,
+    libs_dir: Option<String>,
+}
+
+impl Nx {
+    fn from_directory(dir: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(dir.join("nx.json")).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// The package globs of the workspace, Nx uses `apps` and `libs` by default
+    fn packages(&self) -> Vec<String> {
+        let layout = self.workspace_layout.as_ref();
+
+        [
+            layout
+                .and_then(|layout| layout.apps_dir.as_deref())
+                .unwrap_or("apps"),
+            layout
+                .and_then(|layout| layout.libs_dir.as_deref())
+                .unwrap_or("libs"),
+        ]
+        .iter()
+        .map(|dir| format!("{}/*", dir.trim_end_matches('/')))
+        .collect()
+    }
+}
+
+/// Point a version range at the new version, while keeping its `workspace:` protocol and its `^`
+/// or `~` operator. Returns `None` for ranges that don't mention a version, e.g.: `*`.
+fn bump_range(range: &str, version: &SemVer) -> Option<String> {
+    let (protocol, range) = match range.strip_prefix("workspace:") {
+        Some(range) => ("workspace:", range),
+        None => ("", range),
+    };
+    let (operator, current) = match range.starts_with(['^', '~']) {
+        true => range.split_at(1),
+        false => ("", range),
+    };
+
+    current.parse::<SemVer>().ok()?;
+
+    Some(format!("{}{}{}", protocol, operator, version))
+}
+
+/// Read the package globs from a `pnpm-workspace.yaml` file, e.g.:
+///
+/// ```yaml
+/// packages:
+///   - 'packages/*'
+///   - '!**/test/**'
+/// ```
+fn parse_pnpm_workspace(contents: &str) -> Vec<String> {
+    let unquote = |value: &str| {
+        let value = value.split(" #").next().unwrap_or_default().trim();
+        value.trim_matches(|c| c == '\'' || c == '"').to_string()
+    };
+
+    let mut globs = vec![];
+    let mut in_packages = false;
+
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_packages = false;
+
+            if let Some(value) = trimmed.strip_prefix("packages:") {
+                in_packages = true;
+
+                // Inline list, e.g.: `packages: ['packages/*']`
+                if let Some(list) = value.trim().strip_prefix('[') {
+                    globs.extend(
+                        list.trim_end_matches(']')
+                            .split(',')
+                            .map(unquote)
+                            .filter(|glob| !glob.is_empty()),
+                    );
+                }
+            }
+        } else if let Some(value) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            globs.push(unquote(value));
+        }
+    }
+
+    globs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(version: &str, strategy: &str, preid: Option<&str>) -> String {
+        let mut version: SemVer = version.parse().unwrap();
+        version.change_to(strategy, preid).unwrap().to_string()
+    }
+
+    #[test]
+    fn it_should_compare_versions_by_precedence() {
+        let versions: Vec<SemVer> = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.2.0",
+            "1.10.0",
+            "2.0.0",
+        ]
+        .iter()
+        .map(|version| version.parse().unwrap())
+        .collect();
+
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+
+        assert_eq!(
+            "1.0.0+build.1".parse::<SemVer>().unwrap(),
+            "1.0.0+build.2".parse::<SemVer>().unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_read_pnpm_workspaces() {
+        assert_eq!(
+            parse_pnpm_workspace(
+                "# Packages\npackages:\n  - 'packages/*'\n  - \"apps/**\" # The apps\n  - '!**/test/**'\n\ncatalog:\n  react: ^18\n"
+            ),
+            vec!["packages/*", "apps/**", "!**/test/**"]
+        );
+        assert_eq!(
+            parse_pnpm_workspace("packages: ['packages/*', 'tools/*']\n"),
+            vec!["packages/*", "tools/*"]
+        );
+    }
+
+    #[test]
+    fn it_should_read_nx_workspaces() {
+        let nx: Nx =
+            serde_json::from_str(r#"{"workspaceLayout": {"libsDir": "packages/"}}"#).unwrap();
+        assert_eq!(nx.packages(), vec!["apps/*", "packages/*"]);
+    }
+
+    #[test]
+    fn it_should_sort_dependencies_before_their_dependents() {
+        let package = |contents: &str| serde_json::from_str::<PackageJSON>(contents).unwrap();
+        let packages = vec![
+            package(
+                r#"{"name": "app", "version": "1.0.0", "dependencies": {"ui": "workspace:^"}}"#,
+            ),
+            package(
+                r#"{"name": "ui", "version": "1.0.0", "peerDependencies": {"core": "^1.0.0"}}"#,
+            ),
+            package(r#"{"name": "docs", "version": "1.0.0", "devDependencies": {"app": "*"}}"#),
+            package(r#"{"name": "core", "version": "1.0.0"}"#),
+        ];
+
+        assert_eq!(
+            packages[3]
+                .dependents(&packages)
+                .iter()
+                .map(|package| package.name())
+                .collect::<Vec<_>>(),
+            vec!["ui"]
+        );
+        assert_eq!(
+            PackageJSON::sort_by_dependencies(packages)
+                .iter()
+                .map(|package| package.name())
+                .collect::<Vec<_>>(),
+            vec!["docs", "core", "ui", "app"]
+        );
+    }
+
+    #[test]
+    fn it_should_bump_version_ranges() {
+        let version: SemVer = "1.3.0".parse().unwrap();
+
+        assert_eq!(bump_range("^1.2.3", &version), Some("^1.3.0".to_string()));
+        assert_eq!(bump_range("~1.2.3", &version), Some("~1.3.0".to_string()));
+        assert_eq!(bump_range("1.2.3", &version), Some("1.3.0".to_string()));
+        assert_eq!(
+            bump_range("workspace:^1.2.3", &version),
+            Some("workspace:^1.3.0".to_string())
+        );
+        assert_eq!(bump_range("workspace:^", &version), None);
+        assert_eq!(bump_range("workspace:*", &version), None);
+        assert_eq!(bump_range("*", &version), None);
+        assert_eq!(bump_range(">=1.0.0 <2.0.0", &version), None);
+    }
+
+    #[test]
+    fn it_should_find_the_workspace_of_a_package_directory() {
+        let root =
+            std::env::temp_dir().join(format!("changelog-workspace-{}", uuid::Uuid::new_v4()));
+        let package = root.join("packages").join("widget");
+        std::fs::create_dir_all(package.join("src")).unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"name": "root", "version": "0.0.0", "workspaces": ["packages/*"]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            package.join("package.json"),
+            r#"{"name": "widget", "version": "1.0.0"}"#,
+        )
+        .unwrap();
+
+        let (workspace, found) = PackageJSON::find_workspace(&package.join("src"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(workspace.pwd(), root);
+        assert_eq!(found.name(), "widget");
+        assert!(PackageJSON::find_workspace(&root).unwrap().is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn it_should_keep_build_metadata() {
+        let version: SemVer = "1.2.3-beta.1+20240101.sha".parse().unwrap();
+        assert_eq!(version.to_string(), "1.2.3-beta.1+20240101.sha");
+        assert_eq!(bump("1.2.3+20240101", "patch", None), "1.2.4");
+        assert_eq!(bump("1.2.3+20240101", "infer", None), "1.2.3+20240101");
+    }
+
+    #[test]
+    fn it_should_bump_pre_releases() {
+        assert_eq!(bump("1.2.3", "premajor", Some("beta")), "2.0.0-beta.0");
+        assert_eq!(bump("1.2.3", "preminor", Some("beta")), "1.3.0-beta.0");
+        assert_eq!(bump("1.2.3", "prepatch", Some("beta")), "1.2.4-beta.0");
+        assert_eq!(bump("1.2.3", "prerelease", Some("beta")), "1.2.4-beta.0");
+        assert_eq!(
+            bump("1.2.4-beta.0", "prerelease", Some("beta")),
+            "1.2.4-beta.1"
+        );
+        assert_eq!(bump("1.2.4-beta.1", "prerelease", None), "1.2.4-beta.2");
+        assert_eq!(bump("1.2.4-beta.1", "prerelease", Some("rc")), "1.2.4-rc.0");
+        assert_eq!(bump("1.2.3", "prerelease", None), "1.2.4-0");
+        assert_eq!(bump("1.2.4-0", "prerelease", None), "1.2.4-1");
+    }
+
+    #[test]
+    fn it_should_pick_the_dist_tag_of_pre_releases() {
+        let dist_tag = |version: &str| version.parse::<SemVer>().unwrap().dist_tag();
+
+        assert_eq!(dist_tag("1.2.3"), None);
+        assert_eq!(dist_tag("1.2.4-beta.0"), Some("beta".to_string()));
+        assert_eq!(dist_tag("1.2.4-rc"), Some("rc".to_string()));
+        assert_eq!(dist_tag("1.2.4-0"), Some("next".to_string()));
+    }
+}
@@ -1,35 +1,189 @@
+use colored::*;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
 use uuid::Uuid;
 
-pub fn rich_edit(contents: Option<&str>) -> Option<String> {
-    let editor = std::env::var("EDITOR");
+/// The outcome of editing a temporary file in the `EDITOR` of the user.
+#[derive(Debug)]
+pub enum Edit {
+    /// The file was saved with changes
+    Saved(String),
 
-    if editor.is_err() {
-        return None;
-    }
+    /// The editor was closed without changing the file, which we treat as an abort
+    Unchanged,
+
+    /// The editor exited with an error, the file is kept at this path so that nothing is lost
+    Failed(PathBuf),
+}
+
+pub fn rich_edit(contents: Option<&str>) -> Option<Edit> {
+    let editor = editor_command();
+    let (program, args) = editor.split_first()?;
 
     let mut dir = std::env::temp_dir();
     let file_name = Uuid::new_v4().to_string();
     dir.push(&file_name);
-    let file_path = dir.to_str().unwrap();
-
-    std::fs::write(file_path, contents.unwrap_or("")).unwrap();
-
-    let result = match std::process::Command::new(editor.unwrap())
-        .arg(file_path)
-        .status()
-    {
-        Ok(status) => {
-            if status.success() {
-                std::fs::read_to_string(file_path).ok()
-            } else {
-                None
-            }
-        }
-        Err(_) => None,
+    let contents = contents.unwrap_or("");
+
+    std::fs::write(&dir, contents).ok()?;
+
+    let result = match spawn(program, args, &dir) {
+        Ok(status) if status.success() => match std::fs::read_to_string(&dir) {
+            Ok(data) if normalize(&data) == normalize(contents) => Some(Edit::Unchanged),
+            Ok(data) => Some(Edit::Saved(normalize(&data))),
+            Err(_) => None,
+        },
+        // Keep the file around for recovery, the editor might have crashed with unsaved changes
+        Ok(_) => return Some(Edit::Failed(dir)),
+
+        // The editor doesn't exist (e.g.: in a minimal container), ask in the terminal instead
+        Err(_) => inline_edit(contents),
     };
 
     // Cleanup
-    std::fs::remove_file(file_path).unwrap();
+    std::fs::remove_file(&dir).ok();
 
     result
 }
+
+/// Open the file in the editor and wait for it to close. On Windows, editors are often batch
+/// scripts (e.g.: `code.cmd`) which can't be spawned directly, those go through `cmd /C` instead.
+fn spawn(program: &str, args: &[String], file: &Path) -> std::io::Result<ExitStatus> {
+    let status = Command::new(program).args(args).arg(file).status();
+
+    match status {
+        Err(e) if cfg!(windows) && e.kind() == std::io::ErrorKind::NotFound => {
+            let status = Command::new("cmd")
+                .arg("/C")
+                .arg(program)
+                .args(args)
+                .arg(file)
+                .status()?;
+
+            // `cmd` reports unknown commands with exit code 9009, treat it like a missing editor
+            match status.code() {
+                Some(9009) => Err(e),
+                _ => Ok(status),
+            }
+        }
+        status => status,
+    }
+}
+
+/// Editors on Windows save with CRLF line endings and sometimes with a byte order mark, only keep
+/// the text itself.
+fn normalize(data: &str) -> String {
+    data.trim_start_matches('\u{feff}').replace("\r\n", "\n")
+}
+
+/// Ask for the lines in the terminal, until an empty line. The instructions (the comments) and
+/// the current contents are shown first, nothing typed keeps the contents unchanged.
+fn inline_edit(contents: &str) -> Option<Edit> {
+    eprintln!();
+    for line in contents.lines() {
+        match line.strip_prefix('#') {
+            Some(comment) if comment.contains("Save and close") => {}
+            Some(comment) => eprintln!("  {}", comment.trim_start().white().dimmed()),
+            None if line.trim().is_empty() => {}
+            None => eprintln!("  {} {}", "Current:".white().dimmed(), line),
+        }
+    }
+    eprintln!();
+    eprintln!("  Type the entries, one per line, and finish with an empty line:");
+
+    let lines: Vec<String> = std::io::stdin()
+        .lock()
+        .lines()
+        .map_while(|line| line.ok())
+        .take_while(|line| !line.trim().is_empty())
+        .collect();
+
+    match lines.is_empty() {
+        true => Some(Edit::Unchanged),
+        false => Some(Edit::Saved(lines.join("\n"))),
+    }
+}
+
+/// The editor to use: `VISUAL`, then `EDITOR`, then the default editor of the platform. The
+/// command can contain arguments, e.g.: `code --wait`.
+fn editor_command() -> Vec<String> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|editor| split(&editor))
+        .find(|editor| !editor.is_empty())
+        .unwrap_or_else(|| match cfg!(windows) {
+            true => vec!["notepad".to_string()],
+            false => vec!["vi".to_string()],
+        })
+}
+
+/// Split a command into its arguments like a shell would, e.g.: `"my editor" --wait` becomes
+/// `["my editor", "--wait"]`.
+fn split(command: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            // Only escape the characters that need it, so that Windows paths (e.g.:
+            // `C:\Tools\editor.exe`) keep their backslashes
+            (Some('"') | None, '\\') => {
+                let escaped = chars
+                    .clone()
+                    .next()
+                    .filter(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\'));
+                if escaped.is_some() {
+                    chars.next();
+                }
+                current
+                    .get_or_insert_with(String::new)
+                    .push(escaped.unwrap_or('\\'));
+            }
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_split_editor_commands() {
+        assert_eq!(split("vim"), vec!["vim"]);
+        assert_eq!(split("  code --wait "), vec!["code", "--wait"]);
+        assert_eq!(
+            split(r#""/Applications/Sublime Text.app/subl" -w"#),
+            vec!["/Applications/Sublime Text.app/subl", "-w"]
+        );
+        assert_eq!(split(r"my\ editor ''"), vec!["my editor", ""]);
+        assert!(split("   ").is_empty());
+        assert_eq!(
+            split(r#"C:\Tools\editor.exe "C:\Program Files\My Editor\edit.cmd""#),
+            vec![
+                r"C:\Tools\editor.exe",
+                r"C:\Program Files\My Editor\edit.cmd"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_normalize_windows_line_endings() {
+        assert_eq!(normalize("\u{feff}- One\r\n- Two\r\n"), "- One\n- Two\n");
+        assert_eq!(normalize("- One\n"), "- One\n");
+    }
+}
@@ -0,0 +1,112 @@
+/// Collapse all consecutive whitespace (including newlines) into a single space
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Split a conventional commit prefix from a title, e.g.: `feat(ui)!: Add a button` results in
+/// `Some(("feat", "Add a button"))`.
+pub fn split_conventional_prefix(text: &str) -> Option<(&str, &str)> {
+    let (prefix, rest) = text.split_once(':')?;
+    let rest = rest.strip_prefix(' ')?;
+
+    let kind = prefix.trim_end_matches('!');
+    let kind = match kind.split_once('(') {
+        Some((kind, scope)) if scope.ends_with(')') => kind,
+        Some(_) => return None,
+        None => kind,
+    };
+
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    Some((kind, rest))
+}
+
+/// Remove a conventional commit prefix (`fix:`, `feat(scope):`, ...) and uppercase the first
+/// letter of the remaining text.
+pub fn strip_conventional_prefix(text: &str) -> String {
+    match split_conventional_prefix(text) {
+        Some((_, rest)) => uppercase_first(rest),
+        None => text.to_string(),
+    }
+}
+
+pub fn uppercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Escape characters that have a special meaning in markdown, code spans are kept as-is.
+pub fn escape_markdown(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_code = false;
+
+    for c in text.chars() {
+        match c {
+            '`' => {
+                in_code = !in_code;
+                result.push(c);
+            }
+            '\\' | '*' | '_' | '[' | ']' | '<' | '>' if !in_code => {
+                result.push('\\');
+                result.push(c);
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Truncate the text to at most `max_length` characters (including the ellipsis), preferably on
+/// a word boundary.
+pub fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let truncated: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    let truncated = match truncated.rfind(' ') {
+        Some(idx) if idx > truncated.len() / 2 => &truncated[..idx],
+        _ => &truncated[..],
+    };
+
+    format!("{}…", truncated.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_strip_conventional_commit_prefixes() {
+        assert_eq!(strip_conventional_prefix("fix: a bug"), "A bug");
+        assert_eq!(strip_conventional_prefix("feat(ui)!: a button"), "A button");
+        assert_eq!(
+            strip_conventional_prefix("Note: this is not a prefix"),
+            "Note: this is not a prefix"
+        );
+        assert_eq!(strip_conventional_prefix("Fix the thing"), "Fix the thing");
+    }
+
+    #[test]
+    fn it_should_escape_markdown_outside_of_code_spans() {
+        assert_eq!(
+            escape_markdown("Support <Foo> in `a_b` and *c*"),
+            "Support \\<Foo\\> in `a_b` and \\*c\\*"
+        );
+    }
+
+    #[test]
+    fn it_should_truncate_on_word_boundaries() {
+        assert_eq!(truncate("Short", 10), "Short");
+        assert_eq!(
+            truncate("Improve the performance of the parser", 20),
+            "Improve the…"
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use changelog::changelog::Changelog;
+use changelog::config::Config;
+use changelog::diff::Entry;
+use changelog::github::{autolink::autolink, repo::Repo};
+use changelog::metadata;
+use changelog::package::SemVer;
+use color_eyre::eyre::{eyre, Result};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::path::Path;
+
+const HELP: &str = "tab switch pane · a add · e edit · m move · d delete · r release · q quit";
+
+/// A version of the changelog, with its entries grouped per section
+struct Version {
+    heading: String,
+    sections: Vec<(String, Vec<Entry>)>,
+}
+
+impl Version {
+    fn entries(&self) -> Vec<&Entry> {
+        self.sections
+            .iter()
+            .flat_map(|(_, entries)| entries.iter())
+            .collect()
+    }
+}
+
+#[derive(PartialEq)]
+enum Focus {
+    Versions,
+    Entries,
+}
+
+/// What the dashboard is doing right now, everything besides browsing is shown as a popup
+enum Mode {
+    Browse,
+
+    /// Pick a section for a new entry, or for the entry that moves
+    Pick {
+        entry: Option<Entry>,
+        selected: usize,
+    },
+
+    /// Type the text of a new entry (in the given section), a changed entry or a new version
+    Input {
+        action: Input,
+        value: String,
+    },
+
+    /// Confirm the removal of an entry
+    Delete(Entry),
+}
+
+enum Input {
+    Add(String),
+    Edit(Entry),
+    Release,
+}
+
+pub struct Dashboard<'a> {
+    pwd: &'a Path,
+    config: &'a Config,
+    changelog: Changelog,
+    versions: Vec<Version>,
+    version: ListState,
+    entry: ListState,
+    focus: Focus,
+    mode: Mode,
+    status: Option<String>,
+}
+
+impl<'a> Dashboard<'a> {
+    pub fn new(pwd: &'a Path, config: &'a Config, changelog: Changelog) -> Self {
+        let mut dashboard = Self {
+            pwd,
+            config,
+            changelog,
+            versions: vec![],
+            version: ListState::default().with_selected(Some(0)),
+            entry: ListState::default().with_selected(Some(0)),
+            focus: Focus::Versions,
+            mode: Mode::Browse,
+            status: None,
+        };
+        dashboard.reload();
+        dashboard
+    }
+
+    pub fn run(mut self) -> Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal);
+        ratatui::restore();
+
+        result
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                if !self.handle_key(key.code)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Group the entries of the changelog per version and section, the unreleased version is
+    /// always shown so that there is a place to add entries to.
+    fn reload(&mut self) {
+        let mut versions: Vec<Version> = vec![];
+
+        for entry in self.changelog.entries() {
+            if versions.last().map(|v| &v.heading) != Some(&entry.version) {
+                versions.push(Version {
+                    heading: entry.version.clone(),
+                    sections: vec![],
+                });
+            }
+            let version = versions.last_mut().unwrap();
+
+            match version.sections.last_mut() {
+                Some((section, entries)) if section == &entry.section => entries.push(entry),
+                _ => version.sections.push((entry.section.clone(), vec![entry])),
+            }
+        }
+
+        let unreleased = self.changelog.unreleased_heading(None);
+        if !versions
+            .iter()
+            .any(|version| version.heading.eq_ignore_ascii_case(&unreleased))
+        {
+            versions.insert(
+                0,
+                Version {
+                    heading: unreleased,
+                    sections: vec![],
+                },
+            );
+        }
+
+        self.versions = versions;
+
+        // Keep the selection within bounds, entries might have been removed
+        let version = self.version.selected().unwrap_or(0);
+        self.version
+            .select(Some(version.min(self.versions.len().saturating_sub(1))));
+        let entries = self.selected_version().entries().len();
+        let entry = self.entry.selected().unwrap_or(0);
+        self.entry
+            .select(Some(entry.min(entries.saturating_sub(1))));
+    }
+
+    fn selected_version(&self) -> &Version {
+        &self.versions[self.version.selected().unwrap_or(0)]
+    }
+
+    fn selected_entry(&self) -> Option<Entry> {
+        self.selected_version()
+            .entries()
+            .get(self.entry.selected()?)
+            .map(|entry| (*entry).clone())
+    }
+
+    fn is_unreleased(&self) -> bool {
+        self.selected_version()
+            .heading
+            .eq_ignore_ascii_case(&self.changelog.unreleased_heading(None))
+    }
+
+    /// Handle a key press, returns `false` when the dashboard should close
+    fn handle_key(&mut self, key: KeyCode) -> Result<bool> {
+        let mode = std::mem::replace(&mut self.mode, Mode::Browse);
+
+        match mode {
+            Mode::Browse => return self.browse(key),
+            Mode::Pick { entry, selected } => {
+                let sections = &self.config.sections;
+                match key {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.mode = Mode::Pick {
+                            entry,
+                            selected: selected.saturating_sub(1),
+                        }
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.mode = Mode::Pick {
+                            entry,
+                            selected: (selected + 1).min(sections.len().saturating_sub(1)),
+                        }
+                    }
+                    KeyCode::Enter if selected < sections.len() => {
+                        let section = sections[selected].clone();
+                        match entry {
+                            Some(entry) => {
+                                if self.changelog.move_entry(&entry, &section)? {
+                                    self.save(format!("Moved the entry to {}", section))?;
+                                }
+                            }
+                            None => {
+                                self.mode = Mode::Input {
+                                    action: Input::Add(section),
+                                    value: String::new(),
+                                }
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {}
+                    _ => self.mode = Mode::Pick { entry, selected },
+                }
+            }
+            Mode::Input { action, mut value } => match key {
+                KeyCode::Enter if !value.trim().is_empty() => {
+                    if let Err(e) = self.submit(action, value.trim()) {
+                        self.status = Some(e.to_string());
+                    }
+                }
+                KeyCode::Esc => {}
+                KeyCode::Backspace => {
+                    value.pop();
+                    self.mode = Mode::Input { action, value };
+                }
+                KeyCode::Char(c) => {
+                    value.push(c);
+                    self.mode = Mode::Input { action, value };
+                }
+                _ => self.mode = Mode::Input { action, value },
+            },
+            Mode::Delete(entry) => {
+                if let KeyCode::Char('y') = key {
+                    if self.changelog.remove_entry(&entry) {
+                        self.save("Deleted the entry".to_string())?;
+                    }
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn browse(&mut self, key: KeyCode) -> Result<bool> {
+        let entry = self.selected_entry();
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right | KeyCode::Char('h' | 'l') => {
+                self.focus = match self.focus {
+                    Focus::Versions => Focus::Entries,
+                    Focus::Entries => Focus::Versions,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Char('a') => {
+                self.version.select(Some(0));
+                self.mode = Mode::Pick {
+                    entry: None,
+                    selected: 0,
+                };
+            }
+            KeyCode::Char('e') if self.focus == Focus::Entries => {
+                if let Some(entry) = entry {
+                    self.mode = Mode::Input {
+                        value: entry.text.clone(),
+                        action: Input::Edit(entry),
+                    };
+                }
+            }
+            KeyCode::Char('m') if self.focus == Focus::Entries => {
+                if let Some(entry) = entry {
+                    self.mode = Mode::Pick {
+                        selected: self
+                            .config
+                            .section_rank(&entry.section)
+                            .min(self.config.sections.len().saturating_sub(1)),
+                        entry: Some(entry),
+                    };
+                }
+            }
+            KeyCode::Char('d') if self.focus == Focus::Entries => {
+                if let Some(entry) = entry {
+                    self.mode = Mode::Delete(entry);
+                }
+            }
+            KeyCode::Char('r') => {
+                self.version.select(Some(0));
+                self.mode = match self.is_unreleased() {
+                    true => Mode::Input {
+                        action: Input::Release,
+                        value: "patch".to_string(),
+                    },
+                    false => {
+                        self.status = Some("There is no unreleased section to release".into());
+                        Mode::Browse
+                    }
+                };
+            }
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    fn select(&mut self, offset: isize) {
+        let (state, len) = match self.focus {
+            Focus::Versions => (&mut self.version, self.versions.len()),
+            Focus::Entries => {
+                let len = self.selected_version().entries().len();
+                (&mut self.entry, len)
+            }
+        };
+
+        let selected = state.selected().unwrap_or(0) as isize + offset;
+        state.select(Some(
+            selected.clamp(0, len.saturating_sub(1) as isize) as usize
+        ));
+
+        if self.focus == Focus::Versions {
+            self.entry.select(Some(0));
+        }
+    }
+
+    fn submit(&mut self, action: Input, value: &str) -> Result<()> {
+        match action {
+            Input::Add(section) => {
+                let entry = match (
+                    self.config.autolink,
+                    Repo::from_git_repo(&self.pwd.to_path_buf()),
+                ) {
+                    (true, Ok(repo)) => autolink(value, &repo),
+                    _ => value.to_string(),
+                };
+
+                if !self.changelog.add_list_item_to_section(&section, &entry)? {
+                    return Err(eyre!("The {} section already contains this entry", section));
+                }
+                self.save(format!("Added a new entry to the {} section", section))
+            }
+            Input::Edit(entry) => {
+                if self.changelog.edit_entry(&entry, value) {
+                    self.save("Changed the entry".to_string())?;
+                }
+                Ok(())
+            }
+            Input::Release => {
+                let mut latest = self
+                    .changelog
+                    .versions(None)
+                    .first()
+                    .and_then(|version| version.parse::<SemVer>().ok())
+                    .unwrap_or_else(|| SemVer::new(0, 0, 0, None));
+                let version = latest.change_to(value, None).map_err(|e| eyre!(e))?;
+
+                self.changelog.release(&version, None)?;
+                self.reload();
+                self.status = Some(format!("Released {}", version));
+                Ok(())
+            }
+        }
+    }
+
+    /// Write the changes to disk straight away, so that quitting never loses anything
+    fn save(&mut self, status: String) -> Result<()> {
+        self.changelog.persist()?;
+        self.reload();
+        self.status = Some(status);
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [versions, entries] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(main);
+
+        let border = |focus: Focus| match self.focus == focus {
+            true => Style::new().blue(),
+            false => Style::new().dim(),
+        };
+
+        let list = List::new(self.versions.iter().map(|version| version.heading.clone()))
+            .block(
+                Block::bordered()
+                    .title(" Versions ")
+                    .border_style(border(Focus::Versions)),
+            )
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, versions, &mut self.version);
+
+        // Section headings are not selectable, so map the selected entry to its line
+        let mut lines: Vec<ListItem> = vec![];
+        let mut selected_line = None;
+        let mut idx = 0;
+        for (section, items) in &self.selected_version().sections {
+            if !section.is_empty() {
+                lines.push(ListItem::new(Line::from(section.clone()).bold().blue()));
+            }
+            for entry in items {
+                if Some(idx) == self.entry.selected() {
+                    selected_line = Some(lines.len());
+                }
+                lines.push(ListItem::new(format!(
+                    "  - {}",
+                    metadata::parse(&entry.text).0
+                )));
+                idx += 1;
+            }
+        }
+        if lines.is_empty() {
+            lines.push(ListItem::new(Line::from("  Nothing yet!").dim()));
+        }
+
+        let list = List::new(lines)
+            .block(
+                Block::bordered()
+                    .title(" Entries ")
+                    .border_style(border(Focus::Entries)),
+            )
+            .highlight_style(match self.focus {
+                Focus::Entries => Style::new().reversed(),
+                Focus::Versions => Style::new(),
+            });
+        frame.render_stateful_widget(
+            list,
+            entries,
+            &mut ListState::default().with_selected(selected_line),
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.status.clone().unwrap_or_else(|| HELP.to_string())).dim(),
+            footer,
+        );
+
+        match &self.mode {
+            Mode::Browse => {}
+            Mode::Pick { entry, selected } => {
+                let area = popup(frame.area(), 40, self.config.sections.len() as u16 + 2);
+                let list = List::new(self.config.sections.clone())
+                    .block(Block::bordered().title(match entry {
+                        Some(_) => " Move to section ",
+                        None => " Add to section ",
+                    }))
+                    .highlight_style(Style::new().reversed());
+
+                frame.render_widget(Clear, area);
+                frame.render_stateful_widget(
+                    list,
+                    area,
+                    &mut ListState::default().with_selected(Some(*selected)),
+                );
+            }
+            Mode::Input { action, value } => {
+                let area = popup(frame.area(), 80, 3);
+                let title = match action {
+                    Input::Add(section) => format!(" New entry in {} ", section),
+                    Input::Edit(_) => " Edit entry ".to_string(),
+                    Input::Release => " Release version (major, minor, patch or x.y.z) ".into(),
+                };
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(format!("{}█", value)).block(Block::bordered().title(title)),
+                    area,
+                );
+            }
+            Mode::Delete(entry) => {
+                let area = popup(frame.area(), 80, 3);
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(
+                    Paragraph::new(format!(
+                        "Delete \"{}\"? (y/n)",
+                        metadata::parse(&entry.text).0
+                    ))
+                    .block(Block::bordered().title(" Delete entry ")),
+                    area,
+                );
+            }
+        }
+    }
+}
+
+/// A centered area, `width` is a percentage of the screen and `height` is in lines
+fn popup(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(width)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    area
+}
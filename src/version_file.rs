@@ -0,0 +1,122 @@
+use crate::package::SemVer;
+use color_eyre::eyre::{eyre, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A file that contains the version of the project, which is rewritten when releasing, e.g.:
+/// `VERSION`, `Chart.yaml` or `build.gradle`.
+///
+/// Without a `pattern` or a `jsonPath`, the whole file is the version.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionFile {
+    /// The path of the file, relative to the project
+    pub path: String,
+
+    /// A regex where the first capture group is the version, e.g.: `version: (.*)`
+    pub pattern: Option<String>,
+
+    /// A dot separated path to the version in a JSON file, e.g.: `info.version`
+    pub json_path: Option<String>,
+}
+
+impl VersionFile {
+    /// Read the current version from the file
+    pub fn read(&self, dir: &Path) -> Result<String> {
+        let path = dir.join(&self.path);
+        let contents = std::fs::read_to_string(&path)?;
+
+        let (start, end) = self
+            .locate(&contents)?
+            .ok_or_else(|| eyre!("Couldn't find the version in {}", path.display()))?;
+
+        Ok(contents[start..end].to_string())
+    }
+
+    /// Rewrite the version in the file, the rest of the file is left untouched
+    pub fn write(&self, dir: &Path, version: &SemVer) -> Result<PathBuf> {
+        let path = dir.join(&self.path);
+        let contents = std::fs::read_to_string(&path)?;
+
+        let (start, end) = self
+            .locate(&contents)?
+            .ok_or_else(|| eyre!("Couldn't find the version in {}", path.display()))?;
+
+        std::fs::write(
+            &path,
+            format!("{}{}{}", &contents[..start], version, &contents[end..]),
+        )?;
+
+        Ok(path)
+    }
+
+    /// The byte range of the version in the contents of the file
+    fn locate(&self, contents: &str) -> Result<Option<(usize, usize)>> {
+        let pattern = match (&self.pattern, &self.json_path) {
+            (Some(pattern), _) => pattern.clone(),
+            (None, Some(json_path)) => json_pattern(json_path),
+            (None, None) => {
+                let start = contents.len() - contents.trim_start().len();
+                return Ok(Some((start, contents.trim_end().len().max(start))));
+            }
+        };
+
+        let regex =
+            Regex::new(&pattern).map_err(|e| eyre!("Invalid pattern for {}: {}", self.path, e))?;
+
+        Ok(regex
+            .captures(contents)
+            .and_then(|captures| captures.get(1))
+            .map(|version| (version.start(), version.end())))
+    }
+}
+
+/// A regex that matches the string value at the dot separated path in a JSON file. Every key of
+/// the path has to appear in order, which is good enough to find the version without having to
+/// re-serialize (and re-format) the whole file.
+fn json_pattern(json_path: &str) -> String {
+    let keys = json_path
+        .split('.')
+        .map(|key| format!("\"{}\"\\s*:\\s*", regex::escape(key)))
+        .collect::<Vec<_>>();
+
+    format!("(?s){}\"([^\"]*)\"", keys.join(r"\{.*?"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locate(file: VersionFile, contents: &str) -> Option<String> {
+        file.locate(contents)
+            .unwrap()
+            .map(|(start, end)| contents[start..end].to_string())
+    }
+
+    #[test]
+    fn it_should_locate_the_version_in_files() {
+        let file = |pattern: Option<&str>, json_path: Option<&str>| VersionFile {
+            path: "file".to_string(),
+            pattern: pattern.map(|pattern| pattern.to_string()),
+            json_path: json_path.map(|json_path| json_path.to_string()),
+        };
+
+        assert_eq!(locate(file(None, None), "1.2.3\n"), Some("1.2.3".into()));
+        assert_eq!(
+            locate(
+                file(Some("(?m)^version: (.*)$"), None),
+                "name: chart\nversion: 0.4.0\nappVersion: 1.0.0\n"
+            ),
+            Some("0.4.0".into())
+        );
+        assert_eq!(
+            locate(
+                file(None, Some("info.version")),
+                "{\n  \"version\": \"9.9.9\",\n  \"info\": {\n    \"title\": \"API\",\n    \"version\": \"2.0.0\"\n  }\n}\n"
+            ),
+            Some("2.0.0".into())
+        );
+        assert_eq!(locate(file(Some("version = '(.*)'"), None), "nope"), None);
+    }
+}